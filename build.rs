@@ -156,6 +156,10 @@ fn main() {
             .no_copy("ExifDataRef")
             .no_copy("ExifDatumRef")
             .no_copy("ExifValueRef")
+            .no_copy("XmpDataRef")
+            .no_copy("XmpDatumRef")
+            .no_copy("IptcDataRef")
+            .no_copy("IptcDatumRef")
             // Finish the builder and generate the bindings.
             .generate()
             // Unwrap the Result and panic on failure.
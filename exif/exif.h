@@ -25,6 +25,22 @@ typedef struct ExifDatumRef ExifDatumRef;
 typedef struct ExifValueRef ExifValueRef;
 /// <div rustbindgen opaque></div>
 typedef struct ExifDataMutItor ExifDataMutItor;
+/// <div rustbindgen opaque></div>
+typedef struct XmpData XmpData;
+/// <div rustbindgen opaque></div>
+typedef struct XmpDataRef XmpDataRef;
+/// <div rustbindgen opaque></div>
+typedef struct XmpDataItor XmpDataItor;
+/// <div rustbindgen opaque></div>
+typedef struct XmpDatumRef XmpDatumRef;
+/// <div rustbindgen opaque></div>
+typedef struct IptcData IptcData;
+/// <div rustbindgen opaque></div>
+typedef struct IptcDataRef IptcDataRef;
+/// <div rustbindgen opaque></div>
+typedef struct IptcDataItor IptcDataItor;
+/// <div rustbindgen opaque></div>
+typedef struct IptcDatumRef IptcDatumRef;
 #if defined _WIN32 && defined WIN32_DLL
 #if BUILD_DLL
 #define EXIF_API __declspec(dllexport)
@@ -69,6 +85,7 @@ EXIF_API ExifValue* exif_value_ref_clone(ExifValueRef* value);
 EXIF_API ExifData* exif_data_new();
 EXIF_API int exif_data_ref_add(ExifDataRef* d, ExifKey* key, ExifValueRef* value);
 EXIF_API int exif_data_ref_clear(ExifDataRef* d);
+EXIF_API long exif_data_ref_remove(ExifDataRef* d, ExifKey* key);
 EXIF_API ExifDataRef* exif_data_get_ref(ExifData* d);
 EXIF_API ExifData* exif_data_ref_clone(ExifDataRef* d);
 EXIF_API int exif_data_ref_is_empty(ExifDataRef* d);
@@ -88,6 +105,36 @@ EXIF_API void exif_free_value(ExifValue* value);
 EXIF_API void exif_free_data(ExifData* d);
 EXIF_API void exif_free_data_itor(ExifDataItor* itor);
 EXIF_API void exif_free_data_mutitor(ExifDataMutItor* itor);
+EXIF_API XmpDataRef* exif_image_get_xmp_data(ExifImage* image);
+EXIF_API int exif_image_set_xmp_data(ExifImage* image, XmpData* data);
+EXIF_API XmpData* exif_xmp_data_new();
+EXIF_API XmpDataRef* exif_xmp_data_get_ref(XmpData* d);
+EXIF_API XmpData* exif_xmp_data_ref_clone(XmpDataRef* d);
+EXIF_API int exif_xmp_data_ref_add(XmpDataRef* d, const char* key, ExifValueRef* value);
+EXIF_API long exif_xmp_data_ref_remove(XmpDataRef* d, const char* key);
+EXIF_API int exif_xmp_data_ref_clear(XmpDataRef* d);
+EXIF_API long exif_xmp_data_ref_get_count(XmpDataRef* d);
+EXIF_API XmpDataItor* exif_xmp_data_ref_iter(XmpDataRef* d);
+EXIF_API XmpDatumRef* exif_xmp_data_itor_next(XmpDataItor* itor);
+EXIF_API char* exif_xmp_datum_key(XmpDatumRef* d);
+EXIF_API ExifValueRef* exif_xmp_datum_value(XmpDatumRef* d);
+EXIF_API void exif_free_xmp_data(XmpData* d);
+EXIF_API void exif_free_xmp_data_itor(XmpDataItor* itor);
+EXIF_API IptcDataRef* exif_image_get_iptc_data(ExifImage* image);
+EXIF_API int exif_image_set_iptc_data(ExifImage* image, IptcData* data);
+EXIF_API IptcData* exif_iptc_data_new();
+EXIF_API IptcDataRef* exif_iptc_data_get_ref(IptcData* d);
+EXIF_API IptcData* exif_iptc_data_ref_clone(IptcDataRef* d);
+EXIF_API int exif_iptc_data_ref_add(IptcDataRef* d, const char* key, ExifValueRef* value);
+EXIF_API long exif_iptc_data_ref_remove(IptcDataRef* d, const char* key);
+EXIF_API int exif_iptc_data_ref_clear(IptcDataRef* d);
+EXIF_API long exif_iptc_data_ref_get_count(IptcDataRef* d);
+EXIF_API IptcDataItor* exif_iptc_data_ref_iter(IptcDataRef* d);
+EXIF_API IptcDatumRef* exif_iptc_data_itor_next(IptcDataItor* itor);
+EXIF_API char* exif_iptc_datum_key(IptcDatumRef* d);
+EXIF_API ExifValueRef* exif_iptc_datum_value(IptcDatumRef* d);
+EXIF_API void exif_free_iptc_data(IptcData* d);
+EXIF_API void exif_free_iptc_data_itor(IptcDataItor* itor);
 #ifdef __cplusplus
 }
 #endif
@@ -298,6 +298,19 @@ int exif_data_ref_clear(ExifDataRef* d) {
     return 1;
 }
 
+long exif_data_ref_remove(ExifDataRef* d, ExifKey* key) {
+    if (!d || !key || !key->key) return -1;
+    auto data = (Exiv2::ExifData*)d;
+    long count = 0;
+    auto i = data->findKey(*key->key);
+    while (i != data->end()) {
+        data->erase(i);
+        count++;
+        i = data->findKey(*key->key);
+    }
+    return count;
+}
+
 ExifDataRef* exif_data_get_ref(ExifData* d) {
     if (!d) return nullptr;
     return (ExifDataRef*)&d->data;
@@ -439,3 +452,267 @@ void exif_datum_set_value(ExifDatumRef* d, ExifValueRef* v) {
     auto va = (Exiv2::Value*)v;
     da->setValue(va);
 }
+
+XmpDataRef* exif_image_get_xmp_data(ExifImage* image) {
+    if (!image || !image->image) return nullptr;
+    return (XmpDataRef*)&image->image->xmpData();
+}
+
+int exif_image_set_xmp_data(ExifImage* image, XmpData* data) {
+    if (!image || !image->image || !data) return 1;
+    try {
+        image->image->setXmpData(data->data);
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return 1;
+    }
+    return 0;
+}
+
+XmpData* exif_xmp_data_new() {
+    return new XmpData;
+}
+
+XmpDataRef* exif_xmp_data_get_ref(XmpData* d) {
+    if (!d) return nullptr;
+    return (XmpDataRef*)&d->data;
+}
+
+XmpData* exif_xmp_data_ref_clone(XmpDataRef* d) {
+    if (!d) return nullptr;
+    auto n = new XmpData;
+    if (!n) return nullptr;
+    try {
+        auto data = (Exiv2::XmpData*)d;
+        for (auto i = data->begin(); i != data->end(); ++i) {
+            n->data.add(*i);
+        }
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        delete n;
+        return nullptr;
+    }
+    return n;
+}
+
+int exif_xmp_data_ref_add(XmpDataRef* d, const char* key, ExifValueRef* value) {
+    if (!d || !key || !value) return 0;
+    auto data = (Exiv2::XmpData*)d;
+    auto v = (Exiv2::Value*)value;
+    try {
+        return data->add(Exiv2::XmpKey(key), v) == 0 ? 1 : 0;
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return 0;
+    }
+}
+
+long exif_xmp_data_ref_remove(XmpDataRef* d, const char* key) {
+    if (!d || !key) return -1;
+    auto data = (Exiv2::XmpData*)d;
+    long count = 0;
+    try {
+        Exiv2::XmpKey k(key);
+        auto i = data->findKey(k);
+        while (i != data->end()) {
+            data->erase(i);
+            count++;
+            i = data->findKey(k);
+        }
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return -1;
+    }
+    return count;
+}
+
+int exif_xmp_data_ref_clear(XmpDataRef* d) {
+    if (!d) return 0;
+    auto data = (Exiv2::XmpData*)d;
+    data->clear();
+    return 1;
+}
+
+long exif_xmp_data_ref_get_count(XmpDataRef* d) {
+    if (!d) return -1;
+    auto data = (Exiv2::XmpData*)d;
+    return data->count();
+}
+
+XmpDataItor* exif_xmp_data_ref_iter(XmpDataRef* d) {
+    if (!d) return nullptr;
+    auto re = new XmpDataItor;
+    re->ref = (Exiv2::XmpData*)d;
+    re->itor = re->ref->begin();
+    re->end = re->ref->end();
+    return re;
+}
+
+XmpDatumRef* exif_xmp_data_itor_next(XmpDataItor* itor) {
+    if (!itor->ref) return nullptr;
+    if (itor->itor == itor->end) return nullptr;
+    auto& data = (*itor->itor);
+    itor->itor++;
+    return (XmpDatumRef*)&data;
+}
+
+char* exif_xmp_datum_key(XmpDatumRef* d) {
+    if (!d) return nullptr;
+    auto data = (Exiv2::Xmpdatum*)d;
+    char* re = nullptr;
+    if (!string2char(data->key(), re)) return nullptr;
+    return re;
+}
+
+ExifValueRef* exif_xmp_datum_value(XmpDatumRef* d) {
+    if (!d) return nullptr;
+    auto data = (Exiv2::Xmpdatum*)d;
+    try {
+        return (ExifValueRef*)&data->value();
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return nullptr;
+    }
+}
+
+void exif_free_xmp_data(XmpData* d) {
+    if (!d) return;
+    delete d;
+}
+
+void exif_free_xmp_data_itor(XmpDataItor* itor) {
+    if (!itor) return;
+    delete itor;
+}
+
+IptcDataRef* exif_image_get_iptc_data(ExifImage* image) {
+    if (!image || !image->image) return nullptr;
+    return (IptcDataRef*)&image->image->iptcData();
+}
+
+int exif_image_set_iptc_data(ExifImage* image, IptcData* data) {
+    if (!image || !image->image || !data) return 1;
+    try {
+        image->image->setIptcData(data->data);
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return 1;
+    }
+    return 0;
+}
+
+IptcData* exif_iptc_data_new() {
+    return new IptcData;
+}
+
+IptcDataRef* exif_iptc_data_get_ref(IptcData* d) {
+    if (!d) return nullptr;
+    return (IptcDataRef*)&d->data;
+}
+
+IptcData* exif_iptc_data_ref_clone(IptcDataRef* d) {
+    if (!d) return nullptr;
+    auto n = new IptcData;
+    if (!n) return nullptr;
+    try {
+        auto data = (Exiv2::IptcData*)d;
+        for (auto i = data->begin(); i != data->end(); ++i) {
+            n->data.add(*i);
+        }
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        delete n;
+        return nullptr;
+    }
+    return n;
+}
+
+int exif_iptc_data_ref_add(IptcDataRef* d, const char* key, ExifValueRef* value) {
+    if (!d || !key || !value) return 0;
+    auto data = (Exiv2::IptcData*)d;
+    auto v = (Exiv2::Value*)value;
+    try {
+        return data->add(Exiv2::IptcKey(key), v) == 0 ? 1 : 0;
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return 0;
+    }
+}
+
+long exif_iptc_data_ref_remove(IptcDataRef* d, const char* key) {
+    if (!d || !key) return -1;
+    auto data = (Exiv2::IptcData*)d;
+    long count = 0;
+    try {
+        Exiv2::IptcKey k(key);
+        auto i = data->findKey(k);
+        while (i != data->end()) {
+            data->erase(i);
+            count++;
+            i = data->findKey(k);
+        }
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return -1;
+    }
+    return count;
+}
+
+int exif_iptc_data_ref_clear(IptcDataRef* d) {
+    if (!d) return 0;
+    auto data = (Exiv2::IptcData*)d;
+    data->clear();
+    return 1;
+}
+
+long exif_iptc_data_ref_get_count(IptcDataRef* d) {
+    if (!d) return -1;
+    auto data = (Exiv2::IptcData*)d;
+    return data->count();
+}
+
+IptcDataItor* exif_iptc_data_ref_iter(IptcDataRef* d) {
+    if (!d) return nullptr;
+    auto re = new IptcDataItor;
+    re->ref = (Exiv2::IptcData*)d;
+    re->itor = re->ref->begin();
+    re->end = re->ref->end();
+    return re;
+}
+
+IptcDatumRef* exif_iptc_data_itor_next(IptcDataItor* itor) {
+    if (!itor->ref) return nullptr;
+    if (itor->itor == itor->end) return nullptr;
+    auto& data = (*itor->itor);
+    itor->itor++;
+    return (IptcDatumRef*)&data;
+}
+
+char* exif_iptc_datum_key(IptcDatumRef* d) {
+    if (!d) return nullptr;
+    auto data = (Exiv2::Iptcdatum*)d;
+    char* re = nullptr;
+    if (!string2char(data->key(), re)) return nullptr;
+    return re;
+}
+
+ExifValueRef* exif_iptc_datum_value(IptcDatumRef* d) {
+    if (!d) return nullptr;
+    auto data = (Exiv2::Iptcdatum*)d;
+    try {
+        return (ExifValueRef*)&data->value();
+    } catch (std::exception& e) {
+        printf("%s\n", e.what());
+        return nullptr;
+    }
+}
+
+void exif_free_iptc_data(IptcData* d) {
+    if (!d) return;
+    delete d;
+}
+
+void exif_free_iptc_data_itor(IptcDataItor* itor) {
+    if (!itor) return;
+    delete itor;
+}
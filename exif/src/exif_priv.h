@@ -27,4 +27,20 @@ typedef struct ExifDataMutItor {
     Exiv2::ExifMetadata::iterator itor;
     Exiv2::ExifMetadata::iterator end;
 } ExifDataMutItor;
+typedef struct XmpData {
+    Exiv2::XmpData data;
+} XmpData;
+typedef struct XmpDataItor {
+    Exiv2::XmpData* ref;
+    Exiv2::XmpMetadata::const_iterator itor;
+    Exiv2::XmpMetadata::const_iterator end;
+} XmpDataItor;
+typedef struct IptcData {
+    Exiv2::IptcData data;
+} IptcData;
+typedef struct IptcDataItor {
+    Exiv2::IptcData* ref;
+    Exiv2::IptcMetadata::const_iterator itor;
+    Exiv2::IptcMetadata::const_iterator end;
+} IptcDataItor;
 #endif
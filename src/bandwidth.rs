@@ -0,0 +1,137 @@
+use crate::ext::rw_lock::GetRwLock;
+use crate::opthelper::get_helper;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    #[doc(hidden)]
+    static ref DOWNLOAD_LIMITER: BandwidthLimiter = BandwidthLimiter::new(BandwidthLimitType::Download);
+    #[cfg(feature = "server")]
+    #[doc(hidden)]
+    static ref PROXY_LIMITER: BandwidthLimiter = BandwidthLimiter::new(BandwidthLimitType::Proxy);
+    #[doc(hidden)]
+    static ref TELEGRAM_LIMITER: BandwidthLimiter = BandwidthLimiter::new(BandwidthLimitType::Telegram);
+}
+
+/// The types of traffic which have their own bandwidth limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BandwidthLimitType {
+    /// Downloading files
+    Download,
+    #[cfg(feature = "server")]
+    /// Sending data through the pixiv proxy of the server
+    Proxy,
+    /// Uploading files to Telegram
+    Telegram,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    /// The bytes which can be transferred now.
+    /// Negative if the bytes are borrowed from the future.
+    tokens: f64,
+    /// The time when the tokens were updated
+    last: Instant,
+    /// The rate used last time. 0 means unlimited.
+    rate: u64,
+}
+
+/// A token bucket shared by all tasks and threads.
+///
+/// The bucket holds at most one second of data. Every transfer takes its
+/// size from the bucket first and then waits until the debt is paid off,
+/// so the total speed of all callers stays under the limit.
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+    typ: BandwidthLimitType,
+    bucket: RwLock<TokenBucket>,
+}
+
+impl BandwidthLimiter {
+    fn new(typ: BandwidthLimitType) -> Self {
+        Self {
+            typ,
+            bucket: RwLock::new(TokenBucket {
+                tokens: 0.0,
+                last: Instant::now(),
+                rate: 0,
+            }),
+        }
+    }
+
+    /// Take bytes from the bucket. Returns the time to wait.
+    /// * `rate` - The limit in bytes per second. [None] if unlimited.
+    /// * `size` - The size of the data
+    /// * `now` - Current time
+    fn reserve(&self, rate: Option<u64>, size: u64, now: Instant) -> Duration {
+        let mut bucket = self.bucket.get_mut();
+        let rate = match rate {
+            Some(rate) => rate,
+            None => {
+                bucket.rate = 0;
+                return Duration::ZERO;
+            }
+        };
+        let capacity = rate as f64;
+        if bucket.rate == 0 {
+            bucket.tokens = capacity;
+        } else {
+            let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * capacity).min(capacity);
+        }
+        bucket.rate = rate;
+        bucket.last = now;
+        bucket.tokens -= size as f64;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / capacity)
+        }
+    }
+
+    /// Take bytes from the bucket with the current limit. Returns the time
+    /// to wait before transferring more data.
+    /// * `size` - The size of the data
+    pub fn take(&self, size: u64) -> Duration {
+        self.reserve(get_helper().bandwidth_limit(self.typ), size, Instant::now())
+    }
+
+    /// Take bytes from the bucket and wait if the limit is exceeded.
+    /// * `size` - The size of the data
+    pub async fn acquire(&self, size: u64) {
+        let wait = self.take(size);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Returns the global bandwidth limiter.
+/// * `typ` - The type of the traffic
+pub fn get_limiter(typ: BandwidthLimitType) -> &'static BandwidthLimiter {
+    match typ {
+        BandwidthLimitType::Download => &DOWNLOAD_LIMITER,
+        #[cfg(feature = "server")]
+        BandwidthLimitType::Proxy => &PROXY_LIMITER,
+        BandwidthLimitType::Telegram => &TELEGRAM_LIMITER,
+    }
+}
+
+#[test]
+fn test_bandwidth_limiter() {
+    let l = BandwidthLimiter::new(BandwidthLimitType::Download);
+    let now = Instant::now();
+    assert_eq!(l.reserve(None, 1 << 30, now), Duration::ZERO);
+    // A full bucket allows one second of data at once.
+    assert_eq!(l.reserve(Some(1000), 1000, now), Duration::ZERO);
+    assert_eq!(l.reserve(Some(1000), 500, now), Duration::from_millis(500));
+    assert_eq!(l.reserve(Some(1000), 500, now), Duration::from_secs(1));
+    let now = now + Duration::from_secs(1);
+    assert_eq!(l.reserve(Some(1000), 0, now), Duration::ZERO);
+    // The bucket never holds more than one second of data.
+    let now = now + Duration::from_secs(10);
+    assert_eq!(l.reserve(Some(1000), 1500, now), Duration::from_millis(500));
+    // Unlimited resets the bucket.
+    assert_eq!(l.reserve(None, 1500, now), Duration::ZERO);
+    assert_eq!(l.reserve(Some(2000), 2000, now), Duration::ZERO);
+}
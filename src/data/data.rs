@@ -1,13 +1,19 @@
-#[cfg(feature = "exif")]
-use super::exif::ExifDataSource;
+use super::exif_data_source::ExifDataSource;
 use crate::gettext;
 use crate::opt::author_name_filter::AuthorFiler;
+use crate::opt::image_size::PixivImageSize;
+use crate::opt::ranking::{PixivRankingContent, PixivRankingMode};
+use crate::opt::work_type::PixivWorkType;
 use crate::opthelper::get_helper;
 use crate::pixiv_link::PixivID;
 use crate::pixiv_link::ToPixivID;
 use crate::pixivapp::illust::PixivAppIllust;
+use crate::pixivapp::novel::PixivAppNovel;
+use crate::utils::parse_pixiv_id;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use int_enum::IntEnum;
 use json::JsonValue;
+use std::str::FromStr;
 use xml::unescape;
 
 /// The type of the artwork
@@ -26,6 +32,39 @@ impl PixivAiType {
     }
 }
 
+/// The position of an artwork in a manga series
+#[derive(Clone, Debug)]
+pub struct PixivSeriesChapter {
+    /// Series ID
+    pub series_id: u64,
+    /// The title of the series
+    pub series_title: String,
+    /// Chapter number, starts from 1
+    pub order: u64,
+    /// Total chapter count of the series
+    pub total: u64,
+}
+
+/// The position of an artwork in pixiv ranking
+#[derive(Clone, Debug)]
+pub struct PixivRankingEntry {
+    pub mode: PixivRankingMode,
+    pub content: PixivRankingContent,
+    /// The date of the ranking. [None] if unknown.
+    pub date: Option<NaiveDate>,
+    /// Rank, starts from 1
+    pub rank: u64,
+}
+
+/// Where the artwork is found when downloading
+#[derive(Clone, Debug, Default)]
+pub struct PixivArtworkContext {
+    /// The position in manga series
+    pub series: Option<PixivSeriesChapter>,
+    /// The position in ranking
+    pub ranking: Option<PixivRankingEntry>,
+}
+
 /// Pixiv's basic data
 pub struct PixivData {
     /// ID
@@ -34,11 +73,45 @@ pub struct PixivData {
     pub title: Option<String>,
     /// The author
     pub author: Option<String>,
+    /// The author's user id
+    pub author_id: Option<u64>,
+    /// The author's account name (pixiv ID)
+    pub author_account: Option<String>,
+    /// The upload date
+    pub upload_date: Option<DateTime<FixedOffset>>,
+    /// The date when the images were updated. [None] if never updated or unknown.
+    pub update_date: Option<DateTime<FixedOffset>>,
     pub description: Option<String>,
+    /// The type of the artwork
+    pub work_type: Option<PixivWorkType>,
+    /// The count of pages
+    pub page_count: Option<u64>,
+    /// The width of the first page
+    pub width: Option<u64>,
+    /// The height of the first page
+    pub height: Option<u64>,
     /// Tags (Original, translated)
     pub tags: Option<Vec<(String, Option<String>)>>,
     /// AI type
     pub ai_type: Option<PixivAiType>,
+    /// Age restriction. 0: All ages, 1: R-18, 2: R-18G
+    pub x_restrict: Option<u64>,
+    /// The count of bookmarks
+    pub bookmark_count: Option<u64>,
+    /// The count of views
+    pub view_count: Option<u64>,
+    /// The count of comments
+    pub comment_count: Option<u64>,
+    /// The links of original images of all pages
+    pub original_urls: Option<Vec<String>>,
+    /// The position in manga series
+    pub series: Option<PixivSeriesChapter>,
+    /// The position in ranking
+    pub ranking: Option<PixivRankingEntry>,
+    /// The size variant of the downloaded pictures
+    pub image_size: Option<PixivImageSize>,
+    /// The downloaded pages, starts from 0. [None] if all pages are downloaded.
+    pub pages: Option<Vec<u16>>,
 }
 
 impl PixivData {
@@ -51,12 +124,35 @@ impl PixivData {
             id: i.unwrap(),
             title: None,
             author: None,
+            author_id: None,
+            author_account: None,
+            upload_date: None,
+            update_date: None,
             description: None,
+            work_type: None,
+            page_count: None,
+            width: None,
+            height: None,
             tags: None,
             ai_type: None,
+            x_restrict: None,
+            bookmark_count: None,
+            view_count: None,
+            comment_count: None,
+            original_urls: None,
+            series: None,
+            ranking: None,
+            image_size: None,
+            pages: None,
         })
     }
 
+    /// Set the data which depends on where the artwork is found.
+    pub fn set_context(&mut self, ctx: PixivArtworkContext) {
+        self.series = ctx.series;
+        self.ranking = ctx.ranking;
+    }
+
     /// Read data from [PixivAppIllust].
     pub fn from_app_illust(&mut self, illust: &PixivAppIllust) {
         self.title = illust.title().map(|s| s.to_owned());
@@ -66,7 +162,16 @@ impl PixivData {
                 Some(l) => l.filter(s),
                 None => s.to_owned(),
             });
+        self.author_id = illust.user_id();
+        self.author_account = illust.user_account().map(|s| s.to_owned());
+        self.upload_date = illust
+            .create_date()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok());
         self.description = illust.caption().map(|s| s.to_owned());
+        self.work_type = illust.typ().and_then(|t| PixivWorkType::from_str(t).ok());
+        self.page_count = illust.page_count();
+        self.width = illust.width();
+        self.height = illust.height();
         let mut tags = Vec::new();
         for i in illust.tags() {
             if let Some(name) = i.name() {
@@ -74,6 +179,21 @@ impl PixivData {
             }
         }
         self.tags.replace(tags);
+        self.x_restrict = illust.x_restrict();
+        self.bookmark_count = illust.total_bookmarks();
+        self.view_count = illust.total_view();
+        self.comment_count = illust.total_comments();
+        let meta_pages = illust.meta_pages();
+        let urls: Option<Vec<String>> = if meta_pages.is_empty() {
+            illust.original_image_url().map(|s| vec![s.to_owned()])
+        } else {
+            meta_pages
+                .iter()
+                .map(|p| p.original().map(|s| s.to_owned()))
+                .collect()
+        };
+        self.original_urls = urls;
+        self.set_update_date_from_original_urls();
         self.ai_type = match illust.illust_ai_type() {
             Some(t) => match PixivAiType::from_int(t as u8) {
                 Ok(t) => Some(t),
@@ -83,6 +203,55 @@ impl PixivData {
         };
     }
 
+    /// Read data from [PixivAppNovel].
+    pub fn from_app_novel(&mut self, novel: &PixivAppNovel) {
+        self.title = novel.title().map(|s| s.to_owned());
+        self.author = novel
+            .user_name()
+            .map(|s| match get_helper().author_name_filters() {
+                Some(l) => l.filter(s),
+                None => s.to_owned(),
+            });
+        self.author_id = novel.user_id();
+        self.author_account = novel.user_account().map(|s| s.to_owned());
+        self.upload_date = novel
+            .create_date()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok());
+        self.description = novel.caption().map(|s| s.to_owned());
+        self.page_count = novel.page_count();
+        let mut tags = Vec::new();
+        for i in novel.tags() {
+            if let Some(name) = i.name() {
+                tags.push((name.to_owned(), i.translated_name().map(|s| s.to_owned())));
+            }
+        }
+        self.tags.replace(tags);
+        self.x_restrict = novel.x_restrict();
+        self.bookmark_count = novel.total_bookmarks();
+        self.view_count = novel.total_view();
+        self.comment_count = novel.total_comments();
+        self.ai_type = match novel.novel_ai_type() {
+            Some(t) => match PixivAiType::from_int(t as u8) {
+                Ok(t) => Some(t),
+                Err(_) => None,
+            },
+            None => None,
+        };
+    }
+
+    /// Read data from JSON object.
+    /// The object is from `https://www.pixiv.net/ajax/novel/<id>`
+    /// * `value` - The JSON object
+    /// * `allow_overwrite` - Allow overwrite the data existing.
+    pub fn from_web_novel_ajax_data(&mut self, value: &JsonValue, allow_overwrite: bool) {
+        if self.title.is_none() || allow_overwrite {
+            if let Some(title) = value["title"].as_str() {
+                self.title = Some(String::from(title));
+            }
+        }
+        self.from_web_page_ajax_data(value, allow_overwrite)
+    }
+
     /// Read data from JSON object.
     /// The object is from `https://www.pixiv.net/artworks/<id>`
     /// * `value` - The JSON object
@@ -116,6 +285,50 @@ impl PixivData {
                 }
             }
         }
+        if self.author_id.is_none() || allow_overwrite {
+            if let Some(uid) = parse_pixiv_id(&value["userId"]) {
+                self.author_id.replace(uid);
+            }
+        }
+        if self.author_account.is_none() || allow_overwrite {
+            if let Some(account) = value["userAccount"].as_str() {
+                self.author_account.replace(account.to_owned());
+            }
+        }
+        // `createDate` is the same as `create_date` in app API.
+        // `uploadDate` changes when the images are replaced.
+        if self.upload_date.is_none() || allow_overwrite {
+            if let Some(date) = value["createDate"]
+                .as_str()
+                .or_else(|| value["uploadDate"].as_str())
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            {
+                self.upload_date.replace(date);
+            }
+        }
+        if self.work_type.is_none() || allow_overwrite {
+            if let Some(t) = value["illustType"]
+                .as_i64()
+                .and_then(PixivWorkType::from_illust_type)
+            {
+                self.work_type.replace(t);
+            }
+        }
+        if self.page_count.is_none() || allow_overwrite {
+            if let Some(count) = value["pageCount"].as_u64() {
+                self.page_count.replace(count);
+            }
+        }
+        if self.width.is_none() || allow_overwrite {
+            if let Some(width) = value["width"].as_u64() {
+                self.width.replace(width);
+            }
+        }
+        if self.height.is_none() || allow_overwrite {
+            if let Some(height) = value["height"].as_u64() {
+                self.height.replace(height);
+            }
+        }
         if self.description.is_none() || allow_overwrite {
             let mut description = value["description"].as_str();
             if description.is_none() {
@@ -146,6 +359,36 @@ impl PixivData {
             }
         }
         self.tags.replace(tags);
+        if self.x_restrict.is_none() || allow_overwrite {
+            if let Some(x_restrict) = value["xRestrict"].as_u64() {
+                self.x_restrict.replace(x_restrict);
+            }
+        }
+        if self.bookmark_count.is_none() || allow_overwrite {
+            if let Some(count) = value["bookmarkCount"].as_u64() {
+                self.bookmark_count.replace(count);
+            }
+        }
+        if self.view_count.is_none() || allow_overwrite {
+            if let Some(count) = value["viewCount"].as_u64() {
+                self.view_count.replace(count);
+            }
+        }
+        if self.comment_count.is_none() || allow_overwrite {
+            if let Some(count) = value["commentCount"].as_u64() {
+                self.comment_count.replace(count);
+            }
+        }
+        // Only the first page is included. Use [Self::from_web_illust_pages]
+        // for artworks with multiple pages.
+        if self.original_urls.is_none() || allow_overwrite {
+            if let Some(url) = value["urls"]["original"].as_str() {
+                self.original_urls.replace(vec![url.to_owned()]);
+            }
+        }
+        if self.update_date.is_none() || allow_overwrite {
+            self.set_update_date_from_original_urls();
+        }
         if self.ai_type.is_none() {
             let ai_type = value["aiType"].as_u8();
             match ai_type {
@@ -159,22 +402,112 @@ impl PixivData {
             }
         }
     }
+
+    /// Set [Self::update_date] from the time in the links of original images.
+    /// The time in the links changes when the images are replaced, and it is
+    /// only available in web API as `uploadDate`. Both APIs use the links, so the
+    /// results are the same.
+    fn set_update_date_from_original_urls(&mut self) {
+        let date = self
+            .original_urls
+            .as_ref()
+            .and_then(|urls| urls.first())
+            .and_then(|url| get_pximg_date(url));
+        self.update_date = match (self.upload_date, date) {
+            (Some(upload_date), Some(date)) if upload_date != date => Some(date),
+            _ => None,
+        };
+    }
+
+    /// Read the links of original images from JSON object.
+    /// The object is from `https://www.pixiv.net/ajax/illust/<id>/pages`
+    /// * `value` - The JSON object
+    pub fn from_web_illust_pages(&mut self, value: &JsonValue) {
+        let urls: Option<Vec<String>> = value
+            .members()
+            .map(|p| p["urls"]["original"].as_str().map(|s| s.to_owned()))
+            .collect();
+        if let Some(urls) = urls {
+            if !urls.is_empty() {
+                self.original_urls.replace(urls);
+            }
+        }
+    }
+}
+
+/// Returns the time in the link of a pixiv image. The time is in JST.
+/// For example: `https://i.pximg.net/img-original/img/2021/07/05/00/00/09/91055644_p0.jpg`
+fn get_pximg_date(url: &str) -> Option<DateTime<FixedOffset>> {
+    let (_, path) = url.split_once("/img/")?;
+    let parts: Vec<_> = path.splitn(7, '/').collect();
+    if parts.len() < 7 {
+        return None;
+    }
+    DateTime::parse_from_rfc3339(&format!(
+        "{}-{}-{}T{}:{}:{}+09:00",
+        parts[0], parts[1], parts[2], parts[3], parts[4], parts[5]
+    ))
+    .ok()
 }
 
-#[cfg(feature = "exif")]
 impl ExifDataSource for PixivData {
+    fn image_ai_generated(&self) -> Option<bool> {
+        self.ai_type.map(|t| t.is_ai())
+    }
+
     fn image_author(&self) -> Option<String> {
         self.author.clone()
     }
 
+    fn image_author_id(&self) -> Option<String> {
+        self.author_id.map(|id| id.to_string())
+    }
+
+    fn image_author_url(&self) -> Option<String> {
+        self.author_id
+            .map(|id| format!("https://www.pixiv.net/users/{}", id))
+    }
+
     fn image_comment(&self) -> Option<String> {
         self.description.clone()
     }
 
+    fn image_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.upload_date.clone()
+    }
+
+    fn image_hierarchical_tags(&self) -> Option<Vec<String>> {
+        let tags = self.tags.as_ref()?;
+        let mut list = Vec::new();
+        for (ori, translated) in tags {
+            let tag = format!("pixiv|{}", ori.replace('|', "_"));
+            let child = translated
+                .as_ref()
+                .map(|t| format!("{}|{}", tag, t.replace('|', "_")));
+            list.push(tag);
+            if let Some(child) = child {
+                list.push(child);
+            }
+        }
+        Some(list)
+    }
+
     fn image_id(&self) -> Option<String> {
         Some(self.id.to_link())
     }
 
+    fn image_tags(&self) -> Option<Vec<String>> {
+        let tags = self.tags.as_ref()?;
+        let mut list = Vec::new();
+        for (ori, translated) in tags {
+            list.push(ori.clone());
+            if let Some(translated) = translated {
+                list.push(translated.clone());
+            }
+        }
+        Some(list)
+    }
+
     fn image_title(&self) -> Option<String> {
         self.title.clone()
     }
@@ -1,36 +1,22 @@
+use super::exif_data_source::{image_comment, image_copyright, image_keywords, ExifDataSource};
+use super::xmp::{get_xmp_properties, XmpProperty, XmpValueType};
 use crate::exif::ExifByteOrder;
 use crate::exif::ExifData;
 use crate::exif::ExifImage;
 use crate::exif::ExifKey;
 use crate::exif::ExifTypeID;
 use crate::exif::ExifValue;
+use crate::exif::IptcData;
+use crate::exif::XmpData;
 use crate::ext::try_err::TryErr;
-use crate::parser::description::parse_description;
-use proc_macros::call_parent_data_source_fun;
-use proc_macros::define_exif_data_source;
+use crate::opt::metadata_family::{MetadataFamilies, MetadataFamily};
+use crate::opthelper::get_helper;
 use std::ffi::OsStr;
 use utf16string::LittleEndian;
 use utf16string::WString;
 
-pub trait ExifDataSource {
-    define_exif_data_source!("src/data/exif_data_source.json");
-}
-
-impl<T: ExifDataSource> ExifDataSource for std::sync::Arc<T> {
-    call_parent_data_source_fun!("src/data/exif_data_source.json", **self,);
-}
-
-impl<T: ExifDataSource> ExifDataSource for Option<T> {
-    call_parent_data_source_fun!(
-        "src/data/exif_data_source.json",
-        match self {
-            Some(data) => data,
-            None => {
-                return None;
-            }
-        },
-    );
-}
+/// IPTC coded character set for UTF-8
+const IPTC_UTF8_CHARSET: &[u8] = b"\x1b%G";
 
 fn add_image_id<D: ExifDataSource>(data: &mut ExifData, d: &D) -> Result<(), ()> {
     let link = match d.image_id() {
@@ -73,24 +59,36 @@ fn add_image_author<D: ExifDataSource>(data: &mut ExifData, d: &D) -> Result<(),
     let s: WString<LittleEndian> = WString::from(author.as_str());
     value.read(s.as_bytes(), None)?;
     data.add(&key, &value)?;
+    let artist = match d.image_author_id() {
+        Some(id) => format!("{} ({})", author, id),
+        None => author,
+    };
     let key = ExifKey::try_from("Exif.Image.Artist")?;
     let mut value = ExifValue::try_from(ExifTypeID::AsciiString)?;
-    value.read(author.as_bytes(), None)?;
+    value.read(artist.as_bytes(), None)?;
+    data.add(&key, &value)?;
+    Ok(())
+}
+
+fn add_image_copyright<D: ExifDataSource>(data: &mut ExifData, d: &D) -> Result<(), ()> {
+    let copyright = match image_copyright(d) {
+        Some(copyright) => copyright,
+        None => return Ok(()),
+    };
+    let key = ExifKey::try_from("Exif.Image.Copyright")?;
+    let mut value = ExifValue::try_from(ExifTypeID::AsciiString)?;
+    value.read(copyright.as_bytes(), None)?;
     data.add(&key, &value)?;
     Ok(())
 }
 
 fn add_image_comment<D: ExifDataSource>(data: &mut ExifData, d: &D) -> Result<(), ()> {
-    let odesc = match d.image_comment() {
+    let desc = match image_comment(d) {
         Some(desc) => desc,
         None => {
             return Ok(());
         }
     };
-    let desc = match parse_description(odesc.as_str()) {
-        Some(desc) => desc,
-        None => odesc,
-    };
     let key = ExifKey::try_from("Exif.Image.XPComment")?;
     let mut value = ExifValue::try_from(ExifTypeID::BYTE)?;
     let s: WString<LittleEndian> = WString::from(desc.as_str());
@@ -99,6 +97,32 @@ fn add_image_comment<D: ExifDataSource>(data: &mut ExifData, d: &D) -> Result<()
     Ok(())
 }
 
+fn add_image_keywords<D: ExifDataSource>(data: &mut ExifData, d: &D) -> Result<(), ()> {
+    let list = image_keywords(d);
+    if list.is_empty() {
+        return Ok(());
+    }
+    let key = ExifKey::try_from("Exif.Image.XPKeywords")?;
+    let mut value = ExifValue::try_from(ExifTypeID::BYTE)?;
+    let s: WString<LittleEndian> = WString::from(list.join(";").as_str());
+    value.read(s.as_bytes(), None)?;
+    data.add(&key, &value)?;
+    Ok(())
+}
+
+fn add_image_date<D: ExifDataSource>(data: &mut ExifData, d: &D) -> Result<(), ()> {
+    let date = match d.image_date() {
+        Some(date) => date,
+        None => return Ok(()),
+    };
+    let key = ExifKey::try_from("Exif.Photo.DateTimeOriginal")?;
+    let date = date.format("%Y:%m:%d %H:%M:%S").to_string();
+    let mut value = ExifValue::try_from(ExifTypeID::AsciiString)?;
+    value.read(date.as_bytes(), None)?;
+    data.add(&key, &value)?;
+    Ok(())
+}
+
 fn add_image_page(data: &mut ExifData, page: u16) -> Result<(), ()> {
     let key = ExifKey::try_from("Exif.Image.PageNumber")?;
     let mut value = ExifValue::try_from(ExifTypeID::UShort)?;
@@ -107,21 +131,284 @@ fn add_image_page(data: &mut ExifData, page: u16) -> Result<(), ()> {
     Ok(())
 }
 
+/// Add a XMP property to the data.
+fn add_xmp(data: &mut XmpData, p: &XmpProperty) -> Result<(), ()> {
+    let typ = match p.typ {
+        XmpValueType::Text => ExifTypeID::XmpText,
+        XmpValueType::Bag => ExifTypeID::XmpBag,
+        XmpValueType::Seq => ExifTypeID::XmpSeq,
+        XmpValueType::LangAlt => ExifTypeID::LangAlt,
+    };
+    let mut value = ExifValue::try_from(typ)?;
+    for v in p.values.iter() {
+        match p.typ {
+            XmpValueType::LangAlt => {
+                value.read(format!("lang=\"x-default\" {}", v).as_bytes(), None)?;
+            }
+            _ => value.read(v.as_bytes(), None)?,
+        }
+    }
+    data.add(p.key, &value)
+}
+
+fn add_xmp_data<D: ExifDataSource>(data: &mut XmpData, d: &D) -> Result<(), ()> {
+    for p in get_xmp_properties(d) {
+        add_xmp(data, &p)?;
+    }
+    Ok(())
+}
+
+fn add_iptc(data: &mut IptcData, key: &str, typ: ExifTypeID, value: &[u8]) -> Result<(), ()> {
+    let mut v = ExifValue::try_from(typ)?;
+    v.read(value, None)?;
+    data.add(key, &v)
+}
+
+fn add_iptc_data<D: ExifDataSource>(data: &mut IptcData, d: &D) -> Result<(), ()> {
+    let s = ExifTypeID::String;
+    add_iptc(data, "Iptc.Envelope.CharacterSet", s, IPTC_UTF8_CHARSET)?;
+    if let Some(title) = d.image_title() {
+        add_iptc(data, "Iptc.Application2.ObjectName", s, title.as_bytes())?;
+    }
+    if let Some(author) = d.image_author() {
+        add_iptc(data, "Iptc.Application2.Byline", s, author.as_bytes())?;
+    }
+    if let Some(desc) = image_comment(d) {
+        add_iptc(data, "Iptc.Application2.Caption", s, desc.as_bytes())?;
+    }
+    for k in image_keywords(d) {
+        add_iptc(data, "Iptc.Application2.Keywords", s, k.as_bytes())?;
+    }
+    if let Some(copyright) = image_copyright(d) {
+        add_iptc(data, "Iptc.Application2.Copyright", s, copyright.as_bytes())?;
+    }
+    if let Some(date) = d.image_date() {
+        let day = date.format("%Y%m%d").to_string();
+        add_iptc(
+            data,
+            "Iptc.Application2.DateCreated",
+            ExifTypeID::Date,
+            day.as_bytes(),
+        )?;
+        let time = date.format("%H%M%S%z").to_string();
+        add_iptc(
+            data,
+            "Iptc.Application2.TimeCreated",
+            ExifTypeID::Time,
+            time.as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+fn update_exif_data<D: ExifDataSource>(f: &mut ExifImage, data: &D, page: u16) -> Result<(), ()> {
+    let mut n = ExifData::new()?;
+    add_image_id(&mut n, data)?;
+    add_image_title(&mut n, data)?;
+    add_image_author(&mut n, data)?;
+    add_image_copyright(&mut n, data)?;
+    add_image_comment(&mut n, data)?;
+    add_image_keywords(&mut n, data)?;
+    add_image_date(&mut n, data)?;
+    add_image_page(&mut n, page)?;
+    // Keep the existing metadata, but replace the fields which are written again.
+    let mut d = f.exif_data().try_err(())?.to_owned();
+    for datum in n.iter().try_err(())? {
+        let key = ExifKey::try_from(datum.key().try_err(())?.as_str())?;
+        d.remove(&key)?;
+    }
+    for datum in n.iter().try_err(())? {
+        let key = ExifKey::try_from(datum.key().try_err(())?.as_str())?;
+        d.add(&key, datum.value().try_err(())?)?;
+    }
+    f.set_exif_data(&d)
+}
+
+fn update_xmp_data<D: ExifDataSource>(f: &mut ExifImage, data: &D) -> Result<(), ()> {
+    let mut n = XmpData::new()?;
+    add_xmp_data(&mut n, data)?;
+    let mut d = f.xmp_data().try_err(())?.to_owned();
+    for datum in n.iter().try_err(())? {
+        d.remove(&datum.key().try_err(())?)?;
+    }
+    for datum in n.iter().try_err(())? {
+        d.add(&datum.key().try_err(())?, datum.value().try_err(())?)?;
+    }
+    f.set_xmp_data(&d)
+}
+
+fn update_iptc_data<D: ExifDataSource>(f: &mut ExifImage, data: &D) -> Result<(), ()> {
+    let mut n = IptcData::new()?;
+    add_iptc_data(&mut n, data)?;
+    let mut d = f.iptc_data().try_err(())?.to_owned();
+    for datum in n.iter().try_err(())? {
+        d.remove(&datum.key().try_err(())?)?;
+    }
+    for datum in n.iter().try_err(())? {
+        d.add(&datum.key().try_err(())?, datum.value().try_err(())?)?;
+    }
+    f.set_iptc_data(&d)
+}
+
+/// Write metadata to the image. The families of metadata are specified by
+/// the `metadata-families` setting.
 pub fn add_exifdata_to_image<S: AsRef<OsStr> + ?Sized, D: ExifDataSource>(
     file_name: &S,
     data: &D,
     page: u16,
+) -> Result<(), ()> {
+    add_metadata_to_image(file_name, data, page, get_helper().metadata_families())
+}
+
+fn add_metadata_to_image<S: AsRef<OsStr> + ?Sized, D: ExifDataSource>(
+    file_name: &S,
+    data: &D,
+    page: u16,
+    families: MetadataFamilies,
 ) -> Result<(), ()> {
     let mut f = ExifImage::new(file_name)?;
-    // # TODO: do duplicate check
-    // f.read_metadata()?;
-    let mut d = f.exif_data().try_err(())?.to_owned();
-    add_image_id(&mut d, data)?;
-    add_image_title(&mut d, data)?;
-    add_image_author(&mut d, data)?;
-    add_image_comment(&mut d, data)?;
-    add_image_page(&mut d, page)?;
-    f.set_exif_data(&d)?;
+    f.read_metadata()?;
+    if families.contains(MetadataFamily::Exif) {
+        update_exif_data(&mut f, data, page)?;
+    }
+    if families.contains(MetadataFamily::Xmp) {
+        update_xmp_data(&mut f, data)?;
+    }
+    if families.contains(MetadataFamily::Iptc) {
+        update_iptc_data(&mut f, data)?;
+    }
     f.write_metadata()?;
     Ok(())
 }
+
+#[cfg(test)]
+struct TestExifData;
+
+#[cfg(test)]
+impl ExifDataSource for TestExifData {
+    fn image_ai_generated(&self) -> Option<bool> {
+        Some(true)
+    }
+
+    fn image_date(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        chrono::DateTime::parse_from_rfc3339("2021-07-23T00:00:00+09:00").ok()
+    }
+
+    fn image_hierarchical_tags(&self) -> Option<Vec<String>> {
+        Some(vec![
+            String::from("pixiv|ご注文はうさぎですか?"),
+            String::from("pixiv|ご注文はうさぎですか?|Is the Order a Rabbit?"),
+        ])
+    }
+
+    fn image_tags(&self) -> Option<Vec<String>> {
+        Some(vec![
+            String::from("ご注文はうさぎですか?"),
+            String::from("Is the Order a Rabbit?"),
+        ])
+    }
+
+    fn image_title(&self) -> Option<String> {
+        Some(String::from("夏のチマメ隊🏖️"))
+    }
+}
+
+#[test]
+fn test_add_exifdata_to_image() {
+    let p = std::path::Path::new("./test");
+    if !p.exists() {
+        let re = std::fs::create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let target = "./test/exif_merge_91055644_p0.jpg";
+    std::fs::copy("./testdata/夏のチマメ隊🏖️_91055644_p0.jpg", target).unwrap();
+    // Writing twice should not duplicate the fields.
+    add_exifdata_to_image(target, &TestExifData, 0).unwrap();
+    add_exifdata_to_image(target, &TestExifData, 0).unwrap();
+    let mut img = ExifImage::new(target).unwrap();
+    img.read_metadata().unwrap();
+    let data = img.exif_data().unwrap();
+    let keys: Vec<String> = data.iter().unwrap().filter_map(|d| d.key()).collect();
+    for k in [
+        "Exif.Image.XPTitle",
+        "Exif.Image.XPKeywords",
+        "Exif.Image.PageNumber",
+        "Exif.Photo.DateTimeOriginal",
+    ] {
+        assert_eq!(keys.iter().filter(|x| x.as_str() == k).count(), 1);
+    }
+    let date = data
+        .iter()
+        .unwrap()
+        .find(|d| d.key().as_deref() == Some("Exif.Photo.DateTimeOriginal"))
+        .unwrap();
+    assert_eq!(
+        date.value().unwrap().to_string(),
+        Ok(std::ffi::CString::new("2021:07:23 00:00:00").unwrap())
+    );
+}
+
+#[test]
+fn test_add_xmp_and_iptc_to_image() {
+    let p = std::path::Path::new("./test");
+    if !p.exists() {
+        let re = std::fs::create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let target = "./test/xmp_iptc_91055644_p0.jpg";
+    std::fs::copy("./testdata/夏のチマメ隊🏖️_91055644_p0.jpg", target).unwrap();
+    let families = MetadataFamilies::all();
+    add_metadata_to_image(target, &TestExifData, 0, families).unwrap();
+    add_metadata_to_image(target, &TestExifData, 0, families).unwrap();
+    let mut img = ExifImage::new(target).unwrap();
+    img.read_metadata().unwrap();
+    let xmp = img.xmp_data().unwrap();
+    let count = |k: &str| {
+        xmp.iter()
+            .unwrap()
+            .filter(|d| d.key().as_deref() == Some(k))
+            .count()
+    };
+    for k in [
+        "Xmp.dc.title",
+        "Xmp.dc.subject",
+        "Xmp.lr.hierarchicalSubject",
+        "Xmp.digiKam.TagsList",
+        "Xmp.iptcExt.DigitalSourceType",
+    ] {
+        assert_eq!(count(k), 1);
+    }
+    let subject = xmp
+        .iter()
+        .unwrap()
+        .find(|d| d.key().as_deref() == Some("Xmp.dc.subject"))
+        .unwrap();
+    assert_eq!(subject.value().unwrap().count(), 3);
+    let tags = xmp
+        .iter()
+        .unwrap()
+        .find(|d| d.key().as_deref() == Some("Xmp.digiKam.TagsList"))
+        .unwrap();
+    assert_eq!(
+        tags.value().unwrap().to_nth_string(1),
+        Ok(Some(
+            std::ffi::CString::new("pixiv/ご注文はうさぎですか?/Is the Order a Rabbit?").unwrap()
+        ))
+    );
+    let iptc = img.iptc_data().unwrap();
+    let keywords = iptc
+        .iter()
+        .unwrap()
+        .filter(|d| d.key().as_deref() == Some("Iptc.Application2.Keywords"))
+        .count();
+    assert_eq!(keywords, 3);
+    let time = iptc
+        .iter()
+        .unwrap()
+        .find(|d| d.key().as_deref() == Some("Iptc.Application2.TimeCreated"))
+        .unwrap();
+    assert_eq!(
+        time.value().unwrap().to_string(),
+        Ok(std::ffi::CString::new("00:00:00+09:00").unwrap())
+    );
+}
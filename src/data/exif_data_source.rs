@@ -0,0 +1,64 @@
+use crate::parser::description::parse_description;
+use proc_macros::call_parent_data_source_fun;
+use proc_macros::define_exif_data_source;
+
+/// The keyword added to AI-generated images
+pub const AI_GENERATED_KEYWORD: &str = "AI-generated";
+/// IPTC digital source type of the images generated by AI
+pub const AI_DIGITAL_SOURCE_TYPE: &str =
+    "http://cv.iptc.org/newscodes/digitalsourcetype/trainedAlgorithmicMedia";
+
+/// The source of the metadata written to downloaded files.
+pub trait ExifDataSource {
+    define_exif_data_source!("src/data/exif_data_source.json");
+}
+
+impl<T: ExifDataSource + ?Sized> ExifDataSource for std::sync::Arc<T> {
+    call_parent_data_source_fun!("src/data/exif_data_source.json", **self,);
+}
+
+impl<T: ExifDataSource> ExifDataSource for Option<T> {
+    call_parent_data_source_fun!(
+        "src/data/exif_data_source.json",
+        match self {
+            Some(data) => data,
+            None => {
+                return None;
+            }
+        },
+    );
+}
+
+/// Returns the copyright notice of the image.
+pub fn image_copyright<D: ExifDataSource + ?Sized>(d: &D) -> Option<String> {
+    match (d.image_author(), d.image_author_url()) {
+        (Some(author), Some(url)) => Some(format!("{} ({})", author, url)),
+        (Some(author), None) => Some(author),
+        (None, Some(url)) => Some(url),
+        (None, None) => None,
+    }
+}
+
+/// Returns the comment of the image with HTML removed.
+pub fn image_comment<D: ExifDataSource + ?Sized>(d: &D) -> Option<String> {
+    let odesc = d.image_comment()?;
+    match parse_description(odesc.as_str()) {
+        Some(desc) => Some(desc),
+        None => Some(odesc),
+    }
+}
+
+/// Returns the keywords of the image without duplicates.
+pub fn image_keywords<D: ExifDataSource + ?Sized>(d: &D) -> Vec<String> {
+    let mut keywords = d.image_tags().unwrap_or_default();
+    if d.image_ai_generated().unwrap_or(false) {
+        keywords.push(String::from(AI_GENERATED_KEYWORD));
+    }
+    let mut list: Vec<String> = Vec::new();
+    for k in keywords {
+        if !list.contains(&k) {
+            list.push(k);
+        }
+    }
+    list
+}
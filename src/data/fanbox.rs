@@ -1,21 +1,16 @@
-#[cfg(feature = "exif")]
-use super::exif::ExifDataSource;
+use super::exif_data_source::ExifDataSource;
 use crate::ext::json::ToJson2;
-#[cfg(feature = "exif")]
 use crate::opt::author_name_filter::AuthorFiler;
-#[cfg(feature = "exif")]
 use crate::opthelper::get_helper;
 use crate::pixiv_link::PixivID;
 use crate::pixiv_link::ToPixivID;
 use json::JsonValue;
-#[cfg(feature = "exif")]
 use proc_macros::call_parent_data_source_fun;
 
 pub struct FanboxData {
     pub id: PixivID,
     /// Raw data
     pub raw: JsonValue,
-    #[cfg(feature = "exif")]
     pub exif_data: Option<Box<dyn ExifDataSource + Send + Sync>>,
 }
 
@@ -25,7 +20,6 @@ impl FanboxData {
             Some(id) => Some(Self {
                 id,
                 raw: data.to_json2(),
-                #[cfg(feature = "exif")]
                 exif_data: None,
             }),
             None => None,
@@ -38,13 +32,11 @@ impl Clone for FanboxData {
         Self {
             id: self.id.clone(),
             raw: self.raw.clone(),
-            #[cfg(feature = "exif")]
             exif_data: None,
         }
     }
 }
 
-#[cfg(feature = "exif")]
 impl ExifDataSource for FanboxData {
     call_parent_data_source_fun!(
         "src/data/exif_data_source.json",
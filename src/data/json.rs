@@ -5,6 +5,7 @@ use crate::gettext;
 use crate::parser::description::{convert_description_to_md, parse_description};
 use crate::pixiv_link::PixivID;
 use crate::pixiv_link::ToPixivID;
+use chrono::Utc;
 use int_enum::IntEnum;
 use json::JsonValue;
 use std::collections::HashMap;
@@ -16,6 +17,10 @@ use std::io::Write;
 use std::path::Path;
 use std::sync::Arc;
 
+/// The version of the layout of JSON files which store [PixivData].
+/// Increase it when keys are renamed or removed.
+pub const JSON_SCHEMA_VERSION: u64 = 2;
+
 /// Store metadata informations in JSON file
 pub struct JSONDataFile {
     id: PixivID,
@@ -87,12 +92,34 @@ impl From<&PixivData> for JSONDataFile {
             id: p.id.clone(),
             maps: HashMap::new(),
         };
+        f.add("schema_version", JSON_SCHEMA_VERSION);
         if p.title.is_some() {
             f.add("title", p.title.as_ref().unwrap());
         }
         if p.author.is_some() {
             f.add("author", p.author.as_ref().unwrap());
         }
+        // The keys below always exist, null is used if the value is unknown.
+        // Dates are stored in UTC because web API and app API use different time zones.
+        f.add("author_id", JsonValue::from(p.author_id));
+        f.add("author_account", JsonValue::from(p.author_account.clone()));
+        f.add(
+            "upload_date",
+            JsonValue::from(p.upload_date.map(|d| d.with_timezone(&Utc).to_rfc3339())),
+        );
+        f.add(
+            "update_date",
+            JsonValue::from(p.update_date.map(|d| d.with_timezone(&Utc).to_rfc3339())),
+        );
+        f.add("type", JsonValue::from(p.work_type.map(|t| t.as_str())));
+        f.add("page_count", JsonValue::from(p.page_count));
+        f.add("width", JsonValue::from(p.width));
+        f.add("height", JsonValue::from(p.height));
+        f.add("x_restrict", JsonValue::from(p.x_restrict));
+        f.add("bookmark_count", JsonValue::from(p.bookmark_count));
+        f.add("view_count", JsonValue::from(p.view_count));
+        f.add("comment_count", JsonValue::from(p.comment_count));
+        f.add("original_urls", JsonValue::from(p.original_urls.clone()));
         if p.description.is_some() {
             let desc = p.description.as_ref().unwrap();
             f.add("description", desc);
@@ -122,6 +149,27 @@ impl From<&PixivData> for JSONDataFile {
             }
             None => {}
         }
+        if let Some(series) = &p.series {
+            f.add(
+                "series",
+                json::object! {
+                    "id": series.series_id,
+                    "title": series.series_title.as_str(),
+                    "order": series.order,
+                },
+            );
+        }
+        if let Some(ranking) = &p.ranking {
+            f.add(
+                "ranking",
+                json::object! {
+                    "mode": ranking.mode.web_value(),
+                    "content": ranking.content.as_str(),
+                    "date": ranking.date.map(|d| d.format("%Y-%m-%d").to_string()),
+                    "rank": ranking.rank,
+                },
+            );
+        }
         match &p.ai_type {
             Some(ai_type) => {
                 f.add("is_ai", ai_type.is_ai());
@@ -132,6 +180,13 @@ impl From<&PixivData> for JSONDataFile {
                 f.add("ai_type", JsonValue::Null);
             }
         }
+        if let Some(image_size) = &p.image_size {
+            f.add("image_size", image_size.as_str());
+        }
+        if let Some(pages) = &p.pages {
+            let pages: Vec<u64> = pages.iter().map(|np| *np as u64 + 1).collect();
+            f.add("pages", JsonValue::from(pages));
+        }
         f
     }
 }
@@ -172,3 +227,113 @@ impl ToJson for JSONDataFile {
         Some(value)
     }
 }
+
+#[cfg(test)]
+/// Returns the JSON data files of the same artwork read from app API and web API.
+/// * `create_date` - The date when the artwork is created, in JST
+/// * `upload_date` - The date when the images are uploaded, in UTC
+/// * `url_date` - The date in the links of the images
+fn get_app_and_web_json(
+    create_date: &str,
+    upload_date: &str,
+    url_date: &str,
+) -> (JsonValue, JsonValue) {
+    use crate::pixivapp::illust::PixivAppIllust;
+    let url = |np: u32| {
+        format!(
+            "https://i.pximg.net/img-original/img/{}/91055644_p{}.jpg",
+            url_date, np
+        )
+    };
+    let app = PixivAppIllust::new(json::object! {
+        "id": 91055644,
+        "title": "夏のチマメ隊🏖️",
+        "type": "manga",
+        "caption": "test",
+        "user": {"id": 212801, "name": "作者", "account": "author"},
+        "tags": [
+            {"name": "ご注文はうさぎですか?", "translated_name": "Is the Order a Rabbit?"},
+            {"name": "チマメ隊", "translated_name": null},
+        ],
+        "create_date": create_date,
+        "page_count": 2,
+        "width": 2000,
+        "height": 1414,
+        "x_restrict": 0,
+        "meta_single_page": {},
+        "meta_pages": [
+            {"image_urls": {"original": url(0)}},
+            {"image_urls": {"original": url(1)}},
+        ],
+        "total_view": 1000,
+        "total_bookmarks": 200,
+        "total_comments": 3,
+        "illust_ai_type": 1,
+    });
+    let create_date = chrono::DateTime::parse_from_rfc3339(create_date)
+        .unwrap()
+        .with_timezone(&Utc)
+        .to_rfc3339();
+    let web = json::object! {
+        "illustTitle": "夏のチマメ隊🏖️",
+        "illustType": 1,
+        "description": "test",
+        "userId": "212801",
+        "userName": "作者",
+        "userAccount": "author",
+        "tags": {"tags": [
+            {"tag": "ご注文はうさぎですか?", "translation": {"en": "Is the Order a Rabbit?"}},
+            {"tag": "チマメ隊"},
+        ]},
+        "createDate": create_date,
+        "uploadDate": upload_date,
+        "pageCount": 2,
+        "width": 2000,
+        "height": 1414,
+        "xRestrict": 0,
+        "urls": {"original": url(0)},
+        "viewCount": 1000,
+        "bookmarkCount": 200,
+        "commentCount": 3,
+        "aiType": 1,
+    };
+    let pages = json::array![
+        {"urls": {"original": url(0)}},
+        {"urls": {"original": url(1)}},
+    ];
+    let mut app_data = PixivData::new(91055644).unwrap();
+    app_data.from_app_illust(&app);
+    let mut web_data = PixivData::new(91055644).unwrap();
+    web_data.from_web_page_ajax_data(&web, true);
+    web_data.from_web_illust_pages(&pages);
+    (
+        JSONDataFile::from(&app_data).to_json().unwrap(),
+        JSONDataFile::from(&web_data).to_json().unwrap(),
+    )
+}
+
+#[test]
+fn test_pixiv_data_json_same_for_app_and_web() {
+    let (app_json, web_json) = get_app_and_web_json(
+        "2021-07-05T00:00:09+09:00",
+        "2021-07-04T15:00:09+00:00",
+        "2021/07/05/00/00/09",
+    );
+    assert_eq!(app_json["schema_version"], JSON_SCHEMA_VERSION);
+    assert_eq!(app_json["type"], "manga");
+    assert_eq!(app_json["original_urls"].len(), 2);
+    assert!(app_json["update_date"].is_null());
+    assert_eq!(app_json, web_json);
+}
+
+#[test]
+fn test_pixiv_data_json_same_for_updated_artwork() {
+    let (app_json, web_json) = get_app_and_web_json(
+        "2021-07-05T00:00:09+09:00",
+        "2021-08-01T03:00:00+00:00",
+        "2021/08/01/12/00/00",
+    );
+    assert_eq!(app_json["upload_date"], "2021-07-04T15:00:09+00:00");
+    assert_eq!(app_json["update_date"], "2021-08-01T03:00:00+00:00");
+    assert_eq!(app_json, web_json);
+}
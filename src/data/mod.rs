@@ -1,6 +1,9 @@
 pub mod data;
 #[cfg(feature = "exif")]
 pub mod exif;
+pub mod exif_data_source;
 pub mod fanbox;
 pub mod json;
 pub mod video;
+/// XMP sidecar files
+pub mod xmp;
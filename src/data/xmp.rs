@@ -0,0 +1,284 @@
+use super::exif_data_source::{
+    image_comment, image_copyright, image_keywords, ExifDataSource, AI_DIGITAL_SOURCE_TYPE,
+};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// The namespaces used by [XmpProperty]. (prefix, uri)
+const XMP_NAMESPACES: [(&str, &str); 6] = [
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("xmp", "http://ns.adobe.com/xap/1.0/"),
+    ("photoshop", "http://ns.adobe.com/photoshop/1.0/"),
+    ("lr", "http://ns.adobe.com/lightroom/1.0/"),
+    ("digiKam", "http://www.digikam.org/ns/1.0/"),
+    ("iptcExt", "http://iptc.org/std/Iptc4xmpExt/2008-02-29/"),
+];
+
+/// The type of a XMP property
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XmpValueType {
+    /// Simple text
+    Text,
+    /// Unordered array
+    Bag,
+    /// Ordered array
+    Seq,
+    /// Language alternative. Only `x-default` is used.
+    LangAlt,
+}
+
+/// A XMP property
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XmpProperty {
+    /// The key used by Exiv2, for example `Xmp.dc.title`
+    pub key: &'static str,
+    pub typ: XmpValueType,
+    /// The items of the value. Only the first one is used for simple text and
+    /// language alternative.
+    pub values: Vec<String>,
+}
+
+impl XmpProperty {
+    fn new<S: AsRef<str>>(key: &'static str, typ: XmpValueType, values: &[S]) -> Self {
+        Self {
+            key,
+            typ,
+            values: values.iter().map(|s| s.as_ref().to_owned()).collect(),
+        }
+    }
+
+    /// Returns the namespace prefix and the name of the property.
+    pub fn prefix_and_name(&self) -> (&'static str, &'static str) {
+        let mut s = self.key.splitn(3, '.').skip(1);
+        let prefix = s.next().unwrap_or_default();
+        (prefix, s.next().unwrap_or_default())
+    }
+}
+
+/// Returns the XMP properties of the data. Properties without values are
+/// not included.
+/// * `d` - The data source
+pub fn get_xmp_properties<D: ExifDataSource + ?Sized>(d: &D) -> Vec<XmpProperty> {
+    let mut list = Vec::new();
+    if let Some(title) = d.image_title() {
+        list.push(XmpProperty::new(
+            "Xmp.dc.title",
+            XmpValueType::LangAlt,
+            &[title],
+        ));
+    }
+    if let Some(author) = d.image_author() {
+        list.push(XmpProperty::new(
+            "Xmp.dc.creator",
+            XmpValueType::Seq,
+            &[author],
+        ));
+    }
+    if let Some(desc) = image_comment(d) {
+        list.push(XmpProperty::new(
+            "Xmp.dc.description",
+            XmpValueType::LangAlt,
+            &[desc],
+        ));
+    }
+    let keywords = image_keywords(d);
+    list.push(XmpProperty::new(
+        "Xmp.dc.subject",
+        XmpValueType::Bag,
+        &keywords,
+    ));
+    if let Some(link) = d.image_id() {
+        list.push(XmpProperty::new(
+            "Xmp.dc.source",
+            XmpValueType::Text,
+            &[link],
+        ));
+    }
+    if let Some(copyright) = image_copyright(d) {
+        list.push(XmpProperty::new(
+            "Xmp.dc.rights",
+            XmpValueType::LangAlt,
+            &[copyright],
+        ));
+    }
+    if let Some(date) = d.image_date() {
+        let date = [date.to_rfc3339()];
+        list.push(XmpProperty::new(
+            "Xmp.xmp.CreateDate",
+            XmpValueType::Text,
+            &date,
+        ));
+        list.push(XmpProperty::new(
+            "Xmp.photoshop.DateCreated",
+            XmpValueType::Text,
+            &date,
+        ));
+    }
+    let tags = d.image_hierarchical_tags().unwrap_or_default();
+    list.push(XmpProperty::new(
+        "Xmp.lr.hierarchicalSubject",
+        XmpValueType::Bag,
+        &tags,
+    ));
+    // digiKam uses `/` as the separator of levels.
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|t| {
+            t.split('|')
+                .map(|s| s.replace('/', "_"))
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect();
+    list.push(XmpProperty::new(
+        "Xmp.digiKam.TagsList",
+        XmpValueType::Seq,
+        &tags,
+    ));
+    if d.image_ai_generated().unwrap_or(false) {
+        list.push(XmpProperty::new(
+            "Xmp.iptcExt.DigitalSourceType",
+            XmpValueType::Text,
+            &[AI_DIGITAL_SOURCE_TYPE],
+        ));
+    }
+    list.retain(|p| !p.values.is_empty());
+    list
+}
+
+/// Escape the text for XML. Characters which are not allowed in XML are removed.
+fn escape_xml(s: &str) -> String {
+    let mut re = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => re.push_str("&amp;"),
+            '<' => re.push_str("&lt;"),
+            '>' => re.push_str("&gt;"),
+            '"' => re.push_str("&quot;"),
+            '\t' | '\n' | '\r' => re.push(c),
+            c if c < ' ' || c == '\u{fffe}' || c == '\u{ffff}' => {}
+            c => re.push(c),
+        }
+    }
+    re
+}
+
+/// Serialize the properties to a XMP document.
+/// * `props` - The properties
+pub fn to_xmp_document(props: &[XmpProperty]) -> String {
+    let mut s = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    s += "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n";
+    s += " <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n";
+    s += "  <rdf:Description rdf:about=\"\"";
+    for (prefix, uri) in XMP_NAMESPACES.iter() {
+        s += &format!("\n    xmlns:{}=\"{}\"", prefix, uri);
+    }
+    s += ">\n";
+    for p in props {
+        let (prefix, name) = p.prefix_and_name();
+        let tag = format!("{}:{}", prefix, name);
+        let first = escape_xml(p.values.first().map(|v| v.as_str()).unwrap_or_default());
+        match p.typ {
+            XmpValueType::Text => {
+                s += &format!("   <{0}>{1}</{0}>\n", tag, first);
+            }
+            XmpValueType::LangAlt => {
+                s += &format!("   <{}>\n    <rdf:Alt>\n", tag);
+                s += &format!("     <rdf:li xml:lang=\"x-default\">{}</rdf:li>\n", first);
+                s += &format!("    </rdf:Alt>\n   </{}>\n", tag);
+            }
+            XmpValueType::Bag | XmpValueType::Seq => {
+                let array = if p.typ == XmpValueType::Bag {
+                    "rdf:Bag"
+                } else {
+                    "rdf:Seq"
+                };
+                s += &format!("   <{}>\n    <{}>\n", tag, array);
+                for v in p.values.iter() {
+                    s += &format!("     <rdf:li>{}</rdf:li>\n", escape_xml(v));
+                }
+                s += &format!("    </{}>\n   </{}>\n", array, tag);
+            }
+        }
+    }
+    s += "  </rdf:Description>\n </rdf:RDF>\n</x:xmpmeta>\n";
+    s
+}
+
+/// Returns the path of the XMP sidecar file. The sidecar file is named
+/// after the full file name, like `123_p0.png.xmp`, so files which only
+/// differ in extension do not share one sidecar.
+/// * `path` - The path of the file
+pub fn get_xmp_sidecar_path<P: AsRef<Path> + ?Sized>(path: &P) -> PathBuf {
+    let mut p = OsString::from(path.as_ref().as_os_str());
+    p.push(".xmp");
+    PathBuf::from(p)
+}
+
+/// Write the metadata to a XMP sidecar file next to the file.
+/// * `path` - The path of the file
+/// * `d` - The data source
+pub fn write_xmp_sidecar<P: AsRef<Path> + ?Sized, D: ExifDataSource + ?Sized>(
+    path: &P,
+    d: &D,
+) -> std::io::Result<()> {
+    let props = get_xmp_properties(d);
+    std::fs::write(get_xmp_sidecar_path(path), to_xmp_document(&props))
+}
+
+#[cfg(test)]
+struct TestXmpData;
+
+#[cfg(test)]
+impl ExifDataSource for TestXmpData {
+    fn image_ai_generated(&self) -> Option<bool> {
+        Some(true)
+    }
+
+    fn image_author(&self) -> Option<String> {
+        Some(String::from("<Author> & Co"))
+    }
+
+    fn image_hierarchical_tags(&self) -> Option<Vec<String>> {
+        Some(vec![
+            String::from("pixiv|ご注文はうさぎですか?"),
+            String::from("pixiv|ご注文はうさぎですか?|Is the Order a Rabbit?"),
+        ])
+    }
+
+    fn image_tags(&self) -> Option<Vec<String>> {
+        Some(vec![String::from("ご注文はうさぎですか?")])
+    }
+
+    fn image_title(&self) -> Option<String> {
+        Some(String::from("夏のチマメ隊🏖️\u{1}"))
+    }
+}
+
+#[test]
+fn test_xmp_sidecar() {
+    let props = get_xmp_properties(&TestXmpData);
+    let keys: Vec<_> = props.iter().map(|p| p.key).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "Xmp.dc.title",
+            "Xmp.dc.creator",
+            "Xmp.dc.subject",
+            "Xmp.dc.rights",
+            "Xmp.lr.hierarchicalSubject",
+            "Xmp.digiKam.TagsList",
+            "Xmp.iptcExt.DigitalSourceType",
+        ]
+    );
+    assert_eq!(props[0].prefix_and_name(), ("dc", "title"));
+    let doc = to_xmp_document(&props);
+    assert!(doc.contains("<rdf:li xml:lang=\"x-default\">夏のチマメ隊🏖️</rdf:li>"));
+    assert!(doc.contains("<rdf:li>&lt;Author&gt; &amp; Co</rdf:li>"));
+    assert!(doc.contains("<rdf:li>AI-generated</rdf:li>"));
+    assert!(doc.contains("<rdf:li>pixiv/ご注文はうさぎですか?/Is the Order a Rabbit?</rdf:li>"));
+    assert_eq!(
+        get_xmp_sidecar_path("./test/91055644_p0.jpg"),
+        PathBuf::from("./test/91055644_p0.jpg.xmp")
+    );
+}
@@ -14,7 +14,7 @@ pub use config::check_db_config;
 pub use config::PixivDownloaderDbConfig;
 #[cfg(feature = "db_sqlite")]
 pub use config::PixivDownloaderSqliteConfig;
-pub use pixiv_artworks::{PixivArtwork, PixivArtworkLock};
+pub use pixiv_artworks::{PixivArtwork, PixivArtworkLock, PixivFile};
 #[cfg(feature = "server")]
 pub use push_task::{PushConfig, PushTask, PushTaskConfig};
 #[cfg(feature = "db_sqlite")]
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use flagset::FlagSet;
 
 flagset::flags! {
@@ -28,3 +29,19 @@ pub struct PixivArtwork {
     /// Specify which part should not be updated.
     pub lock: FlagSet<PixivArtworkLock>,
 }
+
+/// A downloaded file of pixiv artwork
+pub struct PixivFile {
+    /// The artwork ID
+    pub id: u64,
+    /// Page number, starts from 0
+    pub page: u64,
+    /// The path of the file
+    pub path: String,
+    /// The URL of the file
+    pub url: String,
+    /// The last modified time when the file is recorded
+    pub last_modified: DateTime<Utc>,
+    /// The SHA-256 hash of the file when the file is recorded
+    pub sha256: Option<String>,
+}
@@ -1,6 +1,6 @@
 #[cfg(feature = "server")]
 use super::super::Optional2Extension;
-use super::super::{PixivArtwork, PixivArtworkLock};
+use super::super::{PixivArtwork, PixivArtworkLock, PixivFile};
 use super::super::{
     PixivDownloaderDb, PixivDownloaderDbConfig, PixivDownloaderDbError, PixivDownloaderSqliteConfig,
 };
@@ -36,7 +36,8 @@ const FILES_TABLE: &'static str = "CREATE TABLE files (
 id INTEGER PRIMARY KEY AUTOINCREMENT,
 path TEXT,
 last_modified DATETIME,
-url TEXT
+url TEXT,
+sha256 TEXT
 );";
 const PIXIV_ARTWORK_TAGS_TABLE: &'static str = "CREATE TABLE pixiv_artwork_tags (
 id INT,
@@ -106,7 +107,7 @@ v3 INT,
 v4 INT,
 PRIMARY KEY (id)
 );";
-const VERSION: [u8; 4] = [1, 0, 0, 9];
+const VERSION: [u8; 4] = [1, 0, 0, 10];
 
 pub struct PixivDownloaderSqlite {
     db: Mutex<Connection>,
@@ -140,6 +141,44 @@ impl PixivDownloaderSqlite {
         Ok(())
     }
 
+    fn _add_pixiv_file(
+        ts: &Transaction,
+        id: u64,
+        page: u64,
+        path: &str,
+        url: &str,
+        last_modified: &DateTime<Utc>,
+        sha256: Option<&str>,
+    ) -> Result<(), SqliteError> {
+        let file_id: Option<i64> = ts
+            .query_row(
+                "SELECT file_id FROM pixiv_files WHERE id = ? AND page = ?;",
+                (id, page),
+                |row| row.get(0),
+            )
+            .optional()?;
+        match file_id {
+            Some(file_id) => {
+                ts.execute(
+                    "UPDATE files SET path = ?, last_modified = ?, url = ?, sha256 = ? WHERE id = ?;",
+                    (path, last_modified, url, sha256, file_id),
+                )?;
+            }
+            None => {
+                ts.execute(
+                    "INSERT INTO files (path, last_modified, url, sha256) VALUES (?, ?, ?, ?);",
+                    (path, last_modified, url, sha256),
+                )?;
+                let file_id = ts.last_insert_rowid();
+                ts.execute(
+                    "INSERT INTO pixiv_files (id, file_id, page) VALUES (?, ?, ?);",
+                    (id, file_id, page),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "server")]
     fn _add_push_task(
         ts: &Transaction,
@@ -271,6 +310,9 @@ impl PixivDownloaderSqlite {
                 if db_version < [1, 0, 0, 9] {
                     tx.execute(TMP_CACHE_TABLE, [])?;
                 }
+                if db_version < [1, 0, 0, 10] && db_version >= [1, 0, 0, 5] {
+                    tx.execute("ALTER TABLE files ADD sha256 TEXT;", [])?;
+                }
                 self._write_version(&tx)?;
                 tx.commit()?;
             }
@@ -331,6 +373,24 @@ impl PixivDownloaderSqlite {
         Ok(())
     }
 
+    fn _delete_pixiv_file(tx: &Transaction, id: u64, page: u64) -> Result<(), SqliteError> {
+        let file_id: Option<i64> = tx
+            .query_row(
+                "SELECT file_id FROM pixiv_files WHERE id = ? AND page = ?;",
+                (id, page),
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(file_id) = file_id {
+            tx.execute(
+                "DELETE FROM pixiv_files WHERE id = ? AND page = ?;",
+                (id, page),
+            )?;
+            tx.execute("DELETE FROM files WHERE id = ?;", [file_id])?;
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "server")]
     fn _delete_tmp_cache(tx: &Transaction, url: &str) -> Result<(), SqliteError> {
         tx.execute("DELETE FROM tmp_cache WHERE url = ?;", [url])?;
@@ -435,6 +495,42 @@ impl PixivDownloaderSqlite {
             .optional()?)
     }
 
+    async fn get_all_pixiv_files(&self) -> Result<Vec<PixivFile>, SqliteError> {
+        let con = self.db.lock().await;
+        let mut stmt = con.prepare("SELECT pixiv_files.id, pixiv_files.page, files.path, files.url, files.last_modified, files.sha256 FROM pixiv_files INNER JOIN files ON pixiv_files.file_id = files.id ORDER BY pixiv_files.id, pixiv_files.page;")?;
+        let mut rows = stmt.query([])?;
+        let mut files = Vec::new();
+        while let Some(row) = rows.next()? {
+            files.push(PixivFile {
+                id: row.get(0)?,
+                page: row.get(1)?,
+                path: row.get(2)?,
+                url: row.get(3)?,
+                last_modified: row.get(4)?,
+                sha256: row.get(5)?,
+            });
+        }
+        Ok(files)
+    }
+
+    async fn get_pixiv_files(&self, id: u64) -> Result<Vec<PixivFile>, SqliteError> {
+        let con = self.db.lock().await;
+        let mut stmt = con.prepare("SELECT pixiv_files.page, files.path, files.url, files.last_modified, files.sha256 FROM pixiv_files INNER JOIN files ON pixiv_files.file_id = files.id WHERE pixiv_files.id = ? ORDER BY pixiv_files.page;")?;
+        let mut rows = stmt.query([id])?;
+        let mut files = Vec::new();
+        while let Some(row) = rows.next()? {
+            files.push(PixivFile {
+                id,
+                page: row.get(0)?,
+                path: row.get(1)?,
+                url: row.get(2)?,
+                last_modified: row.get(3)?,
+                sha256: row.get(4)?,
+            });
+        }
+        Ok(files)
+    }
+
     #[cfg(feature = "server")]
     async fn get_push_task(&self, id: u64) -> Result<Option<PushTask>, SqliteError> {
         let con = self.db.lock().await;
@@ -655,6 +751,33 @@ impl PixivDownloaderSqlite {
         Ok(())
     }
 
+    fn _set_pixiv_artwork_tags(
+        ts: &Transaction,
+        id: u64,
+        tags: &[String],
+    ) -> Result<(), SqliteError> {
+        ts.execute("DELETE FROM pixiv_artwork_tags WHERE id = ?;", [id])?;
+        for tag in tags {
+            let tag_id: Option<i64> = ts
+                .query_row("SELECT id FROM tags WHERE name = ?;", [tag], |row| {
+                    row.get(0)
+                })
+                .optional()?;
+            let tag_id = match tag_id {
+                Some(tag_id) => tag_id,
+                None => {
+                    ts.execute("INSERT INTO tags (name) VALUES (?);", [tag])?;
+                    ts.last_insert_rowid()
+                }
+            };
+            ts.execute(
+                "INSERT INTO pixiv_artwork_tags (id, tag_id) VALUES (?, ?);",
+                (id, tag_id),
+            )?;
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "server")]
     fn _set_push_task_data(
         ts: &Transaction,
@@ -743,6 +866,48 @@ impl PixivDownloaderSqlite {
         }
     }
 
+    fn _update_pixiv_artwork(
+        ts: &Transaction,
+        id: u64,
+        title: &str,
+        author: &str,
+        uid: u64,
+        description: &str,
+        count: u64,
+        is_nsfw: bool,
+        lock: &FlagSet<PixivArtworkLock>,
+    ) -> Result<(), SqliteError> {
+        ts.execute(
+            "UPDATE pixiv_artworks SET uid = ?, count = ?, lock = ? WHERE id = ?;",
+            (uid, count, lock.bits(), id),
+        )?;
+        if !lock.contains(PixivArtworkLock::Title) {
+            ts.execute(
+                "UPDATE pixiv_artworks SET title = ? WHERE id = ?;",
+                (title, id),
+            )?;
+        }
+        if !lock.contains(PixivArtworkLock::Author) {
+            ts.execute(
+                "UPDATE pixiv_artworks SET author = ? WHERE id = ?;",
+                (author, id),
+            )?;
+        }
+        if !lock.contains(PixivArtworkLock::Description) {
+            ts.execute(
+                "UPDATE pixiv_artworks SET description = ? WHERE id = ?;",
+                (description, id),
+            )?;
+        }
+        if !lock.contains(PixivArtworkLock::IsNsfw) {
+            ts.execute(
+                "UPDATE pixiv_artworks SET is_nsfw = ? WHERE id = ?;",
+                (is_nsfw, id),
+            )?;
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "server")]
     fn _update_push_task(
         tx: &Transaction,
@@ -910,22 +1075,64 @@ impl PixivDownloaderDb for PixivDownloaderSqlite {
         {
             let mut con = self.db.lock().await;
             let mut ts = con.transaction()?;
-            Self::_add_pixiv_artwork(
-                &mut ts,
-                id,
-                title,
-                author,
-                uid,
-                description,
-                count,
-                is_nsfw,
-                lock,
-            )?;
+            let old_lock: Option<u8> = ts
+                .query_row(
+                    "SELECT lock FROM pixiv_artworks WHERE id = ?;",
+                    [id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match old_lock {
+                Some(old_lock) => {
+                    // Parts locked before should be kept.
+                    let lock = FlagSet::<PixivArtworkLock>::new_truncated(old_lock) | *lock;
+                    Self::_update_pixiv_artwork(
+                        &mut ts,
+                        id,
+                        title,
+                        author,
+                        uid,
+                        description,
+                        count,
+                        is_nsfw,
+                        &lock,
+                    )?;
+                }
+                None => {
+                    Self::_add_pixiv_artwork(
+                        &mut ts,
+                        id,
+                        title,
+                        author,
+                        uid,
+                        description,
+                        count,
+                        is_nsfw,
+                        lock,
+                    )?;
+                }
+            }
             ts.commit()?;
         }
         Ok(self.get_pixiv_artwork(id).await?.expect("User not found:"))
     }
 
+    async fn add_pixiv_file(
+        &self,
+        id: u64,
+        page: u64,
+        path: &str,
+        url: &str,
+        last_modified: &DateTime<Utc>,
+        sha256: Option<&str>,
+    ) -> Result<(), PixivDownloaderDbError> {
+        let mut con = self.db.lock().await;
+        let ts = con.transaction()?;
+        Self::_add_pixiv_file(&ts, id, page, path, url, last_modified, sha256)?;
+        ts.commit()?;
+        Ok(())
+    }
+
     #[cfg(feature = "server")]
     async fn add_push_task(
         &self,
@@ -1001,6 +1208,14 @@ impl PixivDownloaderDb for PixivDownloaderSqlite {
             .expect("User not found:"))
     }
 
+    async fn delete_pixiv_file(&self, id: u64, page: u64) -> Result<(), PixivDownloaderDbError> {
+        let mut db = self.db.lock().await;
+        let tx = db.transaction()?;
+        Self::_delete_pixiv_file(&tx, id, page)?;
+        tx.commit()?;
+        Ok(())
+    }
+
     #[cfg(feature = "server")]
     async fn delete_tmp_cache(&self, url: &str) -> Result<(), PixivDownloaderDbError> {
         let mut db = self.db.lock().await;
@@ -1083,6 +1298,14 @@ impl PixivDownloaderDb for PixivDownloaderSqlite {
         Ok(self.get_pixiv_artwork(id).await?)
     }
 
+    async fn get_all_pixiv_files(&self) -> Result<Vec<PixivFile>, PixivDownloaderDbError> {
+        Ok(self.get_all_pixiv_files().await?)
+    }
+
+    async fn get_pixiv_files(&self, id: u64) -> Result<Vec<PixivFile>, PixivDownloaderDbError> {
+        Ok(self.get_pixiv_files(id).await?)
+    }
+
     #[cfg(feature = "server")]
     async fn get_push_task(&self, id: u64) -> Result<Option<PushTask>, PixivDownloaderDbError> {
         Ok(self.get_push_task(id).await?)
@@ -1175,6 +1398,18 @@ impl PixivDownloaderDb for PixivDownloaderSqlite {
         Ok(())
     }
 
+    async fn set_pixiv_artwork_tags(
+        &self,
+        id: u64,
+        tags: &[String],
+    ) -> Result<(), PixivDownloaderDbError> {
+        let mut db = self.db.lock().await;
+        let tx = db.transaction()?;
+        Self::_set_pixiv_artwork_tags(&tx, id, tags)?;
+        tx.commit()?;
+        Ok(())
+    }
+
     #[cfg(feature = "server")]
     async fn set_user(
         &self,
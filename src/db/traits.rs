@@ -1,6 +1,6 @@
 use super::PixivDownloaderDbConfig;
 use super::PixivDownloaderDbError;
-use super::{PixivArtwork, PixivArtworkLock};
+use super::{PixivArtwork, PixivArtworkLock, PixivFile};
 #[cfg(feature = "server")]
 use super::{PushConfig, PushTask, PushTaskConfig};
 #[cfg(feature = "server")]
@@ -39,6 +39,24 @@ pub trait PixivDownloaderDb {
         is_nsfw: bool,
         lock: &FlagSet<PixivArtworkLock>,
     ) -> Result<PixivArtwork, PixivDownloaderDbError>;
+    /// Add/Update a downloaded file of an artwork
+    /// * `id` - The artwork ID
+    /// * `page` - Page number, starts from 0
+    /// * `path` - The path of the file
+    /// * `url` - The URL of the file
+    /// * `last_modified` - The last modified time of the file
+    /// * `sha256` - The SHA-256 hash of the file
+    /// # Note
+    /// If the page is already recorded, the record must be replaced.
+    async fn add_pixiv_file(
+        &self,
+        id: u64,
+        page: u64,
+        path: &str,
+        url: &str,
+        last_modified: &DateTime<Utc>,
+        sha256: Option<&str>,
+    ) -> Result<(), PixivDownloaderDbError>;
     #[cfg(feature = "server")]
     /// Add a push task
     /// * `config` - The task's config
@@ -91,6 +109,10 @@ pub trait PixivDownloaderDb {
         password: &[u8],
         is_admin: bool,
     ) -> Result<User, PixivDownloaderDbError>;
+    /// Remove a downloaded file of an artwork from download history
+    /// * `id` - The artwork ID
+    /// * `page` - Page number, starts from 0
+    async fn delete_pixiv_file(&self, id: u64, page: u64) -> Result<(), PixivDownloaderDbError>;
     #[cfg(feature = "server")]
     /// Delete tmp cache entry
     /// * `url` - URL
@@ -134,6 +156,11 @@ pub trait PixivDownloaderDb {
         &self,
         id: u64,
     ) -> Result<Option<PixivArtwork>, PixivDownloaderDbError>;
+    /// Get all downloaded files of all artworks, ordered by artwork ID and page number
+    async fn get_all_pixiv_files(&self) -> Result<Vec<PixivFile>, PixivDownloaderDbError>;
+    /// Get all downloaded files of an artwork, ordered by page number
+    /// * `id` - The artwork ID
+    async fn get_pixiv_files(&self, id: u64) -> Result<Vec<PixivFile>, PixivDownloaderDbError>;
     #[cfg(feature = "server")]
     /// Get proxy pixiv secrets
     async fn get_proxy_pixiv_secrets(&self) -> Result<String, PixivDownloaderDbError> {
@@ -216,6 +243,14 @@ pub trait PixivDownloaderDb {
     /// * `key` - The config key
     /// * `value` - The config value
     async fn set_config(&self, key: &str, value: &str) -> Result<(), PixivDownloaderDbError>;
+    /// Set the tags of an artwork. Old tags will be removed.
+    /// * `id` - The artwork ID
+    /// * `tags` - The tags' names
+    async fn set_pixiv_artwork_tags(
+        &self,
+        id: u64,
+        tags: &[String],
+    ) -> Result<(), PixivDownloaderDbError>;
     #[cfg(feature = "server")]
     /// Set a user's information by ID
     /// * `id`: The user's ID
@@ -1,21 +1,32 @@
 #[cfg(feature = "avdict")]
 use crate::avdict::AVDict;
 use crate::concat_pixiv_downloader_error;
-use crate::data::data::PixivData;
+use crate::data::data::{PixivArtworkContext, PixivData, PixivRankingEntry, PixivSeriesChapter};
 #[cfg(feature = "exif")]
 use crate::data::exif::add_exifdata_to_image;
+use crate::data::exif_data_source::ExifDataSource;
 use crate::data::fanbox::FanboxData;
 use crate::data::json::JSONDataFile;
 #[cfg(feature = "ugoira")]
 use crate::data::video::get_video_metadata;
 use crate::data::video::get_video_metas;
+use crate::data::xmp::write_xmp_sidecar;
 #[cfg(feature = "db")]
 use crate::db::open_and_init_database;
+#[cfg(feature = "db")]
+use crate::db::PixivDownloaderDb;
+#[cfg(feature = "db")]
+use crate::downloader::verify::file_sha256;
+use crate::downloader::verify::write_sha256_file;
 use crate::downloader::Downloader;
+use crate::downloader::DownloaderAction;
 use crate::downloader::DownloaderHelper;
 use crate::downloader::DownloaderResult;
 use crate::downloader::LocalFile;
 use crate::error::PixivDownloaderError;
+use crate::events;
+use crate::ext::json::ToJson;
+use crate::ext::rw_lock::GetRwLock;
 use crate::ext::try_err::TryErr;
 use crate::ext::try_err::TryErr4;
 use crate::fanbox::article::block::FanboxArticleBlock;
@@ -23,30 +34,57 @@ use crate::fanbox::article::url_embed::FanboxArticleUrlEmbed;
 use crate::fanbox::check::CheckUnknown;
 use crate::fanbox::creator::FanboxCreator;
 use crate::fanbox::creator::FanboxProfileItem;
+use crate::fanbox::item::FanboxItem;
 use crate::fanbox::post::FanboxPost;
 use crate::fanbox_api::FanboxClient;
 use crate::gettext;
+use crate::opt::image_size::PixivImageSize;
+use crate::opt::page_range::PageRange;
+use crate::opt::path_template::{PathTemplate, PathTemplateContext};
+use crate::opt::ranking::{PixivRankingContent, PixivRankingMode};
+use crate::opt::search::{PixivSearchParams, PixivSearchSort};
+use crate::opt::ugoira_format::UgoiraFormat;
+use crate::opt::work_type::{PixivWorkType, PixivWorkTypes};
 use crate::opthelper::get_helper;
+use crate::parser::novel::{
+    convert_novel_to_md, convert_novel_to_text, get_novel_images, parse_novel, NovelImage,
+};
 use crate::pixiv_app::PixivAppClient;
+use crate::pixiv_app::{PixivRestrictLessType, PixivRestrictType};
+use crate::pixiv_link::FanboxFeed;
 use crate::pixiv_link::FanboxPostID;
+use crate::pixiv_link::PixivBookmarksID;
 use crate::pixiv_link::PixivID;
+use crate::pixiv_link::PixivRankingID;
+use crate::pixiv_link::PixivSearchID;
+use crate::pixiv_link::PixivSeriesID;
+use crate::pixiv_link::PixivUserID;
 use crate::pixiv_web::PixivWebClient;
 use crate::task_manager::get_progress_bar;
 use crate::task_manager::TaskManager;
-use crate::ugoira::convert_ugoira_to_mp4_subprocess;
 #[cfg(feature = "ugoira")]
-use crate::ugoira::{convert_ugoira_to_mp4, UgoiraFrames};
+use crate::ugoira::{convert_ugoira_to_animation, convert_ugoira_to_mp4, UgoiraFrames};
+use crate::ugoira::{convert_ugoira_to_animation_subprocess, convert_ugoira_to_mp4_subprocess};
 use crate::utils::get_file_name_from_url;
+use crate::utils::parse_pixiv_id;
 use crate::Main;
+#[cfg(feature = "db")]
+use chrono::Utc;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+#[cfg(feature = "db")]
+use flagset::FlagSet;
 use indicatif::MultiProgress;
 use json::JsonValue;
 use proc_macros::print_error;
 use wreq::IntoUrl;
+use std::collections::HashMap;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 use std::ops::Deref;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 impl Main {
     pub async fn download(&mut self) -> i32 {
@@ -64,11 +102,26 @@ impl Main {
         let ac = PixivAppClient::with_db(Some(db));
         let tasks = TaskManager::new_post();
         let download_multiple_posts = get_helper().download_multiple_posts();
-        for id in self.cmd.as_ref().unwrap().ids.iter() {
-            match id {
+        let unavailable_bookmarks = Arc::new(RwLock::new(Vec::new()));
+        let mut re = 0;
+        for pid in self.cmd.as_ref().unwrap().ids.iter() {
+            match pid {
                 PixivID::Artwork(id) => {
                     tasks
-                        .add_task(download_artwork(ac.clone(), Arc::clone(&pw), id.clone()))
+                        .add_task(download_artwork(
+                            ac.clone(),
+                            Arc::clone(&pw),
+                            id.clone(),
+                            PixivArtworkContext::default(),
+                        ))
+                        .await;
+                    if !download_multiple_posts {
+                        tasks.join().await;
+                    }
+                }
+                PixivID::Novel(id) => {
+                    tasks
+                        .add_task(download_novel(ac.clone(), Arc::clone(&pw), id.clone()))
                         .await;
                     if !download_multiple_posts {
                         tasks.join().await;
@@ -109,21 +162,271 @@ impl Main {
                             log::warn!("{}", gettext("Warning: Fanbox client is not logged in."));
                         }
                     }
+                    let target = pid.to_json();
+                    let creator_info =
+                        download_fanbox_creator_info(Arc::clone(&fc), id.to_owned(), None, None);
                     tasks
-                        .add_task(download_fanbox_creator_info(
-                            Arc::clone(&fc),
-                            id.to_owned(),
-                            None,
-                            None,
-                        ))
+                        .add_task(async move {
+                            let re = creator_info.await;
+                            if let Err(e) = &re {
+                                events::emit_error(target, e);
+                            }
+                            re
+                        })
                         .await;
                     if !download_multiple_posts {
                         tasks.join().await;
                     }
+                    if get_helper().fanbox_creator_posts() {
+                        let posts = match get_fanbox_creator_posts(&fc, id).await {
+                            Ok(posts) => posts,
+                            Err(e) => {
+                                log::error!("{} {}", gettext("Failed to get creator's posts:"), e);
+                                events::emit_error(pid.to_json(), &e);
+                                re = 1;
+                                continue;
+                            }
+                        };
+                        for post in posts {
+                            tasks
+                                .add_task(download_fanbox_post(Arc::clone(&fc), post))
+                                .await;
+                            if !download_multiple_posts {
+                                tasks.join().await;
+                            }
+                        }
+                    }
+                }
+                PixivID::FanboxFeed(feed) => {
+                    if !fc.is_inited() {
+                        let helper = get_helper();
+                        if !fc.init(helper.cookies()) {
+                            log::error!("{}", gettext("Failed to initialize fanbox api client."));
+                            return 1;
+                        }
+                        if !fc.check_login().await {
+                            return 1;
+                        }
+                        if !fc.logined() {
+                            log::warn!("{}", gettext("Warning: Fanbox client is not logged in."));
+                        }
+                    }
+                    let posts = match get_fanbox_feed_posts(&fc, *feed).await {
+                        Ok(posts) => posts,
+                        Err(e) => {
+                            log::error!(
+                                "{} {}",
+                                gettext("Failed to get the posts in fanbox feed:"),
+                                e
+                            );
+                            events::emit_error(pid.to_json(), &e);
+                            re = 1;
+                            continue;
+                        }
+                    };
+                    log::info!(
+                        "{}",
+                        gettext("Found <count> new posts in fanbox feed.")
+                            .replace("<count>", &format!("{}", posts.len()))
+                    );
+                    for post in posts {
+                        tasks
+                            .add_task(download_fanbox_post(Arc::clone(&fc), post))
+                            .await;
+                        if !download_multiple_posts {
+                            tasks.join().await;
+                        }
+                    }
+                }
+                PixivID::User(id) => {
+                    let ids = match get_user_artworks(ac.clone(), Arc::clone(&pw), id).await {
+                        Ok(ids) => ids,
+                        Err(e) => {
+                            log::error!("{} {}", gettext("Failed to get user's works:"), e);
+                            events::emit_error(pid.to_json(), &e);
+                            re = 1;
+                            continue;
+                        }
+                    };
+                    for id in ids {
+                        tasks
+                            .add_task(download_artwork(
+                                ac.clone(),
+                                Arc::clone(&pw),
+                                id,
+                                PixivArtworkContext::default(),
+                            ))
+                            .await;
+                        if !download_multiple_posts {
+                            tasks.join().await;
+                        }
+                    }
+                }
+                PixivID::Search(id) => {
+                    let ids = match get_search_artworks(ac.clone(), Arc::clone(&pw), id).await {
+                        Ok(ids) => ids,
+                        Err(e) => {
+                            log::error!("{} {}", gettext("Failed to search artworks:"), e);
+                            events::emit_error(pid.to_json(), &e);
+                            re = 1;
+                            continue;
+                        }
+                    };
+                    log::info!(
+                        "{}",
+                        gettext("Found <count> artworks to download.")
+                            .replace("<count>", &format!("{}", ids.len()))
+                    );
+                    for id in ids {
+                        tasks
+                            .add_task(download_artwork(
+                                ac.clone(),
+                                Arc::clone(&pw),
+                                id,
+                                PixivArtworkContext::default(),
+                            ))
+                            .await;
+                        if !download_multiple_posts {
+                            tasks.join().await;
+                        }
+                    }
+                }
+                PixivID::Ranking(id) => {
+                    let helper = get_helper();
+                    let start = helper.ranking_date().or(id.date);
+                    let dates = match (start, helper.ranking_end_date()) {
+                        (Some(start), Some(end)) => {
+                            if end < start {
+                                log::error!(
+                                    "{}",
+                                    gettext(
+                                        "The end date of ranking is earlier than the start date."
+                                    )
+                                );
+                                re = 1;
+                                continue;
+                            }
+                            start
+                                .iter_days()
+                                .take_while(|d| *d <= end)
+                                .map(Some)
+                                .collect()
+                        }
+                        (None, Some(_)) => {
+                            log::error!(
+                                "{}",
+                                gettext("The start date of ranking is required when end date is specified.")
+                            );
+                            re = 1;
+                            continue;
+                        }
+                        (start, None) => vec![start],
+                    };
+                    for date in dates {
+                        let entries =
+                            match get_ranking_artworks(ac.clone(), Arc::clone(&pw), id, date).await
+                            {
+                                Ok(entries) => entries,
+                                Err(e) => {
+                                    log::error!("{} {}", gettext("Failed to get ranking:"), e);
+                                    events::emit_error(pid.to_json(), &e);
+                                    re = 1;
+                                    continue;
+                                }
+                            };
+                        log::info!(
+                            "{}",
+                            gettext("Found <count> artworks to download.")
+                                .replace("<count>", &format!("{}", entries.len()))
+                        );
+                        for (id, entry) in entries {
+                            tasks
+                                .add_task(download_artwork(
+                                    ac.clone(),
+                                    Arc::clone(&pw),
+                                    id,
+                                    PixivArtworkContext {
+                                        ranking: Some(entry),
+                                        ..Default::default()
+                                    },
+                                ))
+                                .await;
+                            if !download_multiple_posts {
+                                tasks.join().await;
+                            }
+                        }
+                    }
+                }
+                PixivID::Series(id) => {
+                    let series = match get_series(Arc::clone(&pw), id).await {
+                        Ok(series) => series,
+                        Err(e) => {
+                            log::error!("{} {}", gettext("Failed to get series's works:"), e);
+                            events::emit_error(pid.to_json(), &e);
+                            re = 1;
+                            continue;
+                        }
+                    };
+                    let base = PathBuf::from(get_helper().download_base());
+                    if !get_helper().dry_run() {
+                        if let Err(e) = series.save(&base) {
+                            log::error!("{} {}", gettext("Failed to save series's data:"), e);
+                            events::emit_error(pid.to_json(), &e);
+                            re = 1;
+                        }
+                    }
+                    for work in series.works {
+                        tasks
+                            .add_task(download_artwork(
+                                ac.clone(),
+                                Arc::clone(&pw),
+                                work.id,
+                                PixivArtworkContext {
+                                    series: Some(work.chapter),
+                                    ..Default::default()
+                                },
+                            ))
+                            .await;
+                        if !download_multiple_posts {
+                            tasks.join().await;
+                        }
+                    }
+                }
+                PixivID::Bookmarks(id) => {
+                    let bookmarks = match get_user_bookmarks(ac.clone(), Arc::clone(&pw), id).await
+                    {
+                        Ok(bookmarks) => bookmarks,
+                        Err(e) => {
+                            log::error!("{} {}", gettext("Failed to get user's bookmarks:"), e);
+                            events::emit_error(pid.to_json(), &e);
+                            re = 1;
+                            continue;
+                        }
+                    };
+                    log::info!(
+                        "{}",
+                        gettext("Found <count> bookmarked artworks to download.")
+                            .replace("<count>", &format!("{}", bookmarks.ids.len()))
+                    );
+                    unavailable_bookmarks
+                        .get_mut()
+                        .extend(bookmarks.unavailable);
+                    for id in bookmarks.ids {
+                        tasks
+                            .add_task(download_bookmarked_artwork(
+                                ac.clone(),
+                                Arc::clone(&pw),
+                                id,
+                                Arc::clone(&unavailable_bookmarks),
+                            ))
+                            .await;
+                        if !download_multiple_posts {
+                            tasks.join().await;
+                        }
+                    }
                 }
             }
         }
-        let mut re = 0;
         tasks.join().await;
         let tasks = tasks.take_finished_tasks();
         for task in tasks {
@@ -139,6 +442,18 @@ impl Main {
                 }
             }
         }
+        let unavailable_bookmarks = unavailable_bookmarks.get_ref();
+        if !unavailable_bookmarks.is_empty() {
+            log::warn!(
+                "{} {}",
+                gettext("Skipped bookmarked artworks which are deleted or hidden:"),
+                unavailable_bookmarks
+                    .iter()
+                    .map(|id| format!("{}", id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
         re
     }
 
@@ -152,17 +467,27 @@ impl Main {
             let base = Arc::clone(&base);
             tasks
                 .add_task(async move {
-                    let d = DownloaderHelper::builder(url)?.build();
-                    download_file(
-                        d,
-                        if enable_multi_progress_bar {
-                            Some(get_progress_bar())
-                        } else {
-                            None
-                        },
-                        base.clone(),
-                    )
-                    .await
+                    let target = json::object! {"type": "url", "link": url.as_str()};
+                    let re = match DownloaderHelper::builder(url) {
+                        Ok(d) => {
+                            download_file(
+                                d.build(),
+                                if enable_multi_progress_bar {
+                                    Some(get_progress_bar())
+                                } else {
+                                    None
+                                },
+                                base.clone(),
+                                None,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(PixivDownloaderError::from(e)),
+                    };
+                    if let Err(e) = &re {
+                        events::emit_error(Some(target), e);
+                    }
+                    re
                 })
                 .await;
         }
@@ -186,6 +511,363 @@ impl Main {
     }
 }
 
+/// Print what would be done with the file in dry run mode.
+/// * `url` - The link of the file
+/// * `file_name` - The path of the target
+/// * `np` - Number of page in artworks
+fn print_dry_run_file(url: &str, file_name: &Path, np: Option<u16>) {
+    let action = DownloaderAction::predict(file_name, get_helper().overwrite());
+    let page = match np {
+        Some(np) => format!("{}", np),
+        None => String::from("-"),
+    };
+    let line = format!(
+        "{}\t{}\t{}\t{}",
+        action,
+        page,
+        url,
+        file_name.to_str().unwrap_or("(null)")
+    );
+    if events::events_to_stdout() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Wait the downloader. File events are written if the event stream is enabled.
+/// * `d` - The downloader
+/// * `url` - The link of the file
+/// * `file_name` - The path of the target
+async fn join_downloader(
+    d: &Downloader<LocalFile>,
+    url: &str,
+    file_name: &Path,
+) -> Result<(), PixivDownloaderError> {
+    if !events::events_enabled() {
+        d.join().await?;
+        return Ok(());
+    }
+    events::emit_file_started(url, file_name);
+    let mut join = d.join();
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            re = &mut join => {
+                re?;
+                break;
+            }
+            _ = interval.tick() => {
+                events::emit_file_progress(
+                    url,
+                    file_name,
+                    d.get_downloaded_file_size(),
+                    d.get_file_size(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write the SHA-256 hash of the file next to it if enabled.
+/// * `file_name` - The path of the file
+fn write_sha256_if_needed(file_name: &Path) {
+    if !get_helper().sha256_file() {
+        return;
+    }
+    if let Err(e) = write_sha256_file(file_name) {
+        log::warn!(
+            "{} {} {}",
+            gettext("Warning: Failed to write SHA-256 hash of the file:"),
+            file_name.to_str().unwrap_or("(null)"),
+            e
+        );
+    }
+}
+
+/// Write the metadata of the file to a XMP sidecar file if enabled.
+/// * `file_name` - The path of the file
+/// * `datas` - The source of the metadata
+fn write_xmp_sidecar_if_needed<D: ExifDataSource + ?Sized>(file_name: &Path, datas: &D) {
+    if !get_helper().xmp_sidecar() {
+        return;
+    }
+    if let Err(e) = write_xmp_sidecar(file_name, datas) {
+        log::warn!(
+            "{} {} {}",
+            gettext("Warning: Failed to write XMP sidecar file of the file:"),
+            file_name.to_str().unwrap_or("(null)"),
+            e
+        );
+    }
+}
+
+/// Create the parent directory of the file if it is not exists.
+fn create_parent_dir(file_name: &Path) -> Result<(), PixivDownloaderError> {
+    if let Some(parent) = file_name.parent() {
+        if !parent.exists() {
+            match create_dir_all(parent) {
+                Ok(_) => {}
+                Err(e) => {
+                    if !parent.exists() {
+                        return Err(PixivDownloaderError::from(e));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Get the base directory of the artwork. Per-author folder is added if enabled.
+/// Series and chapter folders are added if the artwork is downloaded as a part of manga series.
+/// * `base` - The download base
+/// * `datas` - The artwork's data
+fn get_artwork_base(base: &Path, datas: &PixivData) -> PathBuf {
+    let helper = get_helper();
+    let dir = get_series_dir(base, datas);
+    match &datas.series {
+        Some(series) => {
+            let width = format!("{}", series.total).len().max(2);
+            let t = PathTemplate::from_str(&format!("{{chapter:{}}}", width)).unwrap();
+            let ctx = PathTemplateContext {
+                data: datas,
+                page: None,
+                name: "",
+                ext: "",
+            };
+            dir.join(t.render(&ctx, helper.path_sanitize()))
+        }
+        None => dir,
+    }
+}
+
+/// Get the directory of the manga series. Returns the base directory of the author if the
+/// artwork is not a part of manga series.
+/// * `base` - The download base
+/// * `datas` - The artwork's data
+fn get_series_dir(base: &Path, datas: &PixivData) -> PathBuf {
+    let helper = get_helper();
+    let mut dir = base.to_owned();
+    let ctx = PathTemplateContext {
+        data: datas,
+        page: None,
+        name: "",
+        ext: "",
+    };
+    if helper.artwork_group_by_author() && (datas.author.is_some() || datas.author_id.is_some()) {
+        let t = PathTemplate::from_str("{author} ({uid})").unwrap();
+        dir = dir.join(t.render(&ctx, helper.path_sanitize()));
+    }
+    if datas.series.is_some() {
+        let t = PathTemplate::from_str("{series} ({series_id})").unwrap();
+        dir = dir.join(t.render(&ctx, helper.path_sanitize()));
+    }
+    dir
+}
+
+/// Get the directory to save the artwork's files which are not belong to a page, such as
+/// JSON metadata and converted ugoira video.
+/// * `base` - The download base
+/// * `datas` - The artwork's data
+pub fn get_artwork_dir(base: &Path, datas: &PixivData) -> PathBuf {
+    let helper = get_helper();
+    let dir = get_artwork_base(base, datas);
+    match helper.artwork_path_template() {
+        Some(t) => {
+            let id: Result<u64, ()> = (&datas.id).try_into();
+            let name = id.map(|id| format!("{}", id)).unwrap_or_default();
+            let ctx = PathTemplateContext {
+                data: datas,
+                page: None,
+                name: &name,
+                ext: "json",
+            };
+            match t.render(&ctx, helper.path_sanitize()).parent() {
+                Some(p) => dir.join(p),
+                None => dir,
+            }
+        }
+        None => dir,
+    }
+}
+
+/// Returns true if the JSON metadata of the artwork is already saved.
+/// * `base` - The download base
+/// * `datas` - The artwork's data
+pub fn is_artwork_downloaded(base: &Path, datas: &PixivData) -> bool {
+    let id: Result<u64, ()> = (&datas.id).try_into();
+    match id {
+        Ok(id) => get_artwork_dir(base, datas)
+            .join(format!("{}.json", id))
+            .exists(),
+        Err(_) => false,
+    }
+}
+
+/// Get the path to save a file of the artwork.
+/// * `base` - The download base
+/// * `datas` - The artwork's data
+/// * `file_name` - The original file name
+/// * `np` - Number of page in artworks
+pub fn get_artwork_file_path(base: &Path, datas: &PixivData, file_name: &str, np: u16) -> PathBuf {
+    let helper = get_helper();
+    let dir = get_artwork_base(base, datas);
+    match helper.artwork_path_template() {
+        Some(t) => {
+            let (name, ext) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+            let ctx = PathTemplateContext {
+                data: datas,
+                page: Some(np),
+                name,
+                ext,
+            };
+            dir.join(t.render(&ctx, helper.path_sanitize()))
+        }
+        None => dir.join(file_name),
+    }
+}
+
+/// The files of an artwork which are downloaded
+pub struct PixivDownloadedArtwork {
+    /// The artwork's data
+    pub datas: Arc<PixivData>,
+    /// The download base
+    pub base: Arc<PathBuf>,
+    /// Page number and link of the files
+    pub links: Vec<(u16, String)>,
+}
+
+#[cfg(feature = "db")]
+/// Returns true if the artwork and all its files are recorded in download history.
+/// * `db` - Database
+/// * `id` - Artwork's id
+pub async fn is_artwork_in_history(
+    db: &Box<dyn PixivDownloaderDb + Send + Sync>,
+    id: u64,
+) -> Result<bool, PixivDownloaderError> {
+    let artwork = match db.get_pixiv_artwork(id).await? {
+        Some(artwork) => artwork,
+        None => return Ok(false),
+    };
+    let files = db.get_pixiv_files(id).await?;
+    Ok(!files.is_empty() && files.len() as u64 >= artwork.count)
+}
+
+#[cfg(feature = "db")]
+/// Record a downloaded artwork and its files to download history.
+/// Partial downloads (only some pages or resized pictures) are not recorded,
+/// so they are not treated as downloaded in archive mode.
+/// * `db` - Database
+/// * `artwork` - The downloaded artwork
+pub async fn add_artwork_to_history(
+    db: &Box<dyn PixivDownloaderDb + Send + Sync>,
+    artwork: &PixivDownloadedArtwork,
+) -> Result<(), PixivDownloaderError> {
+    let datas = &artwork.datas;
+    let id: Result<u64, ()> = (&datas.id).try_into();
+    let id = id.try_err(gettext("Failed to get artwork's id."))?;
+    let count = datas.page_count.unwrap_or(artwork.links.len() as u64);
+    let resized = datas
+        .image_size
+        .is_some_and(|s| s != PixivImageSize::Original);
+    let partial = datas
+        .pages
+        .as_ref()
+        .is_some_and(|pages| (pages.len() as u64) < count);
+    if resized || partial {
+        // The recorded files may be rewritten when updating EXIF, keep their hashes up to date.
+        let files = db.get_pixiv_files(id).await?;
+        for (np, link) in artwork.links.iter() {
+            let path = get_history_file_path(artwork, *np, link)?;
+            if files
+                .iter()
+                .any(|f| f.page == *np as u64 && &f.url == link && Path::new(&f.path) == path)
+            {
+                add_file_to_history(db, id, *np, &path, link).await?;
+            }
+        }
+        log::debug!(
+            "{} {}",
+            gettext("Skipped adding partially downloaded artwork to download history:"),
+            id
+        );
+        return Ok(());
+    }
+    db.add_pixiv_artwork(
+        id,
+        datas.title.as_deref().unwrap_or(""),
+        datas.author.as_deref().unwrap_or(""),
+        datas.author_id.unwrap_or(0),
+        datas.description.as_deref().unwrap_or(""),
+        count,
+        datas.x_restrict.is_some_and(|x| x > 0),
+        &FlagSet::default(),
+    )
+    .await?;
+    for (np, link) in artwork.links.iter() {
+        let path = get_history_file_path(artwork, *np, link)?;
+        add_file_to_history(db, id, *np, &path, link).await?;
+    }
+    if let Some(tags) = &datas.tags {
+        let tags: Vec<_> = tags.iter().map(|(t, _)| t.clone()).collect();
+        db.set_pixiv_artwork_tags(id, &tags).await?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "db")]
+/// Returns the path of a downloaded file of the artwork.
+/// * `artwork` - The downloaded artwork
+/// * `np` - Page number
+/// * `link` - The link of the file
+fn get_history_file_path(
+    artwork: &PixivDownloadedArtwork,
+    np: u16,
+    link: &str,
+) -> Result<PathBuf, PixivDownloaderError> {
+    let file_name = get_file_name_from_url(link).try_err(format!(
+        "{} {}",
+        gettext("Failed to get file name from url:"),
+        link
+    ))?;
+    Ok(get_artwork_file_path(
+        &artwork.base,
+        &artwork.datas,
+        &file_name,
+        np,
+    ))
+}
+
+#[cfg(feature = "db")]
+/// Record a downloaded file to download history with its current hash.
+/// * `db` - Database
+/// * `id` - Artwork's id
+/// * `np` - Page number
+/// * `path` - The path of the file
+/// * `link` - The link of the file
+async fn add_file_to_history(
+    db: &Box<dyn PixivDownloaderDb + Send + Sync>,
+    id: u64,
+    np: u16,
+    path: &Path,
+    link: &str,
+) -> Result<(), PixivDownloaderError> {
+    let last_modified = std::fs::metadata(path)?.modified()?;
+    let sha256 = file_sha256(path)?;
+    db.add_pixiv_file(
+        id,
+        np as u64,
+        &path.to_string_lossy(),
+        link,
+        &DateTime::<Utc>::from(last_modified),
+        Some(&sha256),
+    )
+    .await?;
+    Ok(())
+}
+
 /// Download artwork link
 /// * `link` - Link
 /// * `np` - Number of page in artworks
@@ -204,8 +886,14 @@ pub async fn download_artwork_link<L: IntoUrl + Clone>(
         gettext("Failed to get file name from url:"),
         link.as_str()
     ))?;
-    let file_name = base.join(file_name);
+    let file_name = get_artwork_file_path(&base, &datas, &file_name, np);
     let helper = get_helper();
+    if helper.dry_run() {
+        print_dry_run_file(link.as_str(), &file_name, Some(np));
+        return Ok(());
+    }
+    create_parent_dir(&file_name)?;
+    let url = link.as_str().to_owned();
     let downloader = Downloader::<LocalFile>::new(
         link,
         json::object! {"referer": "https://www.pixiv.net/"},
@@ -216,7 +904,7 @@ pub async fn download_artwork_link<L: IntoUrl + Clone>(
         DownloaderResult::Ok(d) => {
             d.handle_options(&helper, progress_bars);
             d.download();
-            d.join().await?;
+            join_downloader(&d, &url, &file_name).await?;
             if d.is_downloaded() {
                 #[cfg(feature = "exif")]
                 {
@@ -228,6 +916,9 @@ pub async fn download_artwork_link<L: IntoUrl + Clone>(
                         );
                     }
                 }
+                write_sha256_if_needed(&file_name);
+                write_xmp_sidecar_if_needed(&file_name, &datas);
+                events::emit_file_completed(&url, &file_name);
             } else if d.is_panic() {
                 return Err(PixivDownloaderError::from(
                     d.get_panic()
@@ -236,6 +927,10 @@ pub async fn download_artwork_link<L: IntoUrl + Clone>(
             }
         }
         DownloaderResult::Canceled => {
+            events::emit_file_skipped(&url, &file_name);
+            if file_name.exists() {
+                write_xmp_sidecar_if_needed(&file_name, &datas);
+            }
             #[cfg(feature = "exif")]
             {
                 if helper.update_exif() && file_name.exists() {
@@ -245,6 +940,8 @@ pub async fn download_artwork_link<L: IntoUrl + Clone>(
                             gettext("Failed to add exif data to image:"),
                             file_name.to_str().unwrap_or("(null)")
                         );
+                    } else {
+                        write_sha256_if_needed(&file_name);
                     }
                 }
             }
@@ -253,37 +950,885 @@ pub async fn download_artwork_link<L: IntoUrl + Clone>(
     Ok(())
 }
 
+/// Check the artwork with the filter rules. Returns true if the artwork should be skipped.
+/// * `datas` - The artwork's data
+fn is_artwork_filtered(datas: &PixivData) -> bool {
+    match get_helper().artwork_filter().check(datas) {
+        Some(reason) => {
+            log::info!(
+                "{} {} ({})",
+                gettext("Skipped the artwork which is filtered out:"),
+                datas.id.to_link(),
+                reason
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Download an artwork
+/// * `ac` - Pixiv APP client
+/// * `pw` - Pixiv web client
+/// * `id` - Artwork's id
+/// * `ctx` - Where the artwork is found
 pub async fn download_artwork(
     ac: PixivAppClient,
     pw: Arc<PixivWebClient>,
     id: u64,
+    ctx: PixivArtworkContext,
+) -> Result<(), PixivDownloaderError> {
+    let re = _download_artwork(ac, pw, id, ctx).await;
+    if let Err(e) = &re {
+        events::emit_error(PixivID::Artwork(id).to_json(), e);
+    }
+    re
+}
+
+async fn _download_artwork(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    id: u64,
+    ctx: PixivArtworkContext,
 ) -> Result<(), PixivDownloaderError> {
     let helper = get_helper();
+    #[cfg(feature = "db")]
+    let db = ac.db();
+    #[cfg(feature = "db")]
+    {
+        if helper.archive() {
+            if let Some(db) = &db {
+                match is_artwork_in_history(db, id).await {
+                    Ok(true) => {
+                        log::info!(
+                            "{} {}",
+                            gettext("Skipped the artwork which is already in download history:"),
+                            id
+                        );
+                        return Ok(());
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        log::warn!(
+                            "{} {}",
+                            gettext("Warning: Failed to check download history:"),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+    }
     let app_ok = helper.refresh_token().is_some();
-    if app_ok && helper.use_app_api() {
-        if let Err(e) = download_artwork_app(ac, pw.clone(), id).await {
-            if e.is_not_found() {
-                return Err(e);
+    let artwork = if app_ok && helper.use_app_api() {
+        match download_artwork_app(ac, pw.clone(), id, ctx.clone()).await {
+            Ok(artwork) => artwork,
+            Err(e) => {
+                if e.is_not_found() {
+                    return Err(e);
+                }
+                log::warn!("{}{}", gettext("Warning: Failed to download artwork with app api, trying to download with web api: "), e);
+                download_artwork_web(pw.clone(), id, ctx).await?
             }
-            log::warn!("{}{}", gettext("Warning: Failed to download artwork with app api, trying to download with web api: "), e);
-            download_artwork_web(pw.clone(), id).await?;
         }
     } else if app_ok {
-        if let Err(_) = download_artwork_web(pw.clone(), id).await {
-            download_artwork_app(ac, pw.clone(), id).await?;
+        match download_artwork_web(pw.clone(), id, ctx.clone()).await {
+            Ok(artwork) => artwork,
+            Err(_) => download_artwork_app(ac, pw.clone(), id, ctx).await?,
         }
     } else {
-        download_artwork_web(pw, id).await?;
+        download_artwork_web(pw, id, ctx).await?
+    };
+    #[cfg_attr(not(feature = "db"), allow(unused_variables))]
+    let artwork = match artwork {
+        Some(artwork) => artwork,
+        None => return Ok(()),
+    };
+    #[cfg(feature = "db")]
+    {
+        if let Some(db) = &db {
+            if !helper.dry_run() {
+                if let Err(e) = add_artwork_to_history(db, &artwork).await {
+                    log::warn!(
+                        "{} {}",
+                        gettext("Warning: Failed to add artwork to download history:"),
+                        e
+                    );
+                }
+            }
+        }
     }
     Ok(())
 }
 
+/// Get the ids of user's artworks, newest first.
+/// * `ac` - Pixiv APP client
+/// * `pw` - Pixiv web client
+/// * `user` - The user
+pub async fn get_user_artworks(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    user: &PixivUserID,
+) -> Result<Vec<u64>, PixivDownloaderError> {
+    let helper = get_helper();
+    let mut types = helper.user_work_types();
+    if let Some(t) = &user.types {
+        types = types.intersect(t);
+    }
+    if types.is_empty() {
+        return Ok(Vec::new());
+    }
+    let max = helper.user_max_works();
+    if helper.refresh_token().is_some() && helper.use_app_api() {
+        match get_user_artworks_app(ac, user.uid, &types, max).await {
+            Ok(ids) => return Ok(ids),
+            Err(e) => {
+                log::warn!(
+                    "{} {}",
+                    gettext("Warning: Failed to get user's works with app api, trying to get with web api:"),
+                    e
+                );
+            }
+        }
+    }
+    get_user_artworks_web(pw, user.uid, &types, max).await
+}
+
+/// Get the ids of user's artworks with app api, newest first.
+async fn get_user_artworks_app(
+    ac: PixivAppClient,
+    uid: u64,
+    types: &PixivWorkTypes,
+    max: Option<usize>,
+) -> Result<Vec<u64>, PixivDownloaderError> {
+    let mut ids = Vec::new();
+    for typ in ["illust", "manga"] {
+        if typ == "illust"
+            && !types.contains(PixivWorkType::Illust)
+            && !types.contains(PixivWorkType::Ugoira)
+        {
+            continue;
+        }
+        if typ == "manga" && !types.contains(PixivWorkType::Manga) {
+            continue;
+        }
+        let mut count = 0;
+        let mut illusts = ac.get_user_illusts(uid, typ).await?;
+        loop {
+            for illust in illusts.illusts.iter() {
+                let t = illust.typ().and_then(|t| PixivWorkType::from_str(t).ok());
+                match (illust.id(), t) {
+                    (Some(id), Some(t)) if types.contains(t) => {
+                        if max.is_some_and(|m| count >= m) {
+                            break;
+                        }
+                        ids.push(id);
+                        count += 1;
+                    }
+                    _ => {}
+                }
+            }
+            if max.is_some_and(|m| count >= m) || !illusts.has_next_page() {
+                break;
+            }
+            illusts = illusts.get_next_page().await?;
+        }
+    }
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    ids.dedup();
+    if let Some(max) = max {
+        ids.truncate(max);
+    }
+    Ok(ids)
+}
+
+/// Get the ids of user's artworks with web api, newest first.
+async fn get_user_artworks_web(
+    pw: Arc<PixivWebClient>,
+    uid: u64,
+    types: &PixivWorkTypes,
+    max: Option<usize>,
+) -> Result<Vec<u64>, PixivDownloaderError> {
+    let data = pw
+        .get_user_works(uid)
+        .await
+        .try_err(gettext("Failed to get user's works."))?;
+    let parse_ids = |obj: &JsonValue| {
+        let mut ids = obj
+            .entries()
+            .filter_map(|(k, _)| k.parse::<u64>().ok())
+            .collect::<Vec<_>>();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        ids
+    };
+    let mut ids = Vec::new();
+    let with_illust = types.contains(PixivWorkType::Illust);
+    let with_ugoira = types.contains(PixivWorkType::Ugoira);
+    if with_illust && with_ugoira {
+        ids.extend(parse_ids(&data["illusts"]));
+    } else if with_illust || with_ugoira {
+        // The list contains both illusts and ugoira, need get their types.
+        let illusts = parse_ids(&data["illusts"]);
+        let mut count = 0;
+        for chunk in illusts.chunks(48) {
+            let info = pw
+                .get_user_illusts_info(uid, chunk)
+                .await
+                .try_err(gettext("Failed to get user's illusts."))?;
+            for id in chunk {
+                let t = info[format!("{}", id)]["illustType"]
+                    .as_i64()
+                    .and_then(PixivWorkType::from_illust_type);
+                match t {
+                    Some(t) if types.contains(t) => {
+                        ids.push(*id);
+                        count += 1;
+                    }
+                    _ => {}
+                }
+            }
+            if max.is_some_and(|m| count >= m) {
+                break;
+            }
+        }
+    }
+    if types.contains(PixivWorkType::Manga) {
+        ids.extend(parse_ids(&data["manga"]));
+    }
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    ids.dedup();
+    if let Some(max) = max {
+        ids.truncate(max);
+    }
+    Ok(ids)
+}
+
+/// Get the parameters to search artworks. Command line options override the options in link.
+/// * `search` - The search
+fn get_search_params(search: &PixivSearchID) -> PixivSearchParams {
+    let helper = get_helper();
+    PixivSearchParams {
+        keywords: search.keywords.clone(),
+        match_type: helper
+            .search_match()
+            .or(search.match_type)
+            .unwrap_or_default(),
+        mode: helper.search_mode().or(search.mode).unwrap_or_default(),
+        sort: helper.search_sort().or(search.sort).unwrap_or_default(),
+        types: helper.search_types().or(search.types).unwrap_or_default(),
+        since: helper.search_since().or(search.since),
+        until: helper.search_until().or(search.until),
+    }
+}
+
+/// Search artworks and returns their ids.
+/// * `ac` - Pixiv APP client
+/// * `pw` - Pixiv web client
+/// * `search` - The search
+pub async fn get_search_artworks(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    search: &PixivSearchID,
+) -> Result<Vec<u64>, PixivDownloaderError> {
+    let helper = get_helper();
+    let mut params = get_search_params(search);
+    if params.types.is_empty() {
+        return Ok(Vec::new());
+    }
+    if params.sort == PixivSearchSort::Popular {
+        if !pw.is_login_checked() {
+            pw.check_login().await;
+        }
+        if !pw.is_premium() {
+            log::warn!(
+                "{}",
+                gettext("Warning: Popular order is only available for premium users, use newest order instead.")
+            );
+            params.sort = PixivSearchSort::Newest;
+        }
+    }
+    let max = helper.search_max();
+    if helper.refresh_token().is_some() && helper.use_app_api() {
+        match get_search_artworks_app(ac, &params, max).await {
+            Ok(ids) => return Ok(ids),
+            Err(e) => {
+                log::warn!(
+                    "{} {}",
+                    gettext("Warning: Failed to search artworks with app api, trying to search with web api:"),
+                    e
+                );
+            }
+        }
+    }
+    get_search_artworks_web(pw, &params, max).await
+}
+
+/// Search artworks with app api.
+async fn get_search_artworks_app(
+    ac: PixivAppClient,
+    params: &PixivSearchParams,
+    max: Option<usize>,
+) -> Result<Vec<u64>, PixivDownloaderError> {
+    let mut ids = Vec::new();
+    let mut illusts = ac.search_illust(params).await?;
+    loop {
+        for illust in illusts.illusts.iter() {
+            if max.is_some_and(|m| ids.len() >= m) {
+                return Ok(ids);
+            }
+            let t = illust.typ().and_then(|t| PixivWorkType::from_str(t).ok());
+            match (illust.id(), t) {
+                (Some(id), Some(t))
+                    if params.types.contains(t)
+                        && params.mode.matches(illust.x_restrict().unwrap_or(0)) =>
+                {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if max.is_some_and(|m| ids.len() >= m) || !illusts.has_next_page() {
+            break;
+        }
+        illusts = illusts.get_next_page().await?;
+    }
+    Ok(ids)
+}
+
+/// Search artworks with web api.
+async fn get_search_artworks_web(
+    pw: Arc<PixivWebClient>,
+    params: &PixivSearchParams,
+    max: Option<usize>,
+) -> Result<Vec<u64>, PixivDownloaderError> {
+    let mut ids = Vec::new();
+    let mut page = 1;
+    loop {
+        let data = pw
+            .search_artworks(params, page)
+            .await
+            .try_err(gettext("Failed to search artworks."))?;
+        let results = &data["illustManga"];
+        let mut count = 0;
+        for item in results["data"].members() {
+            if max.is_some_and(|m| ids.len() >= m) {
+                return Ok(ids);
+            }
+            // Ads have no id.
+            let id = match parse_pixiv_id(&item["id"]) {
+                Some(id) => id,
+                None => continue,
+            };
+            count += 1;
+            let t = item["illustType"]
+                .as_i64()
+                .and_then(PixivWorkType::from_illust_type);
+            if t.is_some_and(|t| params.types.contains(t))
+                && params.mode.matches(item["xRestrict"].as_u64().unwrap_or(0))
+                && !ids.contains(&id)
+            {
+                ids.push(id);
+            }
+        }
+        let last_page = results["lastPage"].as_u64().unwrap_or(page);
+        if count == 0 || page >= last_page || max.is_some_and(|m| ids.len() >= m) {
+            break;
+        }
+        page += 1;
+    }
+    Ok(ids)
+}
+
+/// Get the artworks in ranking and their positions.
+/// * `ac` - Pixiv APP client
+/// * `pw` - Pixiv web client
+/// * `ranking` - The ranking
+/// * `date` - The date of ranking. [None] means the latest one.
+pub async fn get_ranking_artworks(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    ranking: &PixivRankingID,
+    date: Option<NaiveDate>,
+) -> Result<Vec<(u64, PixivRankingEntry)>, PixivDownloaderError> {
+    let helper = get_helper();
+    let mode = ranking.mode.unwrap_or_default();
+    let content = helper
+        .ranking_content()
+        .or(ranking.content)
+        .unwrap_or_default();
+    let max = helper.ranking_max();
+    let use_app = helper.refresh_token().is_some() && helper.use_app_api();
+    if let (true, Some(app_mode)) = (use_app, mode.app_value(content)) {
+        match get_ranking_artworks_app(ac, app_mode, mode, content, date, max).await {
+            Ok(entries) => return Ok(entries),
+            Err(e) => {
+                log::warn!(
+                    "{} {}",
+                    gettext(
+                        "Warning: Failed to get ranking with app api, trying to get with web api:"
+                    ),
+                    e
+                );
+            }
+        }
+    }
+    if mode.is_r18() {
+        if !pw.is_login_checked() {
+            pw.check_login().await;
+        }
+        if !pw.logined() {
+            log::warn!(
+                "{}",
+                gettext("Warning: R-18 ranking is only available after login.")
+            );
+        }
+    }
+    get_ranking_artworks_web(pw, mode, content, date, max).await
+}
+
+/// Get ranking with app api.
+async fn get_ranking_artworks_app(
+    ac: PixivAppClient,
+    app_mode: &str,
+    mode: PixivRankingMode,
+    content: PixivRankingContent,
+    date: Option<NaiveDate>,
+    max: Option<usize>,
+) -> Result<Vec<(u64, PixivRankingEntry)>, PixivDownloaderError> {
+    let mut entries = Vec::new();
+    let mut rank = 0;
+    let mut illusts = ac.get_illust_ranking(app_mode, date).await?;
+    loop {
+        for illust in illusts.illusts.iter() {
+            if max.is_some_and(|m| entries.len() >= m) {
+                return Ok(entries);
+            }
+            rank += 1;
+            if let Some(id) = illust.id() {
+                entries.push((
+                    id,
+                    PixivRankingEntry {
+                        mode,
+                        content,
+                        date,
+                        rank,
+                    },
+                ));
+            }
+        }
+        if max.is_some_and(|m| entries.len() >= m) || !illusts.has_next_page() {
+            break;
+        }
+        illusts = illusts.get_next_page().await?;
+    }
+    Ok(entries)
+}
+
+/// Get ranking with web api.
+async fn get_ranking_artworks_web(
+    pw: Arc<PixivWebClient>,
+    mode: PixivRankingMode,
+    content: PixivRankingContent,
+    date: Option<NaiveDate>,
+    max: Option<usize>,
+) -> Result<Vec<(u64, PixivRankingEntry)>, PixivDownloaderError> {
+    let mut entries = Vec::new();
+    let mut page = 1;
+    loop {
+        let data = pw
+            .get_ranking(mode, content, date, page)
+            .await
+            .try_err(gettext("Failed to get ranking."))?;
+        // The latest ranking's date is only known from response.
+        let rdate = data["date"]
+            .as_str()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+            .or(date);
+        for item in data["contents"].members() {
+            if max.is_some_and(|m| entries.len() >= m) {
+                return Ok(entries);
+            }
+            match (parse_pixiv_id(&item["illust_id"]), item["rank"].as_u64()) {
+                (Some(id), Some(rank)) => entries.push((
+                    id,
+                    PixivRankingEntry {
+                        mode,
+                        content,
+                        date: rdate,
+                        rank,
+                    },
+                )),
+                _ => {}
+            }
+        }
+        let next = data["next"].as_u64();
+        if next.is_none() || max.is_some_and(|m| entries.len() >= m) {
+            break;
+        }
+        page = next.unwrap();
+    }
+    Ok(entries)
+}
+
+/// A work in manga series
+pub struct PixivSeriesWork {
+    /// Artwork's id
+    pub id: u64,
+    /// Artwork's title
+    pub title: Option<String>,
+    /// The position in the series
+    pub chapter: PixivSeriesChapter,
+}
+
+/// Manga series
+pub struct PixivSeries {
+    /// The series's data
+    pub datas: PixivData,
+    /// The works in reading order
+    pub works: Vec<PixivSeriesWork>,
+}
+
+impl PixivSeries {
+    /// Save the series's data to `series.json` in the series's directory.
+    /// * `base` - The download base
+    pub fn save(&self, base: &Path) -> Result<(), PixivDownloaderError> {
+        let json_file = get_series_dir(base, &self.datas).join("series.json");
+        create_parent_dir(&json_file)?;
+        let mut json_data = JSONDataFile::from(&self.datas);
+        let mut chapters = JsonValue::new_array();
+        for work in self.works.iter() {
+            chapters.push(json::object! {
+                "order": work.chapter.order,
+                "id": work.id,
+                "title": work.title.clone(),
+                "link": PixivID::Artwork(work.id).to_link(),
+            })?;
+        }
+        json_data.add("chapters", chapters);
+        if !json_data.save(&json_file) {
+            return Err(PixivDownloaderError::from(gettext(
+                "Failed to save metadata to JSON file.",
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Get the works in manga series in reading order.
+/// * `pw` - Pixiv web client
+/// * `series` - The series
+pub async fn get_series(
+    pw: Arc<PixivWebClient>,
+    series: &PixivSeriesID,
+) -> Result<PixivSeries, PixivDownloaderError> {
+    let sid = series.series_id;
+    let mut datas = PixivData::new(PixivID::Series(series.clone())).unwrap();
+    let mut titles = HashMap::new();
+    let mut works: Vec<(u64, u64)> = Vec::new();
+    let mut total = None;
+    let mut page = 1;
+    loop {
+        let data = pw
+            .get_series(sid, page)
+            .await
+            .try_err(gettext("Failed to get series's data."))?;
+        if page == 1 {
+            for s in data["illustSeries"].members() {
+                if parse_pixiv_id(&s["id"]) != Some(sid) {
+                    continue;
+                }
+                datas.title = s["title"].as_str().map(|s| s.to_owned());
+                datas.description = s["description"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .or_else(|| s["caption"].as_str())
+                    .map(|s| s.to_owned());
+                datas.author_id = parse_pixiv_id(&s["userId"]);
+                datas.upload_date = s["createDate"]
+                    .as_str()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok());
+                total = s["total"].as_u64();
+            }
+            for u in data["users"].members() {
+                if datas.author_id.is_some() && parse_pixiv_id(&u["userId"]) == datas.author_id {
+                    datas.author =
+                        u["name"]
+                            .as_str()
+                            .map(|s| match get_helper().author_name_filters() {
+                                Some(l) => l.filter(s),
+                                None => s.to_owned(),
+                            });
+                }
+            }
+        }
+        for t in data["thumbnails"]["illust"].members() {
+            if let Some(id) = parse_pixiv_id(&t["id"]) {
+                if let Some(title) = t["title"].as_str() {
+                    titles.insert(id, title.to_owned());
+                }
+            }
+        }
+        let mut count = 0;
+        for w in data["page"]["series"].members() {
+            if let (Some(id), Some(order)) = (parse_pixiv_id(&w["workId"]), w["order"].as_u64()) {
+                if !works.iter().any(|(i, _)| *i == id) {
+                    works.push((id, order));
+                    count += 1;
+                }
+            }
+        }
+        if total.is_none() {
+            total = data["page"]["total"].as_u64();
+        }
+        if count == 0 || total.is_some_and(|t| works.len() as u64 >= t) {
+            break;
+        }
+        page += 1;
+    }
+    works.sort_by_key(|(_, order)| *order);
+    let series_title = datas.title.clone().unwrap_or_default();
+    let chapter = |order: u64| PixivSeriesChapter {
+        series_id: sid,
+        series_title: series_title.clone(),
+        order,
+        total: works.len() as u64,
+    };
+    datas.series = Some(chapter(0));
+    let works = works
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| PixivSeriesWork {
+            id: *id,
+            title: titles.get(id).cloned(),
+            chapter: chapter(i as u64 + 1),
+        })
+        .collect();
+    Ok(PixivSeries { datas, works })
+}
+
+/// The artworks found in user's bookmarks
+pub struct PixivBookmarks {
+    /// The ids of artworks to download
+    pub ids: Vec<u64>,
+    /// The ids of artworks which are deleted or hidden
+    pub unavailable: Vec<u64>,
+    /// The download base
+    base: PathBuf,
+}
+
+impl PixivBookmarks {
+    fn new() -> Self {
+        Self {
+            ids: Vec::new(),
+            unavailable: Vec::new(),
+            base: PathBuf::from(get_helper().download_base()),
+        }
+    }
+
+    /// Returns true if the maximum number of bookmarks is reached.
+    fn is_full(&self) -> bool {
+        get_helper()
+            .bookmarks_max()
+            .is_some_and(|m| self.ids.len() >= m)
+    }
+
+    /// Add a bookmarked artwork. Returns true if no more bookmarks are needed
+    /// in current walk.
+    /// * `available` - Whether the artwork is not deleted or hidden
+    /// * `datas` - The artwork's data
+    fn push(&mut self, available: bool, datas: &PixivData) -> bool {
+        let id: u64 = match (&datas.id).try_into() {
+            Ok(id) => id,
+            Err(_) => return false,
+        };
+        if !available {
+            self.unavailable.push(id);
+            return false;
+        }
+        let helper = get_helper();
+        if helper.bookmarks_stop_at_downloaded() && is_artwork_downloaded(&self.base, datas) {
+            log::info!(
+                "{}",
+                gettext("Artwork <id> is already downloaded, stop.")
+                    .replace("<id>", &format!("{}", id))
+            );
+            return true;
+        }
+        self.ids.push(id);
+        self.is_full()
+    }
+}
+
+/// Get the artworks in user's bookmarks, newest bookmarked first.
+/// * `ac` - Pixiv APP client
+/// * `pw` - Pixiv web client
+/// * `bookmarks` - The bookmarks
+pub async fn get_user_bookmarks(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    bookmarks: &PixivBookmarksID,
+) -> Result<PixivBookmarks, PixivDownloaderError> {
+    let helper = get_helper();
+    let restrict = helper
+        .bookmarks_restrict()
+        .or(bookmarks.restrict)
+        .unwrap_or(PixivRestrictType::Public);
+    let tag = helper.bookmarks_tag().or_else(|| bookmarks.tag.clone());
+    let restricts = match restrict {
+        PixivRestrictType::Public => vec![PixivRestrictLessType::Public],
+        PixivRestrictType::Private => vec![PixivRestrictLessType::Private],
+        PixivRestrictType::All => vec![
+            PixivRestrictLessType::Public,
+            PixivRestrictLessType::Private,
+        ],
+    };
+    let use_app = helper.refresh_token().is_some() && helper.use_app_api();
+    let mut re = PixivBookmarks::new();
+    for restrict in restricts {
+        let len = re.ids.len();
+        let unavailable_len = re.unavailable.len();
+        let finished = if use_app {
+            match get_user_bookmarks_app(&ac, bookmarks.uid, &restrict, tag.as_deref(), &mut re)
+                .await
+            {
+                Ok(finished) => finished,
+                Err(e) => {
+                    log::warn!(
+                        "{} {}",
+                        gettext("Warning: Failed to get user's bookmarks with app api, trying to get with web api:"),
+                        e
+                    );
+                    re.ids.truncate(len);
+                    re.unavailable.truncate(unavailable_len);
+                    get_user_bookmarks_web(&pw, bookmarks.uid, &restrict, tag.as_deref(), &mut re)
+                        .await?
+                }
+            }
+        } else {
+            get_user_bookmarks_web(&pw, bookmarks.uid, &restrict, tag.as_deref(), &mut re).await?
+        };
+        // Stopping at a downloaded artwork only ends the walk of current restrict type.
+        if finished && re.is_full() {
+            break;
+        }
+    }
+    Ok(re)
+}
+
+/// Walk user's bookmarks with app api. Returns true if no more bookmarks are needed in this walk.
+async fn get_user_bookmarks_app(
+    ac: &PixivAppClient,
+    uid: u64,
+    restrict: &PixivRestrictLessType,
+    tag: Option<&str>,
+    re: &mut PixivBookmarks,
+) -> Result<bool, PixivDownloaderError> {
+    let mut illusts = ac.get_user_bookmarks(uid, restrict, tag).await?;
+    loop {
+        for illust in illusts.illusts.iter() {
+            let id = match illust.id() {
+                Some(id) => id,
+                None => continue,
+            };
+            let mut datas = PixivData::new(id).unwrap();
+            datas.from_app_illust(illust);
+            if re.push(illust.visible().unwrap_or(true), &datas) {
+                return Ok(true);
+            }
+        }
+        if !illusts.has_next_page() {
+            break;
+        }
+        illusts = illusts.get_next_page().await?;
+    }
+    Ok(false)
+}
+
+/// Walk user's bookmarks with web api. Returns true if no more bookmarks are needed in this walk.
+async fn get_user_bookmarks_web(
+    pw: &PixivWebClient,
+    uid: u64,
+    restrict: &PixivRestrictLessType,
+    tag: Option<&str>,
+    re: &mut PixivBookmarks,
+) -> Result<bool, PixivDownloaderError> {
+    let is_hide = match restrict {
+        PixivRestrictLessType::Public => false,
+        PixivRestrictLessType::Private => true,
+    };
+    let mut offset = 0;
+    loop {
+        let data = pw
+            .get_user_bookmarks(uid, is_hide, tag, Some(offset), Some(48))
+            .await
+            .try_err(gettext("Failed to get user's bookmarks."))?;
+        let works = &data["works"];
+        if works.is_empty() {
+            break;
+        }
+        for work in works.members() {
+            let id = match parse_pixiv_id(&work["id"]) {
+                Some(id) => id,
+                None => continue,
+            };
+            let mut datas = PixivData::new(id).unwrap();
+            datas.from_web_page_ajax_data(work, true);
+            if datas.title.is_none() {
+                datas.title = work["title"].as_str().map(|s| s.to_owned());
+            }
+            if datas.tags.as_ref().is_some_and(|t| t.is_empty()) {
+                datas.tags.replace(
+                    work["tags"]
+                        .members()
+                        .filter_map(|t| t.as_str())
+                        .map(|t| (t.to_owned(), None))
+                        .collect(),
+                );
+            }
+            let available = !work["isMasked"].as_bool().unwrap_or(false);
+            if re.push(available, &datas) {
+                return Ok(true);
+            }
+        }
+        offset += works.len();
+        match data["total"].as_usize() {
+            Some(total) if offset >= total => break,
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+/// Download a bookmarked artwork. Deleted or hidden artworks are not treated as errors.
+/// * `unavailable` - The ids of deleted or hidden artworks. Not found artworks are added.
+pub async fn download_bookmarked_artwork(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    id: u64,
+    unavailable: Arc<RwLock<Vec<u64>>>,
+) -> Result<(), PixivDownloaderError> {
+    match download_artwork(ac, pw, id, PixivArtworkContext::default()).await {
+        Err(e) if e.is_not_found() => {
+            log::warn!(
+                "{} {}",
+                gettext("Warning: Bookmarked artwork is deleted or hidden:"),
+                id
+            );
+            unavailable.get_mut().push(id);
+            Ok(())
+        }
+        re => re,
+    }
+}
+
 pub async fn download_artwork_ugoira(
     pw: Arc<PixivWebClient>,
     id: u64,
     base: Arc<PathBuf>,
     datas: Arc<PixivData>,
-) -> Result<(), PixivDownloaderError> {
+) -> Result<PixivDownloadedArtwork, PixivDownloaderError> {
     let helper = get_helper();
     let ugoira_data = pw
         .get_ugoira(id)
@@ -292,8 +1837,31 @@ pub async fn download_artwork_ugoira(
     let src = (&ugoira_data["originalSrc"])
         .as_str()
         .try_err(gettext("Can not find source link for ugoira."))?;
+    let file_name = get_file_name_from_url(src).try_err(format!(
+        "{} {}",
+        gettext("Failed to get file name from url:"),
+        src
+    ))?;
+    let file_name = get_artwork_file_path(&base, &datas, &file_name, 0);
+    let downloaded = PixivDownloadedArtwork {
+        datas: Arc::clone(&datas),
+        base: Arc::clone(&base),
+        links: vec![(0, src.to_owned())],
+    };
+    if helper.dry_run() {
+        print_dry_run_file(src, &file_name, Some(0));
+        return Ok(downloaded);
+    }
+    create_parent_dir(&file_name)?;
+    let dir = get_artwork_dir(&base, &datas);
     let dh = DownloaderHelper::builder(src)?
         .headers(json::object! { "referer": "https://www.pixiv.net/" })
+        // download_file joins the path with base again.
+        .file_name(
+            file_name
+                .strip_prefix(&*base)
+                .unwrap_or(file_name.as_path()),
+        )
         .build();
     let tasks = TaskManager::default();
     tasks
@@ -305,6 +1873,7 @@ pub async fn download_artwork_ugoira(
                 None
             },
             Arc::clone(&base),
+            Some(datas.clone()),
         ))
         .await;
     tasks.join().await;
@@ -315,52 +1884,59 @@ pub async fn download_artwork_ugoira(
     let use_cli = helper.ugoira_cli();
     #[cfg(not(feature = "ugoira"))]
     let use_cli = true;
+    let formats = helper.ugoira_formats().to_vec();
     if use_cli {
         if let Some(ubase) = helper.ugoira() {
-            let file_name = get_file_name_from_url(src).try_err(format!(
-                "{} {}",
-                gettext("Failed to get file name from url:"),
-                src
-            ))?;
-            let file_name = base.join(file_name);
             let metadata = get_video_metas(&datas.clone());
-            let frames_file_name = base.join(format!("{}_frames.json", id));
+            let frames_file_name = dir.join(format!("{}_frames.json", id));
             std::fs::write(
                 &frames_file_name,
                 json::stringify((&ugoira_data["frames"]).clone()),
             )
             .try_err4(gettext("Failed to write frames info to file:"))?;
-            let output_file_name = base.join(format!("{}.mp4", id));
-            convert_ugoira_to_mp4_subprocess(
-                &ubase,
-                &file_name,
-                &output_file_name,
-                &frames_file_name,
-                helper.ugoira_max_fps(),
-                metadata,
-                helper.force_yuv420p(),
-                helper.x264_crf(),
-                Some(helper.x264_profile()),
-            )
-            .await?;
-            log::info!(
-                "{}",
-                gettext("Converted <src> -> <dest>")
-                    .replace("<src>", file_name.to_str().unwrap_or("(null)"))
-                    .replace("<dest>", output_file_name.to_str().unwrap_or("(null)"))
-                    .as_str()
-            );
-            return Ok(());
+            for format in formats {
+                let output_file_name = dir.join(format!("{}.{}", id, format.extension()));
+                if format == UgoiraFormat::Mp4 {
+                    convert_ugoira_to_mp4_subprocess(
+                        &ubase,
+                        &file_name,
+                        &output_file_name,
+                        &frames_file_name,
+                        helper.ugoira_max_fps(),
+                        metadata.clone(),
+                        helper.force_yuv420p(),
+                        helper.x264_crf(),
+                        Some(helper.x264_profile()),
+                    )
+                    .await?;
+                } else {
+                    convert_ugoira_to_animation_subprocess(
+                        &ubase,
+                        &file_name,
+                        &output_file_name,
+                        &frames_file_name,
+                        format,
+                        metadata.clone(),
+                        Some(helper.ugoira_gif_dither()),
+                    )
+                    .await?;
+                }
+                log::info!(
+                    "{}",
+                    gettext("Converted <src> -> <dest>")
+                        .replace("<src>", file_name.to_str().unwrap_or("(null)"))
+                        .replace("<dest>", output_file_name.to_str().unwrap_or("(null)"))
+                        .as_str()
+                );
+                write_sha256_if_needed(&output_file_name);
+                write_xmp_sidecar_if_needed(&output_file_name, datas.as_ref());
+                events::emit_ugoira_converted(id, &file_name, &output_file_name);
+            }
+            return Ok(downloaded);
         }
     }
     #[cfg(feature = "ugoira")]
     {
-        let file_name = get_file_name_from_url(src).try_err(format!(
-            "{} {}",
-            gettext("Failed to get file name from url:"),
-            src
-        ))?;
-        let file_name = base.join(file_name);
         let metadata = match get_video_metadata(Arc::clone(&datas).as_ref()) {
             Ok(m) => m,
             Err(e) => {
@@ -386,38 +1962,77 @@ pub async fn download_artwork_ugoira(
             }
             None => {}
         }
-        let frames_file_name = base.join(format!("{}_frames.json", id));
+        let frames_file_name = dir.join(format!("{}_frames.json", id));
         std::fs::write(
             &frames_file_name,
             json::stringify((&ugoira_data["frames"]).clone()),
         )
         .try_err4(gettext("Failed to write frames info to file:"))?;
         let frames = UgoiraFrames::from_json(&ugoira_data["frames"])?;
-        let output_file_name = base.join(format!("{}.mp4", id));
-        convert_ugoira_to_mp4(
-            &file_name,
-            &output_file_name,
-            &frames,
-            helper.ugoira_max_fps(),
-            &options,
-            &metadata,
-        )?;
-        log::info!(
-            "{}",
-            gettext("Converted <src> -> <dest>")
-                .replace("<src>", file_name.to_str().unwrap_or("(null)"))
-                .replace("<dest>", output_file_name.to_str().unwrap_or("(null)"))
-                .as_str()
-        );
+        let mut animation_options = AVDict::new();
+        animation_options.set("dither", helper.ugoira_gif_dither().as_str(), None)?;
+        for format in formats {
+            let output_file_name = dir.join(format!("{}.{}", id, format.extension()));
+            if format == UgoiraFormat::Mp4 {
+                convert_ugoira_to_mp4(
+                    &file_name,
+                    &output_file_name,
+                    &frames,
+                    helper.ugoira_max_fps(),
+                    &options,
+                    &metadata,
+                )?;
+            } else {
+                convert_ugoira_to_animation(
+                    &file_name,
+                    &output_file_name,
+                    &frames,
+                    format,
+                    &animation_options,
+                    &metadata,
+                )?;
+            }
+            log::info!(
+                "{}",
+                gettext("Converted <src> -> <dest>")
+                    .replace("<src>", file_name.to_str().unwrap_or("(null)"))
+                    .replace("<dest>", output_file_name.to_str().unwrap_or("(null)"))
+                    .as_str()
+            );
+            write_sha256_if_needed(&output_file_name);
+            write_xmp_sidecar_if_needed(&output_file_name, datas.as_ref());
+            events::emit_ugoira_converted(id, &file_name, &output_file_name);
+        }
+    }
+    return Ok(downloaded);
+}
+
+/// Returns true if the page should be downloaded.
+/// * `page_range` - The selected pages. [None] if all pages are selected.
+/// * `np` - Number of page in artworks
+/// * `total` - The count of pages
+fn is_page_selected(page_range: &Option<PageRange>, np: u16, total: u64) -> bool {
+    match page_range {
+        Some(range) => range.contains(np, total),
+        None => true,
+    }
+}
+
+/// Returns the error message when failed to get the link of the picture.
+/// * `image_size` - The size variant of the picture
+fn get_picture_link_error(image_size: PixivImageSize) -> &'static str {
+    match image_size {
+        PixivImageSize::Original => gettext("Failed to get original picture's link."),
+        _ => gettext("Failed to get the link of the picture in selected size."),
     }
-    return Ok(());
 }
 
 pub async fn download_artwork_app(
     ac: PixivAppClient,
     pw: Arc<PixivWebClient>,
     id: u64,
-) -> Result<(), PixivDownloaderError> {
+    ctx: PixivArtworkContext,
+) -> Result<Option<PixivDownloadedArtwork>, PixivDownloaderError> {
     let data = ac.get_illust_details(id).await?;
     let helper = get_helper();
     log::debug!("{:#?}", data);
@@ -432,9 +2047,20 @@ pub async fn download_artwork_app(
         }
     }
     let base = Arc::new(PathBuf::from(helper.download_base()));
-    let json_file = base.join(format!("{}.json", id));
     let mut datas = PixivData::new(id).unwrap();
+    datas.set_context(ctx);
     datas.from_app_illust(&data);
+    if is_artwork_filtered(&datas) {
+        return Ok(None);
+    }
+    let image_size = helper.image_size();
+    let page_range = helper.page_range();
+    if data.typ() != Some("ugoira") {
+        datas.image_size = Some(image_size);
+        if let (Some(range), Some(page_count)) = (&page_range, data.page_count()) {
+            datas.pages = Some(range.select(page_count));
+        }
+    }
     let mut web_used = false;
     if data.caption_is_empty() && helper.use_web_description() {
         if let Some(data) = pw.get_artwork_ajax(id).await {
@@ -447,7 +2073,7 @@ pub async fn download_artwork_app(
             }
         }
     }
-    if helper.add_history() && !web_used {
+    if helper.add_history() && !web_used && !helper.dry_run() {
         if let Err(e) = ac.add_illust_to_browsing_history(vec![id]).await {
             log::warn!(
                 "{} {}",
@@ -457,17 +2083,22 @@ pub async fn download_artwork_app(
         }
     }
     let datas = Arc::new(datas);
-    let json_data = JSONDataFile::from(Arc::clone(&datas));
-    if !json_data.save(&json_file) {
-        return Err(PixivDownloaderError::from(gettext(
-            "Failed to save metadata to JSON file.",
-        )));
+    events::emit_post_resolved(&datas.id, datas.title.as_deref(), data.page_count());
+    let json_file = get_artwork_dir(&base, &datas).join(format!("{}.json", id));
+    if !helper.dry_run() {
+        create_parent_dir(&json_file)?;
+        let json_data = JSONDataFile::from(Arc::clone(&datas));
+        if !json_data.save(&json_file) {
+            return Err(PixivDownloaderError::from(gettext(
+                "Failed to save metadata to JSON file.",
+            )));
+        }
     }
     let illust_type = data.typ();
     match illust_type {
         Some(illust_type) => match illust_type {
             "ugoira" => {
-                return download_artwork_ugoira(pw, id, base, datas).await;
+                return download_artwork_ugoira(pw, id, base, datas).await.map(Some);
             }
             _ => {}
         },
@@ -479,20 +2110,25 @@ pub async fn download_artwork_app(
         .page_count()
         .ok_or(gettext("Failed to get page count."))?;
     if page_count > 1 && helper.download_multiple_files() {
-        let mut np = 0u16;
+        let mut links = Vec::new();
         let tasks = TaskManager::default();
         let mut re: Result<(), PixivDownloaderError> = Ok(());
-        for page in data.meta_pages() {
-            let url = match page.original() {
+        for (np, page) in data.meta_pages().iter().enumerate() {
+            let np = np as u16;
+            if !is_page_selected(&page_range, np, page_count) {
+                continue;
+            }
+            let url = match image_size.app_url(page) {
                 Some(url) => url.to_owned(),
                 None => {
                     concat_pixiv_downloader_error!(
                         re,
-                        Err::<(), &str>(gettext("Failed to get original picture's link."))
+                        Err::<(), &str>(get_picture_link_error(image_size))
                     );
                     continue;
                 }
             };
+            links.push((np, url.clone()));
             tasks
                 .add_task(download_artwork_link(
                     url,
@@ -506,7 +2142,6 @@ pub async fn download_artwork_app(
                     Arc::clone(&base),
                 ))
                 .await;
-            np += 1;
         }
         tasks.join().await;
         let tasks = tasks.take_finished_tasks();
@@ -518,14 +2153,19 @@ pub async fn download_artwork_app(
             };
             concat_pixiv_downloader_error!(re, r);
         }
-        return re;
+        return re.map(|_| Some(PixivDownloadedArtwork { datas, base, links }));
     } else if page_count > 1 {
-        let mut np = 0u16;
+        let mut links = Vec::new();
         let tasks = TaskManager::default();
-        for page in data.meta_pages() {
-            let link = page
-                .original()
-                .ok_or(gettext("Failed to get original picture's link."))?;
+        for (np, page) in data.meta_pages().iter().enumerate() {
+            let np = np as u16;
+            if !is_page_selected(&page_range, np, page_count) {
+                continue;
+            }
+            let link = image_size
+                .app_url(page)
+                .ok_or(get_picture_link_error(image_size))?;
+            links.push((np, link.to_owned()));
             tasks
                 .add_task(download_artwork_link(
                     link.to_owned(),
@@ -540,7 +2180,6 @@ pub async fn download_artwork_app(
                 ))
                 .await;
             tasks.join().await;
-            np += 1;
         }
         let mut re = Ok(());
         let tasks = tasks.take_finished_tasks();
@@ -552,12 +2191,21 @@ pub async fn download_artwork_app(
             };
             concat_pixiv_downloader_error!(re, r);
         }
-        return re;
+        return re.map(|_| Some(PixivDownloadedArtwork { datas, base, links }));
+    } else if !is_page_selected(&page_range, 0, 1) {
+        return Ok(Some(PixivDownloadedArtwork {
+            datas,
+            base,
+            links: Vec::new(),
+        }));
     } else {
-        let link = data
-            .original_image_url()
-            .ok_or(gettext("Failed to get original picture's link."))?;
+        let link = match image_size {
+            PixivImageSize::Original => data.original_image_url(),
+            _ => image_size.app_url(&data.image_urls()),
+        }
+        .ok_or(get_picture_link_error(image_size))?;
         let tasks = TaskManager::default();
+        let links = vec![(0, link.to_owned())];
         tasks
             .add_task(download_artwork_link(
                 link.to_owned(),
@@ -575,14 +2223,15 @@ pub async fn download_artwork_app(
         let mut tasks = tasks.take_finished_tasks();
         let task = tasks.get_mut(0).try_err(gettext("No tasks finished."))?;
         task.await??;
+        return Ok(Some(PixivDownloadedArtwork { datas, base, links }));
     }
-    Ok(())
 }
 
 pub async fn download_artwork_web(
     pw: Arc<PixivWebClient>,
     id: u64,
-) -> Result<(), PixivDownloaderError> {
+    ctx: PixivArtworkContext,
+) -> Result<Option<PixivDownloadedArtwork>, PixivDownloaderError> {
     if !pw.is_login_checked() {
         if !pw.check_login().await {
             log::error!("{}", gettext("Failed to check login status."));
@@ -605,10 +2254,11 @@ pub async fn download_artwork_web(
             ajax_ver = false;
         }
     }
-    if re.is_none() {
-        re = pw.get_artwork_ajax(id).await;
-    }
-    let re = re.try_err(gettext("Failed to get artwork's data."))?;
+    let re = match re {
+        Some(re) => re,
+        // Keep the error of ajax API, so deleted artworks can be detected.
+        None => pw.try_get_artwork_ajax(id).await?,
+    };
     if ajax_ver {
         pages = (&re["pageCount"]).as_u64();
     } else {
@@ -624,33 +2274,52 @@ pub async fn download_artwork_web(
             "Failed to get pages' data.",
         )));
     }
+    let illust_type = if ajax_ver {
+        (&re["illustType"]).as_i64()
+    } else {
+        (&re["illust"][format!("{}", id).as_str()]["illustType"]).as_i64()
+    };
     let base = Arc::new(PathBuf::from(helper.download_base()));
-    let json_file = base.join(format!("{}.json", id));
     let mut datas = PixivData::new(id).unwrap();
+    datas.set_context(ctx);
     if ajax_ver {
         datas.from_web_page_ajax_data(&re, true);
     } else {
         datas.from_web_page_data(&re, true);
     }
+    if let Some(pages_data) = &pages_data {
+        datas.from_web_illust_pages(pages_data);
+    }
+    if is_artwork_filtered(&datas) {
+        return Ok(None);
+    }
+    let image_size = helper.image_size();
+    let page_range = helper.page_range();
+    if illust_type != Some(2) {
+        datas.image_size = Some(image_size);
+        if let Some(range) = &page_range {
+            datas.pages = Some(range.select(pages));
+        }
+    }
     let datas = Arc::new(datas);
-    let json_data = JSONDataFile::from(Arc::clone(&datas));
-    if !json_data.save(&json_file) {
-        return Err(PixivDownloaderError::from(gettext(
-            "Failed to save metadata to JSON file.",
-        )));
+    events::emit_post_resolved(&datas.id, datas.title.as_deref(), Some(pages));
+    let json_file = get_artwork_dir(&base, &datas).join(format!("{}.json", id));
+    if !helper.dry_run() {
+        create_parent_dir(&json_file)?;
+        let json_data = JSONDataFile::from(Arc::clone(&datas));
+        if !json_data.save(&json_file) {
+            return Err(PixivDownloaderError::from(gettext(
+                "Failed to save metadata to JSON file.",
+            )));
+        }
     }
-    let illust_type = if ajax_ver {
-        (&re["illustType"]).as_i64()
-    } else {
-        (&re["illust"][format!("{}", id).as_str()]["illustType"]).as_i64()
-    };
     if illust_type.is_some() {
         let illust_type = illust_type.unwrap();
         match illust_type {
             0 => {} // Normal illust
             1 => {} // Manga illust
             2 => {
-                return download_artwork_ugoira(pw, id, base, datas).await;
+                return download_artwork_ugoira(pw, id, base, datas).await.map(Some);
             }
             _ => {
                 log::warn!(
@@ -664,19 +2333,24 @@ pub async fn download_artwork_web(
         log::warn!("{}", gettext("Warning: Failed to get illust's type."));
     }
     if pages_data.is_some() && helper.download_multiple_files() {
-        let mut np = 0u16;
+        let mut links = Vec::new();
         let pages_data = pages_data.as_ref().unwrap();
         let tasks = TaskManager::default();
         let mut re: Result<(), PixivDownloaderError> = Ok(());
-        for page in pages_data.members() {
-            let url = page["urls"]["original"].as_str();
+        for (np, page) in pages_data.members().enumerate() {
+            let np = np as u16;
+            if !is_page_selected(&page_range, np, pages) {
+                continue;
+            }
+            let url = image_size.web_url(&page["urls"]);
             if url.is_none() {
                 concat_pixiv_downloader_error!(
                     re,
-                    Err::<(), &str>(gettext("Failed to get original picture's link."))
+                    Err::<(), &str>(get_picture_link_error(image_size))
                 );
                 continue;
             }
+            links.push((np, url.unwrap().to_owned()));
             tasks
                 .add_task(download_artwork_link(
                     url.unwrap().to_owned(),
@@ -690,7 +2364,6 @@ pub async fn download_artwork_web(
                     Arc::clone(&base),
                 ))
                 .await;
-            np += 1;
         }
         tasks.join().await;
         let tasks = tasks.take_finished_tasks();
@@ -702,15 +2375,20 @@ pub async fn download_artwork_web(
             };
             concat_pixiv_downloader_error!(re, r);
         }
-        return re;
+        return re.map(|_| Some(PixivDownloadedArtwork { datas, base, links }));
     } else if pages_data.is_some() {
-        let mut np = 0u16;
+        let mut links = Vec::new();
         let pages_data = pages_data.as_ref().unwrap();
         let tasks = TaskManager::default();
-        for page in pages_data.members() {
-            let link = page["urls"]["original"]
-                .as_str()
-                .try_err(gettext("Failed to get original picture's link."))?;
+        for (np, page) in pages_data.members().enumerate() {
+            let np = np as u16;
+            if !is_page_selected(&page_range, np, pages) {
+                continue;
+            }
+            let link = image_size
+                .web_url(&page["urls"])
+                .try_err(get_picture_link_error(image_size))?;
+            links.push((np, link.to_owned()));
             tasks
                 .add_task(download_artwork_link(
                     link.to_owned(),
@@ -725,7 +2403,6 @@ pub async fn download_artwork_web(
                 ))
                 .await;
             tasks.join().await;
-            np += 1;
         }
         let mut re = Ok(());
         let tasks = tasks.take_finished_tasks();
@@ -737,15 +2414,22 @@ pub async fn download_artwork_web(
             };
             concat_pixiv_downloader_error!(re, r);
         }
-        return re;
+        return re.map(|_| Some(PixivDownloadedArtwork { datas, base, links }));
+    } else if !is_page_selected(&page_range, 0, 1) {
+        return Ok(Some(PixivDownloadedArtwork {
+            datas,
+            base,
+            links: Vec::new(),
+        }));
     } else {
         let link = if ajax_ver {
-            (&re["urls"]["original"]).as_str()
+            image_size.web_url(&re["urls"])
         } else {
-            (&re["illust"][format!("{}", id)]["urls"]["original"]).as_str()
+            image_size.web_url(&re["illust"][format!("{}", id)]["urls"])
         }
-        .try_err(gettext("Failed to get original picture's link."))?;
+        .try_err(get_picture_link_error(image_size))?;
         let tasks = TaskManager::default();
+        let links = vec![(0, link.to_owned())];
         tasks
             .add_task(download_artwork_link(
                 link.to_owned(),
@@ -763,8 +2447,185 @@ pub async fn download_artwork_web(
         let mut tasks = tasks.take_finished_tasks();
         let task = tasks.get_mut(0).try_err(gettext("No tasks finished."))?;
         task.await??;
+        return Ok(Some(PixivDownloadedArtwork { datas, base, links }));
     }
-    Ok(())
+}
+
+/// Download a novel with its cover and referenced images.
+/// * `ac` - Pixiv APP client
+/// * `pw` - Pixiv web client
+/// * `id` - Novel's id
+pub async fn download_novel(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    id: u64,
+) -> Result<(), PixivDownloaderError> {
+    let re = _download_novel(ac, pw, id).await;
+    if let Err(e) = &re {
+        events::emit_error(PixivID::Novel(id).to_json(), e);
+    }
+    re
+}
+
+async fn _download_novel(
+    ac: PixivAppClient,
+    pw: Arc<PixivWebClient>,
+    id: u64,
+) -> Result<(), PixivDownloaderError> {
+    if !pw.is_login_checked() {
+        if !pw.check_login().await {
+            log::error!("{}", gettext("Failed to check login status."));
+        } else {
+            if !pw.logined() {
+                log::warn!(
+                    "{}",
+                    gettext("Warning: Web api client not logged in, some future may not work.")
+                );
+            }
+        }
+    }
+    let helper = get_helper();
+    let mut datas = PixivData::new(PixivID::Novel(id)).unwrap();
+    let mut cover = None;
+    let mut app_ok = false;
+    if helper.refresh_token().is_some() && helper.use_app_api() {
+        match ac.get_novel_details(id).await {
+            Ok(novel) => {
+                datas.from_app_novel(&novel);
+                cover = novel.image_urls().large().map(|s| s.to_owned());
+                app_ok = true;
+            }
+            Err(e) => {
+                if e.is_not_found() {
+                    return Err(e);
+                }
+                log::warn!(
+                    "{} {}",
+                    gettext("Warning: Failed to get novel's data with app api:"),
+                    e
+                );
+            }
+        }
+    }
+    let re = pw
+        .get_novel_ajax(id)
+        .await
+        .try_err(gettext("Failed to get novel's data."))?;
+    datas.from_web_novel_ajax_data(&re, !app_ok);
+    if cover.is_none() {
+        cover = re["coverUrl"].as_str().map(|s| s.to_owned());
+    }
+    let content = re["content"]
+        .as_str()
+        .try_err(gettext("Failed to get novel's text."))?;
+    let base = Arc::new(PathBuf::from(helper.download_base()));
+    let datas = Arc::new(datas);
+    events::emit_post_resolved(&datas.id, datas.title.as_deref(), None);
+    let dir = get_artwork_dir(&base, &datas);
+    let json_file = dir.join(format!("{}.json", id));
+    if !helper.dry_run() {
+        create_parent_dir(&json_file)?;
+        let json_data = JSONDataFile::from(Arc::clone(&datas));
+        if !json_data.save(&json_file) {
+            return Err(PixivDownloaderError::from(gettext(
+                "Failed to save metadata to JSON file.",
+            )));
+        }
+    }
+    let nodes = parse_novel(content);
+    let mut links: Vec<(String, String)> = Vec::new();
+    if let Some(cover) = cover {
+        let ext = get_file_name_from_url(cover.as_str())
+            .and_then(|f| f.rsplit_once('.').map(|(_, e)| e.to_owned()))
+            .unwrap_or(String::from("jpg"));
+        links.push((cover, format!("{}_cover.{}", id, ext)));
+    }
+    let mut images = HashMap::new();
+    for image in get_novel_images(&nodes) {
+        let url = match &image {
+            NovelImage::Pixiv { id, page } => {
+                let pages = pw.get_illust_pages(*id).await;
+                pages.as_ref().and_then(|p| {
+                    p[(*page as usize).saturating_sub(1)]["urls"]["original"]
+                        .as_str()
+                        .map(|s| s.to_owned())
+                })
+            }
+            NovelImage::Uploaded(i) => re["textEmbeddedImages"][format!("{}", i)]["urls"]
+                ["original"]
+                .as_str()
+                .map(|s| s.to_owned()),
+        };
+        let url = match url {
+            Some(url) => url,
+            None => {
+                log::warn!(
+                    "{} {:?}",
+                    gettext("Warning: Failed to get the link of the image in novel:"),
+                    image
+                );
+                continue;
+            }
+        };
+        let ext = get_file_name_from_url(url.as_str())
+            .and_then(|f| f.rsplit_once('.').map(|(_, e)| e.to_owned()))
+            .unwrap_or(String::from("jpg"));
+        let file_name = match &image {
+            NovelImage::Pixiv { id: iid, page } => {
+                format!("{}_pixiv_{}_p{}.{}", id, iid, page.saturating_sub(1), ext)
+            }
+            NovelImage::Uploaded(i) => format!("{}_uploaded_{}.{}", id, i, ext),
+        };
+        images.insert(image, file_name.clone());
+        links.push((url, file_name));
+    }
+    if !helper.dry_run() {
+        let text = convert_novel_to_text(&nodes, |i| images.get(i).cloned());
+        std::fs::write(dir.join(format!("{}.txt", id)), text)
+            .try_err4(gettext("Failed to write novel's text to file:"))?;
+        let mut md = String::new();
+        if let Some(title) = &datas.title {
+            md += &format!("# {}\n\n", title);
+        }
+        md += &convert_novel_to_md(&nodes, |i| images.get(i).cloned());
+        md += "\n";
+        std::fs::write(dir.join(format!("{}.md", id)), md)
+            .try_err4(gettext("Failed to write novel's text to file:"))?;
+    }
+    let tasks = TaskManager::default();
+    // The file names are relative to the novel's directory.
+    let dir = Arc::new(dir);
+    for (url, file_name) in links {
+        let dh = DownloaderHelper::builder(url)?
+            .headers(json::object! { "referer": "https://www.pixiv.net/" })
+            .file_name(&file_name)
+            .build();
+        tasks
+            .add_task(download_file(
+                dh,
+                if helper.enable_multi_progress_bar() {
+                    Some(get_progress_bar())
+                } else {
+                    None
+                },
+                Arc::clone(&dir),
+                Some(datas.clone()),
+            ))
+            .await;
+        if !helper.download_multiple_files() {
+            tasks.join().await;
+        }
+    }
+    tasks.join().await;
+    let mut re = Ok(());
+    for task in tasks.take_finished_tasks() {
+        let r = match task.await {
+            Ok(r) => r,
+            Err(e) => Err(PixivDownloaderError::from(e)),
+        };
+        concat_pixiv_downloader_error!(re, r);
+    }
+    re
 }
 
 /// Download a  file link
@@ -775,25 +2636,206 @@ pub async fn download_file(
     dh: DownloaderHelper,
     progress_bars: Option<Arc<MultiProgress>>,
     base: Arc<PathBuf>,
+    datas: Option<Arc<dyn ExifDataSource + Send + Sync>>,
 ) -> Result<(), PixivDownloaderError> {
     let helper = get_helper();
+    let file_name = dh
+        .get_local_file_path(&*base)
+        .try_err(gettext("Failed to get file name from url."))?;
+    if helper.dry_run() {
+        print_dry_run_file(dh.url.as_str(), &file_name, None);
+        return Ok(());
+    }
     match dh.download_local(helper.overwrite(), &*base)? {
         DownloaderResult::Ok(d) => {
             d.handle_options(&helper, progress_bars);
             d.download();
-            d.join().await?;
-            if d.is_panic() {
+            join_downloader(&d, dh.url.as_str(), &file_name).await?;
+            if d.is_downloaded() {
+                write_sha256_if_needed(&file_name);
+                if let Some(datas) = &datas {
+                    write_xmp_sidecar_if_needed(&file_name, datas.as_ref());
+                }
+                events::emit_file_completed(dh.url.as_str(), &file_name);
+            } else if d.is_panic() {
                 return Err(PixivDownloaderError::from(
                     d.get_panic()
                         .try_err(gettext("Failed to get error message."))?,
                 ));
             }
         }
-        DownloaderResult::Canceled => {}
+        DownloaderResult::Canceled => {
+            events::emit_file_skipped(dh.url.as_str(), &file_name);
+            if let Some(datas) = &datas {
+                if file_name.exists() {
+                    write_xmp_sidecar_if_needed(&file_name, datas.as_ref());
+                }
+            }
+        }
     }
     Ok(())
 }
 
+/// Filter the posts in fanbox post list by publish date, count and restriction.
+/// The post list should be sorted by publish date, newest first.
+struct FanboxPostFilter {
+    since: Option<DateTime<FixedOffset>>,
+    until: Option<DateTime<FixedOffset>>,
+    max: Option<usize>,
+    skip_restricted: bool,
+    /// The number of posts to download
+    count: usize,
+    /// The number of skipped restricted posts
+    restricted: usize,
+}
+
+/// The result of [FanboxPostFilter::check]
+enum FanboxPostFilterResult {
+    Download,
+    Skip,
+    /// No more posts are needed.
+    Stop,
+}
+
+impl FanboxPostFilter {
+    fn new() -> Self {
+        let helper = get_helper();
+        Self {
+            since: helper.fanbox_since(),
+            until: helper.fanbox_until(),
+            max: helper.fanbox_max_posts(),
+            skip_restricted: helper.fanbox_skip_restricted(),
+            count: 0,
+            restricted: 0,
+        }
+    }
+
+    fn check(&mut self, item: &FanboxItem) -> FanboxPostFilterResult {
+        let published = item
+            .published_datetime()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok());
+        if let (Some(since), Some(published)) = (self.since, published) {
+            if published < since {
+                // Pinned posts may be placed before newer posts.
+                if item.is_pinned().unwrap_or(false) {
+                    return FanboxPostFilterResult::Skip;
+                }
+                return FanboxPostFilterResult::Stop;
+            }
+        }
+        if let (Some(until), Some(published)) = (self.until, published) {
+            if published > until {
+                return FanboxPostFilterResult::Skip;
+            }
+        }
+        if self.max.is_some_and(|m| self.count >= m) {
+            return FanboxPostFilterResult::Stop;
+        }
+        if self.skip_restricted && item.is_restricted().unwrap_or(false) {
+            self.restricted += 1;
+            return FanboxPostFilterResult::Skip;
+        }
+        self.count += 1;
+        FanboxPostFilterResult::Download
+    }
+
+    fn log_summary(&self) {
+        if self.restricted > 0 {
+            log::info!(
+                "{}",
+                gettext("Skipped <count> restricted posts.")
+                    .replace("<count>", &format!("{}", self.restricted))
+            );
+        }
+    }
+}
+
+/// Get the posts of a fanbox creator, newest first.
+/// * `fc` - Fanbox client
+/// * `creator_id` - The id of the creator
+pub async fn get_fanbox_creator_posts(
+    fc: &FanboxClient,
+    creator_id: &str,
+) -> Result<Vec<FanboxPostID>, PixivDownloaderError> {
+    let pages = fc
+        .paginate_creator_post(creator_id)
+        .await
+        .try_err(gettext("Failed to get the pages of creator's posts."))?;
+    let mut filter = FanboxPostFilter::new();
+    let mut posts = Vec::new();
+    'outer: for i in 0..pages.len() {
+        let list = pages
+            .get_page(i)
+            .await
+            .try_err(gettext("Failed to get creator's posts."))?;
+        for item in list.items.iter() {
+            let id = match item.id() {
+                Some(id) => id,
+                None => continue,
+            };
+            match filter.check(item) {
+                FanboxPostFilterResult::Download => {
+                    posts.push(FanboxPostID::new(creator_id, id));
+                }
+                FanboxPostFilterResult::Skip => {}
+                FanboxPostFilterResult::Stop => break 'outer,
+            }
+        }
+    }
+    filter.log_summary();
+    Ok(posts)
+}
+
+/// Get the posts in fanbox feed until reaching a post which is already downloaded, newest first.
+/// * `fc` - Fanbox client
+/// * `feed` - The feed
+pub async fn get_fanbox_feed_posts(
+    fc: &FanboxClient,
+    feed: FanboxFeed,
+) -> Result<Vec<FanboxPostID>, PixivDownloaderError> {
+    let mut list = match feed {
+        FanboxFeed::Home => fc.list_home_post(10).await,
+        FanboxFeed::Supporting => fc.list_supporting_post(10).await,
+    }
+    .try_err(gettext("Failed to get the posts in fanbox feed."))?;
+    let mut filter = FanboxPostFilter::new();
+    let mut posts = Vec::new();
+    'outer: loop {
+        for item in list.items.iter() {
+            let id = match (item.creator_id(), item.id()) {
+                (Some(creator_id), Some(id)) => FanboxPostID::new(creator_id, id),
+                _ => continue,
+            };
+            if get_fanbox_post_dir(&id).join("data.json").exists() {
+                log::info!(
+                    "{}",
+                    gettext("Post <id> is already downloaded, stop.")
+                        .replace("<id>", &format!("{}", id.post_id))
+                );
+                break 'outer;
+            }
+            match filter.check(item) {
+                FanboxPostFilterResult::Download => posts.push(id),
+                FanboxPostFilterResult::Skip => {}
+                FanboxPostFilterResult::Stop => break 'outer,
+            }
+        }
+        if !list.has_next_page() {
+            break;
+        }
+        list = list.get_next_page().await?;
+    }
+    filter.log_summary();
+    Ok(posts)
+}
+
+/// Get the directory to save the fanbox post.
+fn get_fanbox_post_dir(id: &FanboxPostID) -> PathBuf {
+    PathBuf::from(get_helper().download_base())
+        .join(&id.creator_id)
+        .join(format!("{}", id.post_id))
+}
+
 /// Download a fanbox image link
 /// * `dh` - Link and other informations
 /// * `np` - Number of page
@@ -817,6 +2859,13 @@ pub async fn download_fanbox_image(
             PixivID::Artwork(a) => format!("{}", a),
             PixivID::FanboxCreator(f) => format!("{}", f),
             PixivID::FanboxPost(p) => format!("{}", p.post_id),
+            PixivID::User(u) => format!("{}", u.uid),
+            PixivID::Bookmarks(b) => format!("{}", b.uid),
+            PixivID::FanboxFeed(f) => f.as_str().to_owned(),
+            PixivID::Novel(n) => format!("{}", n),
+            PixivID::Series(s) => format!("{}", s.series_id),
+            PixivID::Search(s) => s.keywords.clone(),
+            PixivID::Ranking(r) => r.mode.unwrap_or_default().web_value().to_owned(),
         };
         let mut nps = format!("{}", np + 1);
         while nps.len() < len {
@@ -833,11 +2882,16 @@ pub async fn download_fanbox_image(
     let file_name = ndh
         .get_local_file_path(&*base)
         .try_err(gettext("Failed to get file name from url."))?;
+    if helper.dry_run() {
+        print_dry_run_file(ndh.url.as_str(), &file_name, Some(np));
+        return Ok(());
+    }
+    let url = ndh.url.as_str();
     match ndh.download_local(helper.overwrite(), &*base)? {
         DownloaderResult::Ok(d) => {
             d.handle_options(&helper, progress_bars);
             d.download();
-            d.join().await?;
+            join_downloader(&d, url, &file_name).await?;
             if d.is_downloaded() {
                 #[cfg(feature = "exif")]
                 {
@@ -849,6 +2903,9 @@ pub async fn download_fanbox_image(
                         );
                     }
                 }
+                write_sha256_if_needed(&file_name);
+                write_xmp_sidecar_if_needed(&file_name, &datas);
+                events::emit_file_completed(url, &file_name);
             } else if d.is_panic() {
                 return Err(PixivDownloaderError::from(
                     d.get_panic()
@@ -857,6 +2914,10 @@ pub async fn download_fanbox_image(
             }
         }
         DownloaderResult::Canceled => {
+            events::emit_file_skipped(&url, &file_name);
+            if file_name.exists() {
+                write_xmp_sidecar_if_needed(&file_name, &datas);
+            }
             #[cfg(feature = "exif")]
             {
                 if helper.update_exif() && file_name.exists() {
@@ -866,6 +2927,8 @@ pub async fn download_fanbox_image(
                             gettext("Failed to add exif data to image:"),
                             file_name.to_str().unwrap_or("(null)")
                         );
+                    } else {
+                        write_sha256_if_needed(&file_name);
                     }
                 }
             }
@@ -877,6 +2940,18 @@ pub async fn download_fanbox_image(
 pub async fn download_fanbox_post(
     fc: Arc<FanboxClient>,
     id: FanboxPostID,
+) -> Result<(), PixivDownloaderError> {
+    let target = PixivID::FanboxPost(id.clone()).to_json();
+    let re = _download_fanbox_post(fc, id).await;
+    if let Err(e) = &re {
+        events::emit_error(target, e);
+    }
+    re
+}
+
+async fn _download_fanbox_post(
+    fc: Arc<FanboxClient>,
+    id: FanboxPostID,
 ) -> Result<(), PixivDownloaderError> {
     let post = fc
         .get_post_info(id.post_id)
@@ -894,35 +2969,42 @@ pub async fn download_fanbox_post(
             );
         }
     }
-    if post
+    let restricted = post
         .is_restricted()
-        .try_err(gettext("Failed to check the post is restricted or not."))?
-    {
+        .try_err(gettext("Failed to check the post is restricted or not."))?;
+    if restricted && helper.fanbox_skip_restricted() {
         log::warn!("{}", gettext("Warning: This article is restricted."));
-        // #TODO allow to continue
         return Ok(());
     }
-    let base = Arc::new(
-        PathBuf::from(helper.download_base())
-            .join(&id.creator_id)
-            .join(format!("{}", id.post_id)),
-    );
+    let base = Arc::new(get_fanbox_post_dir(&id));
     let json_file = base.join("data.json");
     let data = FanboxData::new(id, &post).try_err("Failed to create data file.")?;
+    events::emit_post_resolved(&data.id, post.title(), None);
     let data_file = JSONDataFile::from(&data);
-    if !base.exists() {
-        match create_dir_all(&*base) {
-            Ok(_) => {}
-            Err(e) => {
-                if !base.exists() {
-                    return Err(PixivDownloaderError::from(e));
+    if !helper.dry_run() {
+        if !base.exists() {
+            match create_dir_all(&*base) {
+                Ok(_) => {}
+                Err(e) => {
+                    if !base.exists() {
+                        return Err(PixivDownloaderError::from(e));
+                    }
                 }
             }
         }
     }
-    data_file
-        .save(&json_file)
-        .try_err(gettext("Failed to save post data to file."))?;
+    if restricted {
+        if !helper.dry_run() {
+            data_file
+                .save(&json_file)
+                .try_err(gettext("Failed to save post data to file."))?;
+        }
+        log::warn!(
+            "{}",
+            gettext("Warning: This article is restricted, only the data of the post is saved.")
+        );
+        return Ok(());
+    }
     let tasks = TaskManager::default();
     let ptasks = TaskManager::new_post();
     let mut re = Ok(());
@@ -946,7 +3028,6 @@ pub async fn download_fanbox_post(
             let mut np = 0;
             let total_page = image_map.len() as u16;
             let mut datas = data.clone();
-            #[cfg(feature = "exif")]
             datas.exif_data.replace(Box::new(Arc::clone(&article)));
             let datas = Arc::new(datas);
             for i in blocks {
@@ -1036,6 +3117,7 @@ pub async fn download_fanbox_post(
                                     None
                                 },
                                 Arc::clone(&base),
+                                Some(datas.clone()),
                             ))
                             .await;
                         if !download_multiple_files {
@@ -1047,6 +3129,10 @@ pub async fn download_fanbox_post(
             }
         }
         FanboxPost::File(file) => {
+            let file = Arc::new(file);
+            let mut datas = data.clone();
+            datas.exif_data.replace(Box::new(Arc::clone(&file)));
+            let datas = Arc::new(datas);
             let body = file
                 .body()
                 .try_err(gettext("Failed to get the body of file post."))?;
@@ -1066,6 +3152,7 @@ pub async fn download_fanbox_post(
                             None
                         },
                         Arc::clone(&base),
+                        Some(datas.clone()),
                     ))
                     .await;
                 if !download_multiple_files {
@@ -1084,7 +3171,6 @@ pub async fn download_fanbox_post(
             let mut np = 0;
             let total_page = images.len() as u16;
             let mut datas = data.clone();
-            #[cfg(feature = "exif")]
             datas.exif_data.replace(Box::new(Arc::clone(&img)));
             let datas = Arc::new(datas);
             for img in images.iter() {
@@ -1115,9 +3201,11 @@ pub async fn download_fanbox_post(
             let text = t
                 .text()
                 .ok_or(gettext("Failed to get text from text post."))?;
-            let text_file = base.join("data.txt");
-            let mut f = File::create(&text_file)?;
-            f.write_all(text.as_bytes())?;
+            if !helper.dry_run() {
+                let text_file = base.join("data.txt");
+                let mut f = File::create(&text_file)?;
+                f.write_all(text.as_bytes())?;
+            }
         }
         FanboxPost::Unknown(_) => {
             return Err(PixivDownloaderError::from(gettext(
@@ -1145,6 +3233,12 @@ pub async fn download_fanbox_post(
         };
         concat_pixiv_downloader_error!(re, r);
     }
+    // The data file is used to check whether the post is downloaded, so save it after all files are downloaded.
+    if re.is_ok() && !helper.dry_run() {
+        data_file
+            .save(&json_file)
+            .try_err(gettext("Failed to save post data to file."))?;
+    }
     re
 }
 
@@ -1196,21 +3290,22 @@ pub async fn download_fanbox_creator_info(
     };
     let json_file = base.join("creator.json");
     let data_file = JSONDataFile::from(&fdata);
-    if !base.exists() {
-        match create_dir_all(&*base) {
-            Ok(_) => {}
-            Err(e) => {
-                if !base.exists() {
-                    return Err(PixivDownloaderError::from(e));
+    if !helper.dry_run() {
+        if !base.exists() {
+            match create_dir_all(&*base) {
+                Ok(_) => {}
+                Err(e) => {
+                    if !base.exists() {
+                        return Err(PixivDownloaderError::from(e));
+                    }
                 }
             }
         }
+        data_file
+            .save(&json_file)
+            .try_err(gettext("Failed to save post data to file."))?;
     }
-    data_file
-        .save(&json_file)
-        .try_err(gettext("Failed to save post data to file."))?;
     let tasks = TaskManager::default();
-    #[cfg(feature = "exif")]
     fdata.exif_data.replace(Box::new(Arc::clone(&data)));
     let fdata = Arc::new(fdata);
     let download_multiple_files = helper.download_multiple_files();
@@ -6,6 +6,7 @@ use super::pd_file::PdFile;
 use super::pd_file::PdFilePartStatus;
 use super::pd_file::PdFileResult;
 use super::tasks::check_tasks;
+use super::verify::verify_file;
 use crate::ext::atomic::AtomicQuick;
 use crate::ext::io::ClearFile;
 use crate::ext::replace::ReplaceWith2;
@@ -33,6 +34,7 @@ use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ops::Drop;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::AtomicU32;
@@ -105,6 +107,14 @@ pub struct DownloaderInternal<
     dropped: AtomicBool,
     /// true if the progress bar has length
     progress_has_length: AtomicBool,
+    /// The timeout when waiting for the next chunk of data.
+    chunk_timeout: RwLock<Duration>,
+    /// The path of the target file
+    path: Option<PathBuf>,
+    /// Whether to verify the file after downloaded
+    verify: AtomicBool,
+    /// Whether to check the whole structure of the file when verifying
+    verify_full: AtomicBool,
 }
 
 impl DownloaderInternal<LocalFile> {
@@ -194,6 +204,10 @@ impl DownloaderInternal<LocalFile> {
             part_size: AtomicU32::new(0x10000),
             dropped: AtomicBool::new(false),
             progress_has_length: AtomicBool::new(false),
+            chunk_timeout: RwLock::new(Duration::new(10, 0)),
+            path: path.map(|p| p.as_ref().to_owned()),
+            verify: AtomicBool::new(true),
+            verify_full: AtomicBool::new(false),
         }))
     }
 }
@@ -279,6 +293,12 @@ impl<T: Write + Seek + Send + Sync + ClearFile + GetTargetFileName + SetLen> Dow
         }
     }
 
+    #[inline]
+    /// Returns the timeout when waiting for the next chunk of data.
+    pub fn get_chunk_timeout(&self) -> Duration {
+        self.chunk_timeout.get_ref().clone()
+    }
+
     #[inline]
     /// Get the file name from url.
     /// If not available, use `(Unknown)`
@@ -386,6 +406,15 @@ impl<T: Write + Seek + Send + Sync + ClearFile + GetTargetFileName + SetLen> Dow
         }
     }
 
+    /// Discard all downloaded data and restart the download in multiple thread mode.
+    pub fn restart_multi_download(&self) -> Result<(), DownloaderError> {
+        self.pd.clear()?;
+        self.pd.enable_multi()?;
+        self.pd.set_part_size(self.part_size.qload())?;
+        self.clear_file()?;
+        Ok(())
+    }
+
     /// Seek in the file.
     /// * `data` - Data
     pub fn seek(&self, pos: SeekFrom) -> Result<u64, DownloaderError> {
@@ -395,6 +424,12 @@ impl<T: Write + Seek + Send + Sync + ClearFile + GetTargetFileName + SetLen> Dow
         }
     }
 
+    #[inline]
+    /// Set the timeout when waiting for the next chunk of data.
+    pub fn set_chunk_timeout(&self, timeout: Duration) {
+        self.chunk_timeout.replace_with2(timeout);
+    }
+
     #[inline]
     /// Set the status to [DownloaderStatus::Downloading] and returns the current value
     pub fn set_downloading(&self) -> DownloaderStatus {
@@ -491,6 +526,47 @@ impl<T: Write + Seek + Send + Sync + ClearFile + GetTargetFileName + SetLen> Dow
         self.retry_interval.replace_with2(retry_interval);
     }
 
+    #[inline]
+    /// Set whether to verify the file after downloaded.
+    pub fn set_verify(&self, verify: bool) {
+        self.verify.qstore(verify)
+    }
+
+    #[inline]
+    /// Set whether to check the whole structure of the file when verifying.
+    pub fn set_verify_full(&self, verify_full: bool) {
+        self.verify_full.qstore(verify_full)
+    }
+
+    /// Verify the downloaded file. Should be called before completing the pd file.
+    ///
+    /// The size of the file is compared with the size from server,
+    /// and the content is checked with the extension of the file.
+    pub fn verify(&self) -> Result<(), DownloaderError> {
+        if !self.verify.qload() {
+            return Ok(());
+        }
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        match self.file.get_mut().deref_mut() {
+            Some(f) => f.flush()?,
+            None => {}
+        }
+        let size = match self.pd.get_file_size() {
+            0 => self.pd.get_content_length(),
+            size => size,
+        };
+        // The file is preallocated, so its size on disk can not be trusted.
+        verify_file(
+            path,
+            size,
+            Some(self.pd.get_written_size()),
+            self.verify_full.qload(),
+        )
+    }
+
     /// Write datas to the file.
     /// * `data` - Data
     pub fn write(&self, data: &[u8]) -> Result<(), DownloaderError> {
@@ -675,6 +751,18 @@ impl<T: Write + Seek + Send + Sync + ClearFile + GetTargetFileName + SetLen + 's
         self.downloader.enable_progress_bar(style, mults)
     }
 
+    #[inline]
+    /// Returns the size of the downloaded data
+    pub fn get_downloaded_file_size(&self) -> u64 {
+        self.downloader.pd.get_downloaded_file_size()
+    }
+
+    #[inline]
+    /// The target size of the file. 0 if unknown.
+    pub fn get_file_size(&self) -> u64 {
+        self.downloader.pd.get_file_size()
+    }
+
     /// Get panic error
     pub fn get_panic(self) -> Option<DownloaderError> {
         self.downloader.get_panic()
@@ -713,6 +801,15 @@ impl<T: Write + Seek + Send + Sync + ClearFile + GetTargetFileName + SetLen + 's
         }
         self.set_max_threads(helper.max_threads());
         self.set_part_size(helper.part_size());
+        self.set_chunk_timeout(helper.download_chunk_timeout());
+        self.set_verify(helper.download_verify());
+        self.set_verify_full(helper.download_verify_full());
+    }
+
+    #[inline]
+    /// Set the timeout when waiting for the next chunk of data.
+    pub fn set_chunk_timeout(&self, timeout: Duration) {
+        self.downloader.set_chunk_timeout(timeout)
     }
 
     #[inline]
@@ -746,6 +843,18 @@ impl<T: Write + Seek + Send + Sync + ClearFile + GetTargetFileName + SetLen + 's
     pub fn set_retry_interval(&self, retry_interval: NonTailList<Duration>) {
         self.downloader.set_retry_interval(retry_interval)
     }
+
+    #[inline]
+    /// Set whether to verify the file after downloaded.
+    pub fn set_verify(&self, verify: bool) {
+        self.downloader.set_verify(verify)
+    }
+
+    #[inline]
+    /// Set whether to check the whole structure of the file when verifying.
+    pub fn set_verify_full(&self, verify_full: bool) {
+        self.downloader.set_verify_full(verify_full)
+    }
     define_downloader_fn!(
         is_created,
         bool,
@@ -1,4 +1,5 @@
 use std::fmt::Display;
+use std::path::Path;
 
 #[derive(Debug)]
 /// The result when try create a new [super::Downloader] interface
@@ -32,3 +33,87 @@ impl Display for DownloaderStatus {
         })
     }
 }
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// What the [super::Downloader] would do with the target file
+pub enum DownloaderAction {
+    /// The target file is not existed and will be downloaded.
+    Download,
+    /// The target file is existed and will be skipped.
+    Skip,
+    /// The target file is existed and will be overwritten.
+    Overwrite,
+    /// The target file is partially downloaded and will be resumed.
+    Resume,
+    /// The target file is existed and the user will be asked whether to overwrite it.
+    Ask,
+}
+
+impl DownloaderAction {
+    /// Predict the action for the target file without touching the disk.
+    /// * `path` - The path of the target file
+    /// * `overwrite` - Whether to overwrite file
+    pub fn predict<P: AsRef<Path> + ?Sized>(path: &P, overwrite: Option<bool>) -> Self {
+        let p = path.as_ref();
+        if !p.exists() {
+            return Self::Download;
+        }
+        let mut pd = p.as_os_str().to_owned();
+        pd.push(".pd");
+        if Path::new(&pd).exists() {
+            return Self::Resume;
+        }
+        match overwrite {
+            Some(true) => Self::Overwrite,
+            Some(false) => Self::Skip,
+            None => Self::Ask,
+        }
+    }
+}
+
+impl Display for DownloaderAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Download => "Download",
+            Self::Skip => "Skip",
+            Self::Overwrite => "Overwrite",
+            Self::Resume => "Resume",
+            Self::Ask => "Ask",
+        })
+    }
+}
+
+#[test]
+fn test_downloader_action() {
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = std::fs::create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let target = p.join("action.txt");
+    let pd = p.join("action.txt.pd");
+    let _ = std::fs::remove_file(&target);
+    let _ = std::fs::remove_file(&pd);
+    assert_eq!(
+        DownloaderAction::predict(&target, Some(false)),
+        DownloaderAction::Download
+    );
+    std::fs::write(&target, "a").unwrap();
+    assert_eq!(
+        DownloaderAction::predict(&target, Some(false)),
+        DownloaderAction::Skip
+    );
+    assert_eq!(
+        DownloaderAction::predict(&target, Some(true)),
+        DownloaderAction::Overwrite
+    );
+    assert_eq!(
+        DownloaderAction::predict(&target, None),
+        DownloaderAction::Ask
+    );
+    std::fs::write(&pd, "").unwrap();
+    assert_eq!(
+        DownloaderAction::predict(&target, Some(false)),
+        DownloaderAction::Resume
+    );
+}
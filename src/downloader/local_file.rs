@@ -36,14 +36,10 @@ impl LocalFile {
         })
     }
 
-    /// Attempts to open a file in read-only mode.
+    /// Attempts to open an existing file without truncating it.
     pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
         let p = path.as_ref().to_owned();
-        let f = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .truncate(true)
-            .open(&p)?;
+        let f = OpenOptions::new().read(true).write(true).open(&p)?;
         Ok(Self {
             file: Some(f),
             path: p,
@@ -12,7 +12,10 @@ pub mod local_file;
 pub mod pd_file;
 /// Deal download tasks
 pub mod tasks;
+/// Verify downloaded files
+pub mod verify;
 pub use downloader::Downloader;
+pub use enums::DownloaderAction;
 pub use enums::DownloaderResult;
 pub use error::DownloaderError;
 pub use helper::DownloaderHelper;
@@ -50,12 +50,14 @@ const FILE_SIZE_OFFSET: SeekFrom = SeekFrom::Start(12);
 const DOWNLOADED_FILE_SIZE_OFFSET: SeekFrom = SeekFrom::Start(20);
 /// The offset of the part_size in pd file
 const PART_SIZE_OFFSET: SeekFrom = SeekFrom::Start(28);
+/// The size of the fixed size part in pd file
+const FIXED_PART_SIZE: u64 = 32;
 
 #[derive(Debug)]
 /// The pd file
 pub struct PdFile {
     /// The version of the current file.
-    version: PdFileVersion,
+    version: RwLock<PdFileVersion>,
     /// Needed to save to file.
     need_saved: AtomicBool,
     /// The file handle of the pd file.
@@ -80,13 +82,21 @@ pub struct PdFile {
     part_datas: RwLock<Vec<Arc<PdFilePartStatus>>>,
     /// The offset of the first part data
     part_data_offset: AtomicU64,
+    /// The source URL of the file.
+    url: RwLock<Option<String>>,
+    /// The `ETag` header of the remote file.
+    etag: RwLock<Option<String>>,
+    /// The `Last-Modified` header of the remote file.
+    last_modified: RwLock<Option<String>>,
+    /// The expected content length of the remote file. 0 if unknown.
+    content_length: AtomicU64,
 }
 
 impl PdFile {
     /// Create a new instance of the [PdFile]
     pub fn new() -> Self {
         Self {
-            version: PdFileVersion::new(1, 0),
+            version: RwLock::new(PdFileVersion::current()),
             need_saved: AtomicBool::new(false),
             file: RwLock::new(None),
             file_path: RwLock::new(None),
@@ -98,10 +108,25 @@ impl PdFile {
             part_size: AtomicU32::new(0),
             mem_only: AtomicBool::new(true),
             part_datas: RwLock::new(Vec::new()),
-            part_data_offset: AtomicU64::new(32),
+            part_data_offset: AtomicU64::new(FIXED_PART_SIZE + 20),
+            url: RwLock::new(None),
+            etag: RwLock::new(None),
+            last_modified: RwLock::new(None),
+            content_length: AtomicU64::new(0),
         }
     }
 
+    /// Returns the offset of the first part data when written in current version.
+    fn calc_part_data_offset(&self) -> u64 {
+        let len = |s: &RwLock<Option<String>>| s.get_ref().as_ref().map_or(0, |s| s.len() as u64);
+        FIXED_PART_SIZE
+            + len(&self.file_name)
+            + 20
+            + len(&self.url)
+            + len(&self.etag)
+            + len(&self.last_modified)
+    }
+
     /// Set the status to the initailzed status.
     pub fn clear(&self) -> Result<(), PdFileError> {
         self.status.replace_with2(PdFileStatus::Started);
@@ -110,6 +135,11 @@ impl PdFile {
         self.downloaded_file_size.qstore(0);
         self.part_size.qstore(0);
         self.part_datas.get_mut().clear();
+        self.url.get_mut().take();
+        self.etag.get_mut().take();
+        self.last_modified.get_mut().take();
+        self.content_length.qstore(0);
+        self.part_data_offset.qstore(self.calc_part_data_offset());
         if !self.is_mem_only() {
             self.need_saved.qstore(true);
             // Remove old file and reopen
@@ -203,12 +233,40 @@ impl PdFile {
         self.downloaded_file_size.qload()
     }
 
+    #[inline]
+    /// Returns the expected content length of the remote file. 0 if unknown.
+    pub fn get_content_length(&self) -> u64 {
+        self.content_length.qload()
+    }
+
+    #[inline]
+    /// Returns the `ETag` header of the remote file.
+    pub fn get_etag(&self) -> Option<String> {
+        self.etag.get_ref().clone()
+    }
+
     #[inline]
     /// The target size of the file. 0 if unknown.
     pub fn get_file_size(&self) -> u64 {
         self.file_size.qload()
     }
 
+    /// Returns the value of the `If-Range` header when resuming the download.
+    /// Weak `ETag` can not be used in `If-Range`, so `Last-Modified` is used instead.
+    pub fn get_if_range(&self) -> Option<String> {
+        match self.etag.get_ref().as_ref() {
+            Some(etag) if !etag.starts_with("W/") => return Some(etag.clone()),
+            _ => {}
+        }
+        self.last_modified.get_ref().clone()
+    }
+
+    #[inline]
+    /// Returns the `Last-Modified` header of the remote file.
+    pub fn get_last_modified(&self) -> Option<String> {
+        self.last_modified.get_ref().clone()
+    }
+
     /// Get the next waited part data
     /// * `data` - The part data if found
     ///
@@ -290,6 +348,21 @@ impl PdFile {
         Ok(())
     }
 
+    /// Returns the size of the data which is actually written to the file.
+    ///
+    /// In multiple thread mode, the downloaded size is not decreased when a part is
+    /// downloaded again, so the sizes of the parts are used instead.
+    pub fn get_written_size(&self) -> u64 {
+        let part_datas = self.part_datas.get_ref();
+        if self.is_multi_threads() && !part_datas.is_empty() {
+            return part_datas
+                .iter()
+                .map(|part| part.downloaded_size() as u64)
+                .sum();
+        }
+        self.downloaded_file_size.qload()
+    }
+
     /// Returns true if all parts are downloaded.
     pub fn is_all_part_downloaded(&self) -> bool {
         #[cfg(test)]
@@ -350,6 +423,16 @@ impl PdFile {
         self.need_saved.qload()
     }
 
+    /// Returns true if the data is downloaded from the url.
+    /// If the source URL is unknown, returns true.
+    /// * `url` - The url of the file
+    pub fn is_same_url<S: AsRef<str> + ?Sized>(&self, url: &S) -> bool {
+        match self.url.get_ref().as_ref() {
+            Some(u) => u == url.as_ref(),
+            None => true,
+        }
+    }
+
     /// Open a new [PdFile] if download is needed.
     /// * `path` - The path of the file which want to download.
     pub fn open<P: AsRef<Path> + ?Sized>(path: &P) -> Result<PdFileResult, PdFileError> {
@@ -363,7 +446,7 @@ impl PdFile {
         pb.set_file_name(&file_name);
         if p.exists() {
             if pb.exists() {
-                match Self::read_from_file(p) {
+                match Self::read_from_file(&pb) {
                     Ok(f) => {
                         if f.is_completed() {
                             return Ok(PdFileResult::TargetExisted);
@@ -405,15 +488,11 @@ impl PdFile {
     /// Returns errors or a new instance.
     pub fn read_from_file<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, PdFileError> {
         let p = path.as_ref();
-        let mut f = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .truncate(true)
-            .open(p)?;
+        let mut f = OpenOptions::new().read(true).write(true).open(p)?;
         f.seek(SeekFrom::Start(0))?;
         let mut buf = [0u8, 0, 0, 0];
         f.read_exact(&mut buf)?;
-        if MAGIC_WORDS.as_ref() == buf {
+        if MAGIC_WORDS.as_ref() != buf {
             return Err(PdFileError::InvalidPdFile);
         }
         let version = PdFileVersion::read_from(&mut f)?;
@@ -427,16 +506,34 @@ impl PdFile {
         let downloaded_file_size = f.read_le_u64()?;
         let part_size = f.read_le_u32()?;
         let file_name = String::from_utf8(f.read_bytes(file_name_len as usize)?)?;
+        let mut part_data_offset = FIXED_PART_SIZE + (file_name_len as u64);
+        let mut content_length = 0;
+        let mut url = None;
+        let mut etag = None;
+        let mut last_modified = None;
+        if version.has_validators() {
+            content_length = f.read_le_u64()?;
+            url = read_str(&mut f)?;
+            etag = read_str(&mut f)?;
+            last_modified = read_str(&mut f)?;
+            let len = |s: &Option<String>| s.as_ref().map_or(0, |s| s.len() as u64);
+            part_data_offset += 20 + len(&url) + len(&etag) + len(&last_modified);
+        }
         let mut part_datas = Vec::new();
         if ftype.is_multi() && file_size != 0 && part_size != 0 {
             let part_counts = (file_size + (part_size as u64) - 1) / (part_size as u64);
             for _ in 0..part_counts {
                 let data = PdFilePartStatus::read_from(&mut f)?;
+                // The parts which are not completed in last time should be downloaded again.
+                if data.is_downloading() {
+                    data.set_waited()?;
+                    data.set_downloaded_size(0)?;
+                }
                 part_datas.push(Arc::new(data));
             }
         }
         Ok(Self {
-            version,
+            version: RwLock::new(version),
             need_saved: AtomicBool::new(false),
             file: RwLock::new(Some(f)),
             file_path: RwLock::new(Some(p.to_path_buf())),
@@ -448,7 +545,11 @@ impl PdFile {
             part_size: AtomicU32::new(part_size),
             mem_only: AtomicBool::new(false),
             part_datas: RwLock::new(part_datas),
-            part_data_offset: AtomicU64::new(32 + (file_name_len as u64)),
+            part_data_offset: AtomicU64::new(part_data_offset),
+            url: RwLock::new(url),
+            etag: RwLock::new(etag),
+            last_modified: RwLock::new(last_modified),
+            content_length: AtomicU64::new(content_length),
         })
     }
 
@@ -538,7 +639,7 @@ impl PdFile {
             Err(gettext("File name should not be empty."))?
         } else {
             self.file_name.get_mut().replace(String::from(fname));
-            self.part_data_offset.qstore(32 + (fname.len() as u64));
+            self.part_data_offset.qstore(self.calc_part_data_offset());
             if !self.is_mem_only() {
                 self.need_saved.qstore(true);
                 self.reopen()?;
@@ -583,6 +684,33 @@ impl PdFile {
         Ok(())
     }
 
+    /// Set the source URL and the validators of the remote file.
+    /// * `url` - The url of the file
+    /// * `etag` - The `ETag` header
+    /// * `last_modified` - The `Last-Modified` header
+    /// * `content_length` - The expected content length. 0 if unknown.
+    pub fn set_validators<S: AsRef<str> + ?Sized>(
+        &self,
+        url: &S,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+        content_length: u64,
+    ) -> Result<(), PdFileError> {
+        self.url.get_mut().replace(url.as_ref().to_owned());
+        self.etag.replace_with2(etag.map(|s| s.to_owned()));
+        self.last_modified
+            .replace_with2(last_modified.map(|s| s.to_owned()));
+        self.content_length.qstore(content_length);
+        self.part_data_offset.qstore(self.calc_part_data_offset());
+        if !self.is_mem_only() {
+            self.need_saved.qstore(true);
+            self.reopen()?;
+            // Rewrite all datas.
+            self.write()?;
+        }
+        Ok(())
+    }
+
     /// Set the size of the each part. Ignored in single thread mode.
     pub fn set_part_size(&self, part_size: u32) -> Result<(), PdFileError> {
         self.part_size.qstore(part_size);
@@ -615,7 +743,7 @@ impl PdFile {
             match f.as_mut() {
                 Some(f) => {
                     f.seek(SeekFrom::Start(
-                        self.part_data_offset.qload() + 2 * (index as u64),
+                        self.part_data_offset.qload() + 4 * (index as u64),
                     ))?;
                     part.write_to(f)?;
                     self.need_saved.qstore(false);
@@ -628,11 +756,14 @@ impl PdFile {
 
     /// Write all data to the file.
     pub fn write(&self) -> Result<(), PdFileError> {
+        self.part_data_offset.qstore(self.calc_part_data_offset());
         let mut f = self.file.get_mut();
         let mut f = f.as_mut().try_err(gettext("The file is not opened."))?;
         f.seek(SeekFrom::Start(0))?;
         f.write_all(&MAGIC_WORDS)?;
-        self.version.write_to(&mut f)?;
+        let version = PdFileVersion::current();
+        version.write_to(&mut f)?;
+        self.version.replace_with2(version);
         let file_name = self
             .file_name
             .get_ref()
@@ -652,6 +783,10 @@ impl PdFile {
         };
         f.write_le_u32(part_size)?;
         f.write_all(file_name)?;
+        f.write_le_u64(self.content_length.qload())?;
+        write_str(&mut f, self.url.get_ref().as_deref())?;
+        write_str(&mut f, self.etag.get_ref().as_deref())?;
+        write_str(&mut f, self.last_modified.get_ref().as_deref())?;
         if ftype.is_multi() && file_size != 0 && part_size != 0 {
             let part_counts = (file_size + (part_size as u64) - 1) / (part_size as u64);
             let part_datas = self.part_datas.get_ref();
@@ -666,6 +801,23 @@ impl PdFile {
     }
 }
 
+/// Read a string with its length. Empty string is treated as [None].
+fn read_str<R: Read>(f: &mut R) -> Result<Option<String>, PdFileError> {
+    let len = f.read_le_u32()?;
+    if len == 0 {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8(f.read_bytes(len as usize)?)?))
+}
+
+/// Write a string with its length. [None] is written as empty string.
+fn write_str<W: Write>(f: &mut W, s: Option<&str>) -> Result<(), PdFileError> {
+    let s = s.unwrap_or("").as_bytes();
+    f.write_le_u32(s.len() as u32)?;
+    f.write_all(s)?;
+    Ok(())
+}
+
 impl Drop for PdFile {
     fn drop(&mut self) {
         #[cfg(test)]
@@ -721,6 +873,138 @@ fn test_pd_file() -> Result<(), PdFileError> {
         f.open_with_create_file("test/a.pd")?;
         f.set_file_name("a")?;
     }
-    check_file_size("test/a.pd", 33)?;
+    check_file_size("test/a.pd", 53)?;
+    Ok(())
+}
+
+#[test]
+fn test_pd_file_validators() -> Result<(), PdFileError> {
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    {
+        let f = PdFile::new();
+        f.open_with_create_file("test/b.pd")?;
+        f.set_file_name("b")?;
+        f.set_validators(
+            "https://i.pximg.net/b.png",
+            Some("\"abc\""),
+            Some("Mon, 01 Jan 2024 00:00:00 GMT"),
+            100,
+        )?;
+        f.set_file_size(100)?;
+        f.inc(40)?;
+    }
+    {
+        let f = PdFile::read_from_file("test/b.pd")?;
+        assert!(f.is_downloading());
+        assert_eq!(f.get_file_size(), 100);
+        assert_eq!(f.get_downloaded_file_size(), 40);
+        assert_eq!(f.get_content_length(), 100);
+        assert!(f.is_same_url("https://i.pximg.net/b.png"));
+        assert!(!f.is_same_url("https://i.pximg.net/c.png"));
+        assert_eq!(f.get_if_range(), Some(String::from("\"abc\"")));
+        assert_eq!(
+            f.get_last_modified(),
+            Some(String::from("Mon, 01 Jan 2024 00:00:00 GMT"))
+        );
+        f.clear()?;
+        assert_eq!(f.get_etag(), None);
+        assert!(f.is_same_url("https://i.pximg.net/c.png"));
+    }
+    // Version 1.0 file without validators
+    let mut data = MAGIC_WORDS.clone();
+    data.extend_from_slice(&[1, 0]);
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&[1, 0]);
+    data.extend_from_slice(&100u64.to_le_bytes());
+    data.extend_from_slice(&40u64.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(b"c");
+    std::fs::write("test/c.pd", &data)?;
+    {
+        let f = PdFile::read_from_file("test/c.pd")?;
+        assert!(f.is_downloading());
+        assert_eq!(f.get_file_size(), 100);
+        assert_eq!(f.get_downloaded_file_size(), 40);
+        assert_eq!(f.get_if_range(), None);
+        assert!(f.is_same_url("https://i.pximg.net/c.png"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_pd_file_reopen() -> Result<(), PdFileError> {
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let mut data = MAGIC_WORDS.clone();
+    data[0] = 0;
+    std::fs::write("test/d.pd", &data)?;
+    assert!(matches!(
+        PdFile::read_from_file("test/d.pd"),
+        Err(PdFileError::InvalidPdFile)
+    ));
+    {
+        let f = PdFile::new();
+        f.open_with_create_file("test/e.pd")?;
+        f.set_file_name("e")?;
+        f.set_validators("https://i.pximg.net/e.png", None, None, 10)?;
+        f.enable_multi()?;
+        f.set_part_size(4)?;
+        f.set_file_size(10)?;
+        f.initialize_part_datas()?;
+        // Every part data takes 4 bytes.
+        for i in 1..3 {
+            f.get_part_data(i).unwrap().set_downloaded()?;
+            f.update_part_data(i)?;
+        }
+    }
+    let size = metadata("test/e.pd")?.len();
+    {
+        let f = PdFile::read_from_file("test/e.pd")?;
+        assert!(f.is_multi_threads());
+        assert!(f.get_part_data(0).unwrap().is_waited());
+        assert!(f.get_part_data(1).unwrap().is_downloaded());
+        assert!(f.get_part_data(2).unwrap().is_downloaded());
+    }
+    // Reading the pd file should not truncate it.
+    check_file_size("test/e.pd", size)?;
+    // The pd file should be read instead of the target file.
+    std::fs::write("test/e", b"")?;
+    match PdFile::open("test/e")? {
+        PdFileResult::ExistedOk(f) => {
+            assert!(f.is_same_url("https://i.pximg.net/e.png"));
+            assert!(!f.is_same_url("https://i.pximg.net/f.png"));
+            assert!(f.get_part_data(1).unwrap().is_downloaded());
+        }
+        r => panic!("Unexpected result: {:?}", r),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_pd_file_written_size() -> Result<(), PdFileError> {
+    let f = PdFile::new();
+    f.inc(3)?;
+    assert_eq!(f.get_written_size(), 3);
+    f.clear()?;
+    f.enable_multi()?;
+    f.set_part_size(4)?;
+    f.set_file_size(10)?;
+    f.initialize_part_datas()?;
+    let part = f.get_part_data(0).unwrap();
+    part.inc(3)?;
+    f.inc(3)?;
+    // The part failed and is downloaded again.
+    part.set_downloaded_size(0)?;
+    part.inc(4)?;
+    f.inc(4)?;
+    assert_eq!(f.get_downloaded_file_size(), 7);
+    assert_eq!(f.get_written_size(), 4);
     Ok(())
 }
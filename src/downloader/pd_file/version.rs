@@ -37,9 +37,19 @@ impl PdFileVersion {
         }
     }
 
+    /// Returns the version used to write pd files.
+    pub fn current() -> Self {
+        Self::new(1, 1)
+    }
+
+    /// Returns true if the source URL and the validators are stored in the pd file.
+    pub fn has_validators(&self) -> bool {
+        *self >= [1, 1]
+    }
+
     /// Check the version is supported or not.
     pub fn is_supported(&self) -> bool {
-        *self <= [1, 0]
+        *self <= Self::current()
     }
 
     /// Get version bytes
@@ -140,5 +150,10 @@ fn test_pd_file_version() {
     assert!(PdFileVersion::new(2, 3) > [1, 10]);
     assert!(PdFileVersion::new(3, 1) < [10, 1]);
     assert!(PdFileVersion::new(3, 3) >= [3, 3]);
-    assert!(!(PdFileVersion::new(2, 3) > [1]))
+    assert!(!(PdFileVersion::new(2, 3) > [1]));
+    assert!(PdFileVersion::new(1, 0).is_supported());
+    assert!(!PdFileVersion::new(1, 0).has_validators());
+    assert!(PdFileVersion::current().is_supported());
+    assert!(PdFileVersion::current().has_validators());
+    assert!(!PdFileVersion::new(2, 0).is_supported());
 }
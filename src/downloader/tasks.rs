@@ -2,7 +2,9 @@ use super::downloader::DownloaderInternal;
 use super::downloader::GetTargetFileName;
 use super::downloader::SetLen;
 use super::error::DownloaderError;
+use super::pd_file::PdFile;
 use super::pd_file::PdFilePartStatus;
+use crate::bandwidth::{get_limiter, BandwidthLimitType};
 use crate::concat_error;
 use crate::ext::atomic::AtomicQuick;
 use crate::ext::io::ClearFile;
@@ -13,6 +15,7 @@ use crate::gettext;
 use futures_util::StreamExt;
 use http_content_range::ContentRange;
 use itertools::partition;
+use wreq::header::HeaderMap;
 use wreq::Response;
 use std::io::Seek;
 use std::io::SeekFrom;
@@ -42,6 +45,86 @@ macro_rules! check_dropped {
     };
 }
 
+/// Returns the value of the header.
+/// * `headers` - The headers of the response
+/// * `name` - The name of the header
+fn get_header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Returns true if the remote file is changed since the download started.
+/// * `pd` - The pd file
+/// * `headers` - The headers of the response
+fn is_remote_changed(pd: &PdFile, headers: &HeaderMap) -> bool {
+    if let (Some(old), Some(new)) = (pd.get_etag(), get_header(headers, "ETag")) {
+        if old != new {
+            return true;
+        }
+    }
+    if let (Some(old), Some(new)) = (pd.get_last_modified(), get_header(headers, "Last-Modified")) {
+        if old != new {
+            return true;
+        }
+    }
+    false
+}
+
+/// Save the source URL and the validators of the remote file to the pd file.
+/// * `d` - The downloader
+/// * `re` - The response which contains the whole file
+fn save_validators<T: Seek + Write + Send + Sync + ClearFile + GetTargetFileName + SetLen>(
+    d: &DownloaderInternal<T>,
+    re: &Response,
+) -> Result<(), DownloaderError> {
+    let headers = re.headers();
+    d.pd.set_validators(
+        d.url.as_str(),
+        get_header(headers, "ETag"),
+        get_header(headers, "Last-Modified"),
+        re.content_length().unwrap_or(0),
+    )?;
+    Ok(())
+}
+
+/// Returns true if the remote file is not changed and the download can be resumed.
+/// Used before resuming a download in multiple thread mode.
+async fn check_remote_file<
+    T: Seek + Write + Send + Sync + ClearFile + GetTargetFileName + SetLen,
+>(
+    d: &Arc<DownloaderInternal<T>>,
+) -> Result<bool, DownloaderError> {
+    if !d.pd.is_same_url(d.url.as_str()) {
+        return Ok(false);
+    }
+    let mut headers = d.headers.deref().clone();
+    headers.insert(String::from("Range"), String::from("bytes=0-0"));
+    match d.pd.get_if_range() {
+        Some(v) => {
+            headers.insert(String::from("If-Range"), v);
+        }
+        None => {}
+    }
+    let result = d
+        .client
+        .get(d.url.deref().clone(), headers)
+        .await
+        .try_err(gettext("Failed to get url."))?;
+    let status = result.status();
+    if status.as_u16() >= 400 && status != 416 {
+        return Err(DownloaderError::from(status));
+    }
+    if status != 206 || is_remote_changed(&d.pd, result.headers()) {
+        return Ok(false);
+    }
+    match get_header(result.headers(), "Content-Range") {
+        Some(v) => match ContentRange::parse_bytes(v.as_bytes()) {
+            ContentRange::Bytes(b) => Ok(b.complete_length == d.pd.get_file_size()),
+            _ => Ok(false),
+        },
+        None => Ok(false),
+    }
+}
+
 /// Create a download tasks in simple thread mode.
 pub async fn create_download_tasks_simple<
     T: Seek + Write + Send + Sync + ClearFile + GetTargetFileName + SetLen,
@@ -56,23 +139,40 @@ pub async fn create_download_tasks_simple<
     let file_size = d.pd.get_file_size();
     let mut headers = d.headers.deref().clone();
     if start != 0 {
-        match d.seek(SeekFrom::Start(start)) {
-            Ok(data) => {
-                if data != start {
+        if !d.pd.is_same_url(d.url.as_str()) {
+            // The data is downloaded from another url.
+            start = 0;
+        } else {
+            match d.seek(SeekFrom::Start(start)) {
+                Ok(data) => {
+                    if data != start {
+                        start = 0;
+                    }
+                }
+                Err(_) => {
                     start = 0;
                 }
             }
-            Err(_) => {
-                start = 0;
-            }
         }
         if start == 0 {
             d.seek(SeekFrom::Start(0))?;
             d.pd.clear()?;
         }
+    } else if d.pd.get_downloaded_file_size() != 0 {
+        // The pd file is not in downloading status, so the partially downloaded data
+        // can not be trusted. Restart from the beginning.
+        d.pd.clear()?;
+        d.clear_file()?;
     }
     if start != 0 {
         headers.insert(String::from("Range"), format!("bytes={}-", start));
+        // The server will send the whole file if the remote file is changed.
+        match d.pd.get_if_range() {
+            Some(v) => {
+                headers.insert(String::from("If-Range"), v);
+            }
+            None => {}
+        }
     }
     let mut result = d
         .client
@@ -81,6 +181,8 @@ pub async fn create_download_tasks_simple<
         .try_err(gettext("Failed to get url."))?;
     let mut status = result.status();
     if status == 416 {
+        d.pd.clear()?;
+        d.clear_file()?;
         result = d
             .client
             .get(d.url.deref().clone(), d.headers.deref().clone())
@@ -89,11 +191,16 @@ pub async fn create_download_tasks_simple<
         status = result.status();
     } else if status == 206 {
         let headers = result.headers();
-        let need_reget = if headers.contains_key("Content-Range") {
+        let content_length = d.pd.get_content_length();
+        let need_reget = if is_remote_changed(&d.pd, headers) {
+            true
+        } else if headers.contains_key("Content-Range") {
             match ContentRange::parse_bytes(headers["Content-Range"].as_bytes()) {
                 ContentRange::Bytes(b) => {
                     if file_size != 0 && b.complete_length != file_size {
                         true
+                    } else if content_length != 0 && b.complete_length != content_length {
+                        true
                     } else if start != b.first_byte {
                         true
                     } else {
@@ -123,11 +230,19 @@ pub async fn create_download_tasks_simple<
                 .try_err(gettext("Failed to get url."))?;
             status = result.status();
         }
+    } else if start != 0 && status == 200 {
+        // The server does not support range or the remote file is changed,
+        // restart from the beginning.
+        d.pd.clear()?;
+        d.clear_file()?;
     }
     if status.as_u16() >= 400 {
         return Err(DownloaderError::from(status));
     }
-    if file_size == 0 && status != 206 {
+    if status == 200 {
+        save_validators(&d, &result)?;
+    }
+    if d.pd.get_file_size() == 0 && status != 206 {
         match result.content_length() {
             Some(len) => {
                 d.pd.set_file_size(len)?;
@@ -140,6 +255,11 @@ pub async fn create_download_tasks_simple<
         }
     }
     if d.enabled_progress_bar() {
+        let file_size = d.pd.get_file_size();
+        if file_size != 0 {
+            d.set_progress_bar_length(file_size);
+        }
+        d.set_progress_bar_position(d.pd.get_downloaded_file_size());
         d.set_progress_bar_message(
             gettext("Downloading \"<loc>\".").replace("<loc>", d.get_file_name().as_str()),
         );
@@ -170,6 +290,7 @@ pub async fn create_download_tasks_multi_first<
     if status.as_u16() >= 400 {
         return Err(DownloaderError::from(status));
     }
+    save_validators(&d, &result)?;
     match result.content_length() {
         Some(len) => {
             match d.pd.set_file_size(len) {
@@ -240,6 +361,12 @@ pub async fn create_download_tasks_multi_internal<
     let end = std::cmp::min(start + part_size - 1, file_size);
     let mut headers = d.headers.deref().clone();
     headers.insert(String::from("Range"), format!("bytes={}-{}", start, end));
+    match d.pd.get_if_range() {
+        Some(v) => {
+            headers.insert(String::from("If-Range"), v);
+        }
+        None => {}
+    }
     let result = d
         .client
         .get(d.url.deref().clone(), headers)
@@ -275,21 +402,25 @@ pub async fn handle_download<
 ) -> Result<(), DownloaderError> {
     let mut stream = re.bytes_stream();
     let is_multi = d.is_multi_threads();
+    let chunk_timeout = d.get_chunk_timeout();
     loop {
         let mut n = stream.next();
         check_dropped!(d);
-        let re = tokio::time::timeout(std::time::Duration::from_secs(10), &mut n).await;
+        let re = tokio::time::timeout(chunk_timeout, &mut n).await;
         match re {
             Ok(s) => match s {
                 Some(data) => match data {
                     Ok(data) => {
+                        get_limiter(BandwidthLimitType::Download)
+                            .acquire(data.len() as u64)
+                            .await;
                         if !is_multi {
                             let len = data.len() as u64;
+                            d.write(&data)?;
                             d.pd.inc(len)?;
                             if d.enabled_progress_bar() {
                                 d.inc_progress_bar(len);
                             }
-                            d.write(&data)?;
                         } else {
                             if !d.is_multi_threads() {
                                 return Ok(());
@@ -307,9 +438,8 @@ pub async fn handle_download<
                     }
                     Err(e) => {
                         if !is_multi {
-                            d.pd.clear()?;
+                            // Keep the downloaded data, the next try will resume from it.
                             if d.enabled_progress_bar() {
-                                d.set_progress_bar_position(0);
                                 d.set_progress_bar_message(format!(
                                     "{} {}",
                                     gettext("Error when downloading file:"),
@@ -326,6 +456,12 @@ pub async fn handle_download<
                 },
                 None => {
                     if !is_multi {
+                        if let Err(e) = d.verify() {
+                            // The downloaded data is broken, the next try will download it again.
+                            d.pd.clear()?;
+                            d.clear_file()?;
+                            return Err(e);
+                        }
                         d.pd.complete()?;
                         if d.enabled_progress_bar() {
                             d.finish_progress_bar_with_message(format!(
@@ -350,9 +486,7 @@ pub async fn handle_download<
             },
             Err(e) => {
                 if !is_multi {
-                    d.pd.clear()?;
                     if d.enabled_progress_bar() {
-                        d.set_progress_bar_position(0);
                         d.set_progress_bar_message(format!(
                             "{} {}",
                             gettext("Error when downloading file:"),
@@ -413,6 +547,14 @@ pub async fn check_tasks<
         if d.pd.is_started() {
             let task = tokio::spawn(create_download_tasks_multi_first(Arc::clone(&d)));
             d.add_task(task);
+        } else if !check_remote_file(&d).await? {
+            log::warn!(
+                "{}",
+                gettext("Warning: The remote file is changed, restart the download.")
+            );
+            d.restart_multi_download()?;
+            let task = tokio::spawn(create_download_tasks_multi_first(Arc::clone(&d)));
+            d.add_task(task);
         } else {
             add_new_multi_tasks(&d).await?;
         }
@@ -492,21 +634,40 @@ pub async fn check_tasks<
                     add_new_multi_tasks(&d).await?;
                 }
                 if d.pd.is_all_part_downloaded() {
-                    match d.pd.complete() {
-                        Ok(_) => {
-                            need_break = true;
-                            d.set_downloaded();
-                            if d.enabled_progress_bar() {
-                                d.finish_progress_bar_with_message(format!(
-                                    "{} {}",
-                                    gettext("Downloaded file:"),
-                                    d.get_target_file_name()
-                                        .unwrap_or(String::from("(unknown)"))
-                                ));
+                    if let Err(e) = d.verify() {
+                        log::error!("{}", e);
+                        match d.get_retry_duration() {
+                            Some(dur) => {
+                                d.restart_multi_download()?;
+                                if !dur.is_zero() {
+                                    tokio::time::sleep(dur).await;
+                                }
+                                let task =
+                                    tokio::spawn(create_download_tasks_multi_first(Arc::clone(&d)));
+                                d.add_task(task);
+                            }
+                            None => {
+                                d.set_panic(e);
+                                need_break = true;
                             }
                         }
-                        Err(e) => {
-                            log::error!("{}", e);
+                    } else {
+                        match d.pd.complete() {
+                            Ok(_) => {
+                                need_break = true;
+                                d.set_downloaded();
+                                if d.enabled_progress_bar() {
+                                    d.finish_progress_bar_with_message(format!(
+                                        "{} {}",
+                                        gettext("Downloaded file:"),
+                                        d.get_target_file_name()
+                                            .unwrap_or(String::from("(unknown)"))
+                                    ));
+                                }
+                            }
+                            Err(e) => {
+                                log::error!("{}", e);
+                            }
                         }
                     }
                 }
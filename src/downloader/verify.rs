@@ -0,0 +1,522 @@
+use super::error::DownloaderError;
+use crate::gettext;
+use sha2::Digest;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The signature of PNG files
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// The lookup table of CRC-32, used by PNG chunks and ZIP entries
+const CRC32_TABLE: [u32; 256] = make_crc32_table();
+
+const fn make_crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+}
+
+/// Calculate the CRC-32 of the data.
+fn crc32(data: &[u8]) -> u32 {
+    let mut c = 0xFFFFFFFFu32;
+    for b in data {
+        c = CRC32_TABLE[((c ^ *b as u32) & 0xFF) as usize] ^ (c >> 8);
+    }
+    c ^ 0xFFFFFFFF
+}
+
+fn read_u16_be(data: &[u8], pos: usize) -> Option<usize> {
+    Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?) as usize)
+}
+
+fn read_u16_le(data: &[u8], pos: usize) -> Option<usize> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?) as usize)
+}
+
+fn read_u32_be(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn read_u32_le(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+/// The type of the files which can be verified
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyFileType {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Zip,
+}
+
+impl VerifyFileType {
+    /// Get the type from the extension of the file.
+    /// Returns [None] if the type is not supported.
+    pub fn from_path<P: AsRef<Path> + ?Sized>(path: &P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "gif" => Some(Self::Gif),
+            "webp" => Some(Self::Webp),
+            "zip" => Some(Self::Zip),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the data starts with the magic bytes of this type.
+    /// At least 12 bytes are needed.
+    pub fn check_magic(&self, data: &[u8]) -> bool {
+        match self {
+            Self::Jpeg => data.starts_with(&[0xFF, 0xD8, 0xFF]),
+            Self::Png => data.starts_with(PNG_SIGNATURE),
+            Self::Gif => data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a"),
+            Self::Webp => data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP"),
+            Self::Zip => data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06"),
+        }
+    }
+
+    /// Returns true if the whole structure of the data is complete.
+    /// The compressed image data is not decoded.
+    pub fn check_structure(&self, data: &[u8]) -> bool {
+        match self {
+            Self::Jpeg => check_jpeg(data),
+            Self::Png => check_png(data),
+            Self::Gif => check_gif(data),
+            Self::Webp => check_webp(data),
+            Self::Zip => check_zip(data),
+        }
+    }
+}
+
+/// Walk all segments and the entropy-coded data until the EOI marker.
+fn check_jpeg(data: &[u8]) -> bool {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return false;
+    }
+    let mut pos = 2;
+    loop {
+        // Skip fill bytes before the marker.
+        while data.get(pos) == Some(&0xFF) && data.get(pos + 1) == Some(&0xFF) {
+            pos += 1;
+        }
+        if data.get(pos) != Some(&0xFF) {
+            return false;
+        }
+        let marker = match data.get(pos + 1) {
+            Some(m) => *m,
+            None => return false,
+        };
+        pos += 2;
+        match marker {
+            0xD9 => return true,
+            0x01 | 0xD0..=0xD7 => continue,
+            _ => {}
+        }
+        let len = match read_u16_be(data, pos) {
+            Some(len) if len >= 2 => len,
+            _ => return false,
+        };
+        pos += len;
+        if pos > data.len() {
+            return false;
+        }
+        if marker == 0xDA {
+            // The entropy-coded data ends at the first marker which is
+            // neither a stuffed byte nor a restart marker.
+            loop {
+                let next = match data.get(pos + 1) {
+                    Some(next) => *next,
+                    None => return false,
+                };
+                if data[pos] == 0xFF && next != 0x00 && !(0xD0..=0xD7).contains(&next) {
+                    break;
+                }
+                pos += if data[pos] == 0xFF { 2 } else { 1 };
+            }
+        }
+    }
+}
+
+/// Walk all chunks, check their CRC and make sure the file ends with IEND.
+fn check_png(data: &[u8]) -> bool {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return false;
+    }
+    let mut pos = PNG_SIGNATURE.len();
+    loop {
+        let len = match read_u32_be(data, pos) {
+            Some(len) => len as usize,
+            None => return false,
+        };
+        let end = match (pos + 8).checked_add(len) {
+            Some(end) => end,
+            None => return false,
+        };
+        let crc = match read_u32_be(data, end) {
+            Some(crc) => crc,
+            None => return false,
+        };
+        if crc32(&data[pos + 4..end]) != crc {
+            return false;
+        }
+        if &data[pos + 4..pos + 8] == b"IEND" {
+            return true;
+        }
+        pos = end + 4;
+    }
+}
+
+/// Skip GIF data sub-blocks. Returns the position after the block terminator.
+fn skip_gif_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let size = *data.get(pos)? as usize;
+        pos += 1;
+        if size == 0 {
+            return Some(pos);
+        }
+        pos += size;
+    }
+}
+
+/// Walk all blocks until the trailer.
+fn check_gif(data: &[u8]) -> bool {
+    if data.len() < 13 || !VerifyFileType::Gif.check_magic(data) {
+        return false;
+    }
+    let mut pos = 13;
+    if data[10] & 0x80 != 0 {
+        pos += 3 << ((data[10] & 7) + 1);
+    }
+    loop {
+        match data.get(pos) {
+            Some(0x3B) => return true,
+            Some(0x21) => {
+                pos = match skip_gif_sub_blocks(data, pos + 2) {
+                    Some(pos) => pos,
+                    None => return false,
+                };
+            }
+            Some(0x2C) => {
+                let flags = match data.get(pos + 9) {
+                    Some(flags) => *flags,
+                    None => return false,
+                };
+                pos += 10;
+                if flags & 0x80 != 0 {
+                    pos += 3 << ((flags & 7) + 1);
+                }
+                // Skip LZW minimum code size
+                pos = match skip_gif_sub_blocks(data, pos + 1) {
+                    Some(pos) => pos,
+                    None => return false,
+                };
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Walk all chunks in the RIFF container.
+fn check_webp(data: &[u8]) -> bool {
+    if !VerifyFileType::Webp.check_magic(data) {
+        return false;
+    }
+    let end = match read_u32_le(data, 4) {
+        Some(size) => 8 + size as usize,
+        None => return false,
+    };
+    if end > data.len() {
+        return false;
+    }
+    let mut pos = 12;
+    while pos < end {
+        let size = match read_u32_le(data, pos + 4) {
+            Some(size) => size as usize,
+            None => return false,
+        };
+        pos += 8 + size + (size & 1);
+    }
+    pos == end || pos == end + 1
+}
+
+/// Walk the central directory and check all local file headers.
+/// The CRC of stored entries is checked too.
+fn check_zip(data: &[u8]) -> bool {
+    if data.len() < 22 {
+        return false;
+    }
+    let min = data.len().saturating_sub(22 + 0xFFFF);
+    let eocd = match (min..=data.len() - 22)
+        .rev()
+        .find(|i| data[*i..].starts_with(b"PK\x05\x06"))
+    {
+        Some(eocd) => eocd,
+        None => return false,
+    };
+    let count = read_u16_le(data, eocd + 10).unwrap_or(0);
+    let cd_size = read_u32_le(data, eocd + 12).unwrap_or(0) as usize;
+    let cd_offset = read_u32_le(data, eocd + 16).unwrap_or(0) as usize;
+    if cd_offset + cd_size > eocd {
+        return false;
+    }
+    let mut pos = cd_offset;
+    for _ in 0..count {
+        if !data[pos..].starts_with(b"PK\x01\x02") || pos + 46 > eocd {
+            return false;
+        }
+        let method = read_u16_le(data, pos + 10).unwrap_or(0);
+        let crc = read_u32_le(data, pos + 16).unwrap_or(0);
+        let compressed_size = read_u32_le(data, pos + 20).unwrap_or(0);
+        let size = read_u32_le(data, pos + 24).unwrap_or(0);
+        let name_len = read_u16_le(data, pos + 28).unwrap_or(0);
+        let extra_len = read_u16_le(data, pos + 30).unwrap_or(0);
+        let comment_len = read_u16_le(data, pos + 32).unwrap_or(0);
+        let offset = read_u32_le(data, pos + 42).unwrap_or(0) as usize;
+        pos += 46 + name_len + extra_len + comment_len;
+        if pos > cd_offset + cd_size {
+            return false;
+        }
+        if compressed_size == u32::MAX || size == u32::MAX || offset == u32::MAX as usize {
+            // ZIP64 entries are not checked.
+            continue;
+        }
+        if !data[offset.min(data.len())..].starts_with(b"PK\x03\x04") {
+            return false;
+        }
+        let local_name_len = read_u16_le(data, offset + 26).unwrap_or(0);
+        let local_extra_len = read_u16_le(data, offset + 28).unwrap_or(0);
+        let start = offset + 30 + local_name_len + local_extra_len;
+        let end = start + compressed_size as usize;
+        if end > cd_offset {
+            return false;
+        }
+        if method == 0 && (compressed_size != size || crc32(&data[start..end]) != crc) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Verify the downloaded file.
+/// The type of the file is detected from the extension. Unknown types only
+/// have their size checked.
+/// * `path` - The path of the file
+/// * `size` - The expected size of the file. 0 if unknown.
+/// * `written` - The size of the data actually written. [None] to use the size of the file.
+/// The file may be preallocated to its full size before downloading.
+/// * `full` - Whether to check the whole structure of the file.
+pub fn verify_file<P: AsRef<Path> + ?Sized>(
+    path: &P,
+    size: u64,
+    written: Option<u64>,
+    full: bool,
+) -> Result<(), DownloaderError> {
+    let path = path.as_ref();
+    let len = match written {
+        Some(written) => written,
+        None => std::fs::metadata(path)?.len(),
+    };
+    if size != 0 && len != size {
+        return Err(DownloaderError::String(format!(
+            "{} {} != {}",
+            gettext("The size of the file does not match:"),
+            len,
+            size
+        )));
+    }
+    let typ = match VerifyFileType::from_path(path) {
+        Some(typ) => typ,
+        None => return Ok(()),
+    };
+    let mut data = Vec::new();
+    let mut f = File::open(path)?;
+    if full {
+        f.read_to_end(&mut data)?;
+    } else {
+        f.take(12).read_to_end(&mut data)?;
+    }
+    if !typ.check_magic(&data) {
+        return Err(DownloaderError::from(gettext(
+            "The content of the file does not match its extension.",
+        )));
+    }
+    if full && !typ.check_structure(&data) {
+        return Err(DownloaderError::from(gettext(
+            "The file is truncated or corrupted.",
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the SHA-256 hash of the file.
+/// * `path` - The path of the file
+pub fn file_sha256<P: AsRef<Path> + ?Sized>(path: &P) -> std::io::Result<String> {
+    let mut f = File::open(path)?;
+    let mut hasher = sha2::Sha256::new();
+    let mut buf = [0u8; 65536];
+    loop {
+        let len = f.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        hasher.update(&buf[..len]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Returns the path of the file which stores the SHA-256 hash. (`<file>.sha256`)
+/// * `path` - The path of the file
+pub fn get_sha256_file_path<P: AsRef<Path> + ?Sized>(path: &P) -> PathBuf {
+    let mut p = OsString::from(path.as_ref().as_os_str());
+    p.push(".sha256");
+    PathBuf::from(p)
+}
+
+/// Calculate the SHA-256 hash of the file and write it next to the file
+/// in the format of `sha256sum`. Returns the hash.
+/// * `path` - The path of the file
+pub fn write_sha256_file<P: AsRef<Path> + ?Sized>(path: &P) -> std::io::Result<String> {
+    let path = path.as_ref();
+    let hash = file_sha256(path)?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    std::fs::write(get_sha256_file_path(path), format!("{}  {}\n", hash, name))?;
+    Ok(hash)
+}
+
+/// Read the SHA-256 hash stored next to the file.
+/// Returns [None] if not available.
+/// * `path` - The path of the file
+pub fn read_sha256_file<P: AsRef<Path> + ?Sized>(path: &P) -> Option<String> {
+    let s = std::fs::read_to_string(get_sha256_file_path(path)).ok()?;
+    let hash = s.split_whitespace().next()?;
+    if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(hash.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+/// Build a PNG chunk
+fn png_chunk(typ: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut re = Vec::new();
+    re.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    re.extend_from_slice(typ);
+    re.extend_from_slice(data);
+    let crc = crc32(&re[4..]);
+    re.extend_from_slice(&crc.to_be_bytes());
+    re
+}
+
+#[test]
+fn test_verify_structure() {
+    assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    let mut png = PNG_SIGNATURE.to_vec();
+    png.extend(png_chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]));
+    png.extend(png_chunk(b"IDAT", &[0x78, 0x9C, 0x63, 0, 0, 0, 1, 0, 1]));
+    png.extend(png_chunk(b"IEND", &[]));
+    assert!(VerifyFileType::Png.check_magic(&png));
+    assert!(VerifyFileType::Png.check_structure(&png));
+    assert!(!VerifyFileType::Png.check_structure(&png[..png.len() - 12]));
+    assert!(!VerifyFileType::Jpeg.check_magic(&png));
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 0, 4, 0, 0];
+    jpeg.extend_from_slice(&[0xFF, 0xDA, 0, 2, 0x12, 0xFF, 0x00, 0x34, 0xFF, 0xD0, 0x56]);
+    assert!(!VerifyFileType::Jpeg.check_structure(&jpeg));
+    jpeg.extend_from_slice(&[0xFF, 0xD9]);
+    assert!(VerifyFileType::Jpeg.check_magic(&jpeg));
+    assert!(VerifyFileType::Jpeg.check_structure(&jpeg));
+    let mut gif = b"GIF89a\x01\x00\x01\x00\x80\x00\x00\x00\x00\x00\xff\xff\xff".to_vec();
+    gif.extend_from_slice(b"\x21\xf9\x04\x00\x00\x00\x00\x00");
+    gif.extend_from_slice(b"\x2c\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02\x44\x01\x00");
+    assert!(!VerifyFileType::Gif.check_structure(&gif));
+    gif.push(0x3B);
+    assert!(VerifyFileType::Gif.check_structure(&gif));
+    let mut webp = b"RIFF\x12\x00\x00\x00WEBPVP8L\x05\x00\x00\x00\x2f\x00\x00\x00\x00".to_vec();
+    assert!(!VerifyFileType::Webp.check_structure(&webp));
+    webp.push(0);
+    assert!(VerifyFileType::Webp.check_structure(&webp));
+    let content = b"pixiv";
+    let crc = crc32(content).to_le_bytes();
+    let mut zip = b"PK\x03\x04\x0a\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec();
+    zip.extend_from_slice(&crc);
+    zip.extend_from_slice(b"\x05\x00\x00\x00\x05\x00\x00\x00\x05\x00\x00\x00a.txt");
+    zip.extend_from_slice(content);
+    let cd_offset = zip.len() as u32;
+    zip.extend_from_slice(b"PK\x01\x02\x14\x00\x0a\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+    zip.extend_from_slice(&crc);
+    zip.extend_from_slice(b"\x05\x00\x00\x00\x05\x00\x00\x00\x05\x00\x00\x00\x00\x00");
+    zip.extend_from_slice(&[0; 12]);
+    zip.extend_from_slice(b"a.txt");
+    let cd_size = zip.len() as u32 - cd_offset;
+    zip.extend_from_slice(b"PK\x05\x06\x00\x00\x00\x00\x01\x00\x01\x00");
+    zip.extend_from_slice(&cd_size.to_le_bytes());
+    zip.extend_from_slice(&cd_offset.to_le_bytes());
+    zip.extend_from_slice(b"\x00\x00");
+    assert!(VerifyFileType::Zip.check_magic(&zip));
+    assert!(VerifyFileType::Zip.check_structure(&zip));
+    zip[36] = b'X';
+    assert!(!VerifyFileType::Zip.check_structure(&zip));
+    assert!(!VerifyFileType::Zip.check_structure(&zip[10..]));
+}
+
+#[test]
+fn test_verify_file() {
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = std::fs::create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let f = p.join("verify.png");
+    std::fs::write(&f, b"<html></html>").unwrap();
+    assert!(verify_file(&f, 13, None, false).is_err());
+    assert!(verify_file(&f, 12, None, false).is_err());
+    let f = p.join("verify.txt");
+    std::fs::write(&f, "pixiv").unwrap();
+    assert!(verify_file(&f, 5, None, true).is_ok());
+    let f = p.join("verify_partial.jpg");
+    let mut data = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
+    data.extend_from_slice(b"JFIF\0");
+    std::fs::write(&f, &data).unwrap();
+    File::options()
+        .write(true)
+        .open(&f)
+        .unwrap()
+        .set_len(100)
+        .unwrap();
+    // The preallocated file has the expected size, but only a part of it is written.
+    assert!(verify_file(&f, 100, None, false).is_ok());
+    assert!(verify_file(&f, 100, Some(data.len() as u64), false).is_err());
+    assert!(verify_file(&f, 100, Some(100), false).is_ok());
+    assert_eq!(
+        write_sha256_file(&f).unwrap(),
+        "0bddaa00491d6e207687c249fac209959a9eb1c625ba165bcdc812746e6646e9"
+    );
+    assert_eq!(
+        read_sha256_file(&f),
+        Some(String::from(
+            "0bddaa00491d6e207687c249fac209959a9eb1c625ba165bcdc812746e6646e9"
+        ))
+    );
+}
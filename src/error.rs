@@ -25,6 +25,7 @@ pub enum PixivDownloaderError {
     ParseIntError(std::num::ParseIntError),
     ReqwestError(wreq::Error),
     PixivAppError(crate::pixivapp::error::PixivAppError),
+    PixivWebError(crate::pixiv_web::PixivWebError),
     SerdeJsonError(serde_json::Error),
     #[cfg(feature = "serde_urlencoded")]
     SerdeUrlencodedError(serde_urlencoded::ser::Error),
@@ -46,9 +47,42 @@ impl From<http::header::InvalidHeaderValue> for PixivDownloaderError {
 }
 
 impl PixivDownloaderError {
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::DownloaderError(_) => "DownloaderError",
+            Self::String(_) => "String",
+            Self::JoinError(_) => "JoinError",
+            Self::UgoiraError(_) => "UgoiraError",
+            #[cfg(feature = "server")]
+            Self::Hyper(_) => "Hyper",
+            Self::HTTP(_) => "HTTP",
+            Self::IOError(_) => "IOError",
+            Self::Fanbox(_) => "Fanbox",
+            #[cfg(feature = "avdict")]
+            Self::AVDict(_) => "AVDict",
+            #[cfg(feature = "db")]
+            Self::DbError(_) => "DbError",
+            #[cfg(feature = "server")]
+            Self::FromUtf8Error(_) => "FromUtf8Error",
+            #[cfg(feature = "server")]
+            Self::ToStrError(_) => "ToStrError",
+            Self::JSONError(_) => "JSONError",
+            Self::ParseIntError(_) => "ParseIntError",
+            Self::ReqwestError(_) => "ReqwestError",
+            Self::PixivAppError(_) => "PixivAppError",
+            Self::PixivWebError(_) => "PixivWebError",
+            Self::SerdeJsonError(_) => "SerdeJsonError",
+            #[cfg(feature = "serde_urlencoded")]
+            Self::SerdeUrlencodedError(_) => "SerdeUrlencodedError",
+            Self::BotApiError(_) => "BotApiError",
+        }
+    }
+
     pub fn is_not_found(&self) -> bool {
         match self {
             Self::PixivAppError(e) => e.is_not_found(),
+            Self::PixivWebError(e) => e.is_not_found(),
             _ => false,
         }
     }
@@ -0,0 +1,248 @@
+use crate::downloader::verify::file_sha256;
+use crate::error::PixivDownloaderError;
+use crate::ext::atomic::AtomicQuick;
+use crate::ext::json::ToJson;
+use crate::ext::rw_lock::GetRwLock;
+use crate::gettext;
+use crate::pixiv_link::PixivID;
+use chrono::Utc;
+use json::JsonValue;
+use std::fs::File;
+use std::io::{stdout, Write};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::RwLock;
+
+lazy_static! {
+    #[doc(hidden)]
+    static ref EVENT_WRITER: RwLock<Option<Box<dyn Write + Send + Sync>>> = RwLock::new(None);
+    #[doc(hidden)]
+    static ref EVENT_SUMMARY: RwLock<EventSummary> = RwLock::new(EventSummary::default());
+    #[doc(hidden)]
+    static ref EVENT_TO_STDOUT: AtomicBool = AtomicBool::new(false);
+}
+
+#[derive(Debug, Default)]
+/// The counts of the events written in this run
+struct EventSummary {
+    /// The number of resolved posts
+    posts: u64,
+    /// The number of completed files
+    completed: u64,
+    /// The number of skipped files
+    skipped: u64,
+    /// The number of converted ugoira
+    converted: u64,
+    /// The number of errors
+    errors: u64,
+    /// The total size of completed files
+    size: u64,
+}
+
+/// Start writing events to the target. `-` means stdout.
+/// * `target` - The path of the file
+pub fn init_events<S: AsRef<str> + ?Sized>(target: &S) -> std::io::Result<()> {
+    let target = target.as_ref();
+    let writer: Box<dyn Write + Send + Sync> = if target == "-" {
+        Box::new(stdout())
+    } else {
+        Box::new(File::create(target)?)
+    };
+    EVENT_WRITER.get_mut().replace(writer);
+    EVENT_TO_STDOUT.qstore(target == "-");
+    Ok(())
+}
+
+/// Returns true if the event stream is enabled.
+pub fn events_enabled() -> bool {
+    EVENT_WRITER.get_ref().is_some()
+}
+
+/// Returns true if the events are written to stdout.
+/// Other outputs should be written to stderr to keep the stream valid.
+pub fn events_to_stdout() -> bool {
+    EVENT_TO_STDOUT.qload()
+}
+
+/// Write an event as a single line of JSON.
+/// * `event` - The type of the event
+/// * `data` - The data of the event
+pub fn emit_event(event: &str, mut data: JsonValue) {
+    let mut writer = EVENT_WRITER.get_mut();
+    let writer = match writer.as_mut() {
+        Some(writer) => writer,
+        None => return,
+    };
+    {
+        let mut summary = EVENT_SUMMARY.get_mut();
+        match event {
+            "post_resolved" => summary.posts += 1,
+            "file_completed" => {
+                summary.completed += 1;
+                summary.size += data["size"].as_u64().unwrap_or(0);
+            }
+            "file_skipped" => summary.skipped += 1,
+            "ugoira_converted" => summary.converted += 1,
+            "error" => summary.errors += 1,
+            _ => {}
+        }
+    }
+    if !data.is_object() {
+        data = json::object! {};
+    }
+    data["event"] = event.into();
+    data["time"] = Utc::now().to_rfc3339().into();
+    let line = json::stringify(data) + "\n";
+    if let Err(e) = writer
+        .write_all(line.as_bytes())
+        .and_then(|_| writer.flush())
+    {
+        log::warn!("{} {}", gettext("Warning: Failed to write event:"), e);
+    }
+}
+
+/// Write a post resolved event.
+/// * `id` - The id of the post
+/// * `title` - The title of the post
+/// * `pages` - The count of pages
+pub fn emit_post_resolved(id: &PixivID, title: Option<&str>, pages: Option<u64>) {
+    emit_event(
+        "post_resolved",
+        json::object! {"id": id.to_json(), "title": title, "pages": pages},
+    );
+}
+
+/// Write a file started event.
+/// * `url` - The link of the file
+/// * `path` - The path of the target
+pub fn emit_file_started(url: &str, path: &Path) {
+    emit_event(
+        "file_started",
+        json::object! {"url": url, "path": path.to_str().unwrap_or("(null)")},
+    );
+}
+
+/// Write a file progress event.
+/// * `url` - The link of the file
+/// * `path` - The path of the target
+/// * `downloaded` - The size of the downloaded data
+/// * `total` - The size of the file. 0 if unknown.
+pub fn emit_file_progress(url: &str, path: &Path, downloaded: u64, total: u64) {
+    let total = if total == 0 { None } else { Some(total) };
+    emit_event(
+        "file_progress",
+        json::object! {
+            "url": url,
+            "path": path.to_str().unwrap_or("(null)"),
+            "downloaded": downloaded,
+            "total": total,
+        },
+    );
+}
+
+/// Write a file completed event with the size and the SHA-256 hash of the file.
+/// * `url` - The link of the file
+/// * `path` - The path of the target
+pub fn emit_file_completed(url: &str, path: &Path) {
+    if !events_enabled() {
+        return;
+    }
+    let (size, hash) = match file_size_and_sha256(path) {
+        Ok((size, hash)) => (Some(size), Some(hash)),
+        Err(e) => {
+            log::warn!(
+                "{} {}",
+                gettext("Warning: Failed to calculate file's hash:"),
+                e
+            );
+            (None, None)
+        }
+    };
+    emit_event(
+        "file_completed",
+        json::object! {
+            "url": url,
+            "path": path.to_str().unwrap_or("(null)"),
+            "size": size,
+            "sha256": hash,
+        },
+    );
+}
+
+/// Write a file skipped event.
+/// * `url` - The link of the file
+/// * `path` - The path of the target
+pub fn emit_file_skipped(url: &str, path: &Path) {
+    emit_event(
+        "file_skipped",
+        json::object! {"url": url, "path": path.to_str().unwrap_or("(null)")},
+    );
+}
+
+/// Write an ugoira converted event.
+/// * `id` - The id of the artwork
+/// * `src` - The path of the source zip file
+/// * `dest` - The path of the output video
+pub fn emit_ugoira_converted(id: u64, src: &Path, dest: &Path) {
+    emit_event(
+        "ugoira_converted",
+        json::object! {
+            "id": PixivID::Artwork(id).to_json(),
+            "src": src.to_str().unwrap_or("(null)"),
+            "dest": dest.to_str().unwrap_or("(null)"),
+        },
+    );
+}
+
+/// Write an error event.
+/// * `target` - The post or the link which failed
+/// * `e` - The error
+pub fn emit_error(target: Option<JsonValue>, e: &PixivDownloaderError) {
+    emit_event(
+        "error",
+        json::object! {"target": target, "kind": e.kind(), "message": format!("{}", e)},
+    );
+}
+
+/// Write the summary of the run. Should be the last event.
+/// * `code` - The exit code
+pub fn emit_summary(code: i32) {
+    let data = {
+        let summary = EVENT_SUMMARY.get_ref();
+        json::object! {
+            "posts": summary.posts,
+            "completed": summary.completed,
+            "skipped": summary.skipped,
+            "converted": summary.converted,
+            "errors": summary.errors,
+            "size": summary.size,
+            "code": code,
+        }
+    };
+    emit_event("summary", data);
+}
+
+/// Returns the size and the SHA-256 hash of the file.
+/// * `path` - The path of the file
+fn file_size_and_sha256(path: &Path) -> std::io::Result<(u64, String)> {
+    let size = std::fs::metadata(path)?.len();
+    Ok((size, file_sha256(path)?))
+}
+
+#[test]
+fn test_file_size_and_sha256() {
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = std::fs::create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let f = p.join("events_sha256.txt");
+    std::fs::write(&f, "pixiv").unwrap();
+    assert_eq!(
+        file_size_and_sha256(&f).unwrap(),
+        (
+            5,
+            String::from("0bddaa00491d6e207687c249fac209959a9eb1c625ba165bcdc812746e6646e9")
+        )
+    );
+}
@@ -1,5 +1,7 @@
 use crate::_exif;
-pub use crate::_exif::{ExifDataRef, ExifDatumRef, ExifValueRef};
+pub use crate::_exif::{
+    ExifDataRef, ExifDatumRef, ExifValueRef, IptcDataRef, IptcDatumRef, XmpDataRef, XmpDatumRef,
+};
 use crate::ext::rawhandle::AsNonNullPtr;
 use crate::ext::rawhandle::FromRawHandle;
 use crate::ext::rawhandle::ToRawHandle;
@@ -619,6 +621,21 @@ impl ExifDataRef {
         }
     }
 
+    /// Delete all Exifdatum instances which have the key.
+    /// Returns the count of deleted instances.
+    pub fn remove(&mut self, key: &ExifKey) -> Result<usize, ()> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return Err(());
+        }
+        let r = unsafe { _exif::exif_data_ref_remove(data, key.as_ptr()) };
+        if r == -1 {
+            Err(())
+        } else {
+            Ok(r as usize)
+        }
+    }
+
     /// Get the number of metadata entries.
     pub fn count(&self) -> Option<usize> {
         let data = unsafe { self.to_raw_handle() };
@@ -829,6 +846,478 @@ impl<'a> ExactSizeIterator for ExifDataMutItor<'a> {
     }
 }
 
+/// A container for XMP data.
+pub struct XmpData {
+    data: *mut _exif::XmpData,
+}
+
+#[allow(dead_code)]
+impl XmpData {
+    /// Create a new container
+    pub fn new() -> Result<Self, ()> {
+        let d = unsafe { _exif::exif_xmp_data_new() };
+        if d.is_null() {
+            return Err(());
+        }
+        Ok(Self { data: d })
+    }
+
+    pub unsafe fn from_raw_pointer(data: *mut _exif::XmpData) -> Self {
+        Self { data }
+    }
+}
+
+impl Borrow<XmpDataRef> for XmpData {
+    fn borrow(&self) -> &XmpDataRef {
+        self.deref()
+    }
+}
+
+impl BorrowMut<XmpDataRef> for XmpData {
+    fn borrow_mut(&mut self) -> &mut XmpDataRef {
+        self.deref_mut()
+    }
+}
+
+impl Deref for XmpData {
+    type Target = XmpDataRef;
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            XmpDataRef::from_const_handle(
+                _exif::exif_xmp_data_get_ref(self.to_raw_handle()) as *const XmpDataRef
+            )
+        }
+    }
+}
+
+impl DerefMut for XmpData {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { XmpDataRef::from_raw_handle(_exif::exif_xmp_data_get_ref(self.to_raw_handle())) }
+    }
+}
+
+impl Drop for XmpData {
+    fn drop(&mut self) {
+        if !self.data.is_null() {
+            unsafe { _exif::exif_free_xmp_data(self.data) };
+        }
+    }
+}
+
+impl ToRawHandle<_exif::XmpData> for XmpData {
+    unsafe fn to_raw_handle(&self) -> *mut _exif::XmpData {
+        self.data
+    }
+}
+
+#[allow(dead_code)]
+impl XmpDataRef {
+    /// Add a data from the supplied key and value pair.
+    /// * `key` - The key of the XMP data. For example, `Xmp.dc.subject`.
+    /// * `value` - The value
+    pub fn add(&mut self, key: &str, value: &ExifValueRef) -> Result<(), ()> {
+        let data = unsafe { self.to_raw_handle() };
+        let v = unsafe { value.to_raw_handle() };
+        if v.is_null() || data.is_null() {
+            return Err(());
+        }
+        let k = CString::new(key).map_err(|_| ())?;
+        let r = unsafe { _exif::exif_xmp_data_ref_add(data, k.as_ptr(), v) };
+        if r == 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Delete all Xmpdatum instances resulting in an empty container.
+    pub fn clear(&mut self) -> Result<(), ()> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return Err(());
+        }
+        let r = unsafe { _exif::exif_xmp_data_ref_clear(data) };
+        if r == 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Delete all Xmpdatum instances which have the key.
+    /// Returns the count of deleted instances.
+    pub fn remove(&mut self, key: &str) -> Result<usize, ()> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return Err(());
+        }
+        let k = CString::new(key).map_err(|_| ())?;
+        let r = unsafe { _exif::exif_xmp_data_ref_remove(data, k.as_ptr()) };
+        if r == -1 {
+            Err(())
+        } else {
+            Ok(r as usize)
+        }
+    }
+
+    /// Get the number of metadata entries.
+    pub fn count(&self) -> Option<usize> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_xmp_data_ref_get_count(data) };
+        if r == -1 {
+            return None;
+        }
+        Some(r as usize)
+    }
+
+    pub fn iter<'a>(&'a self) -> Option<XmpDataItor<'a>> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_xmp_data_ref_iter(data) };
+        if r.is_null() {
+            return None;
+        }
+        Some(XmpDataItor {
+            itor: r,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl ToOwned for XmpDataRef {
+    type Owned = XmpData;
+    fn to_owned(&self) -> Self::Owned {
+        let o = unsafe { self.to_raw_handle() };
+        if o.is_null() {
+            return XmpData::new().unwrap();
+        }
+        let r = unsafe { _exif::exif_xmp_data_ref_clone(o) };
+        if r.is_null() {
+            panic!("Failed to convert XmpDataRef to XmpData.");
+        }
+        unsafe { XmpData::from_raw_pointer(r) }
+    }
+}
+
+impl ToRawHandle<XmpDataRef> for XmpDataRef {
+    unsafe fn to_raw_handle(&self) -> *mut XmpDataRef {
+        self.to_const_handle() as *mut XmpDataRef
+    }
+    unsafe fn to_const_handle(&self) -> *const XmpDataRef {
+        self
+    }
+}
+
+pub struct XmpDataItor<'a> {
+    itor: *mut _exif::XmpDataItor,
+    phantom: PhantomData<&'a _exif::XmpDataItor>,
+}
+
+impl<'a> Drop for XmpDataItor<'a> {
+    fn drop(&mut self) {
+        if !self.itor.is_null() {
+            unsafe { _exif::exif_free_xmp_data_itor(self.itor) };
+            self.itor = std::ptr::null_mut();
+        }
+    }
+}
+
+impl<'a> Iterator for XmpDataItor<'a> {
+    type Item = &'a XmpDatumRef;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.itor.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_xmp_data_itor_next(self.itor) };
+        if r.is_null() {
+            return None;
+        }
+        Some(unsafe { XmpDatumRef::from_const_handle(r as *const XmpDatumRef) })
+    }
+}
+
+#[allow(dead_code)]
+impl XmpDatumRef {
+    /// Return the key of the Xmpdatum.
+    pub fn key(&self) -> Option<String> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_xmp_datum_key(data) };
+        if r.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(r) };
+        let s = s.to_owned();
+        unsafe { _exif::exif_free(r as *mut ::std::os::raw::c_void) };
+        s.into_string().ok()
+    }
+
+    /// Return a constant reference to the value.
+    pub fn value<'a>(&'a self) -> Option<&'a ExifValueRef> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_xmp_datum_value(data) };
+        if r.is_null() {
+            return None;
+        }
+        Some(unsafe { ExifValueRef::from_const_handle(r as *const ExifValueRef) })
+    }
+}
+
+impl ToRawHandle<XmpDatumRef> for XmpDatumRef {
+    unsafe fn to_raw_handle(&self) -> *mut XmpDatumRef {
+        self.to_const_handle() as *mut XmpDatumRef
+    }
+    unsafe fn to_const_handle(&self) -> *const XmpDatumRef {
+        self
+    }
+}
+
+/// A container for IPTC data.
+pub struct IptcData {
+    data: *mut _exif::IptcData,
+}
+
+#[allow(dead_code)]
+impl IptcData {
+    /// Create a new container
+    pub fn new() -> Result<Self, ()> {
+        let d = unsafe { _exif::exif_iptc_data_new() };
+        if d.is_null() {
+            return Err(());
+        }
+        Ok(Self { data: d })
+    }
+
+    pub unsafe fn from_raw_pointer(data: *mut _exif::IptcData) -> Self {
+        Self { data }
+    }
+}
+
+impl Borrow<IptcDataRef> for IptcData {
+    fn borrow(&self) -> &IptcDataRef {
+        self.deref()
+    }
+}
+
+impl BorrowMut<IptcDataRef> for IptcData {
+    fn borrow_mut(&mut self) -> &mut IptcDataRef {
+        self.deref_mut()
+    }
+}
+
+impl Deref for IptcData {
+    type Target = IptcDataRef;
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            IptcDataRef::from_const_handle(
+                _exif::exif_iptc_data_get_ref(self.to_raw_handle()) as *const IptcDataRef
+            )
+        }
+    }
+}
+
+impl DerefMut for IptcData {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { IptcDataRef::from_raw_handle(_exif::exif_iptc_data_get_ref(self.to_raw_handle())) }
+    }
+}
+
+impl Drop for IptcData {
+    fn drop(&mut self) {
+        if !self.data.is_null() {
+            unsafe { _exif::exif_free_iptc_data(self.data) };
+        }
+    }
+}
+
+impl ToRawHandle<_exif::IptcData> for IptcData {
+    unsafe fn to_raw_handle(&self) -> *mut _exif::IptcData {
+        self.data
+    }
+}
+
+#[allow(dead_code)]
+impl IptcDataRef {
+    /// Add a data from the supplied key and value pair.
+    /// * `key` - The key of the IPTC data. For example, `Iptc.Application2.Keywords`.
+    /// * `value` - The value
+    pub fn add(&mut self, key: &str, value: &ExifValueRef) -> Result<(), ()> {
+        let data = unsafe { self.to_raw_handle() };
+        let v = unsafe { value.to_raw_handle() };
+        if v.is_null() || data.is_null() {
+            return Err(());
+        }
+        let k = CString::new(key).map_err(|_| ())?;
+        let r = unsafe { _exif::exif_iptc_data_ref_add(data, k.as_ptr(), v) };
+        if r == 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Delete all Iptcdatum instances resulting in an empty container.
+    pub fn clear(&mut self) -> Result<(), ()> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return Err(());
+        }
+        let r = unsafe { _exif::exif_iptc_data_ref_clear(data) };
+        if r == 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Delete all Iptcdatum instances which have the key.
+    /// Returns the count of deleted instances.
+    pub fn remove(&mut self, key: &str) -> Result<usize, ()> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return Err(());
+        }
+        let k = CString::new(key).map_err(|_| ())?;
+        let r = unsafe { _exif::exif_iptc_data_ref_remove(data, k.as_ptr()) };
+        if r == -1 {
+            Err(())
+        } else {
+            Ok(r as usize)
+        }
+    }
+
+    /// Get the number of metadata entries.
+    pub fn count(&self) -> Option<usize> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_iptc_data_ref_get_count(data) };
+        if r == -1 {
+            return None;
+        }
+        Some(r as usize)
+    }
+
+    pub fn iter<'a>(&'a self) -> Option<IptcDataItor<'a>> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_iptc_data_ref_iter(data) };
+        if r.is_null() {
+            return None;
+        }
+        Some(IptcDataItor {
+            itor: r,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl ToOwned for IptcDataRef {
+    type Owned = IptcData;
+    fn to_owned(&self) -> Self::Owned {
+        let o = unsafe { self.to_raw_handle() };
+        if o.is_null() {
+            return IptcData::new().unwrap();
+        }
+        let r = unsafe { _exif::exif_iptc_data_ref_clone(o) };
+        if r.is_null() {
+            panic!("Failed to convert IptcDataRef to IptcData.");
+        }
+        unsafe { IptcData::from_raw_pointer(r) }
+    }
+}
+
+impl ToRawHandle<IptcDataRef> for IptcDataRef {
+    unsafe fn to_raw_handle(&self) -> *mut IptcDataRef {
+        self.to_const_handle() as *mut IptcDataRef
+    }
+    unsafe fn to_const_handle(&self) -> *const IptcDataRef {
+        self
+    }
+}
+
+pub struct IptcDataItor<'a> {
+    itor: *mut _exif::IptcDataItor,
+    phantom: PhantomData<&'a _exif::IptcDataItor>,
+}
+
+impl<'a> Drop for IptcDataItor<'a> {
+    fn drop(&mut self) {
+        if !self.itor.is_null() {
+            unsafe { _exif::exif_free_iptc_data_itor(self.itor) };
+            self.itor = std::ptr::null_mut();
+        }
+    }
+}
+
+impl<'a> Iterator for IptcDataItor<'a> {
+    type Item = &'a IptcDatumRef;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.itor.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_iptc_data_itor_next(self.itor) };
+        if r.is_null() {
+            return None;
+        }
+        Some(unsafe { IptcDatumRef::from_const_handle(r as *const IptcDatumRef) })
+    }
+}
+
+#[allow(dead_code)]
+impl IptcDatumRef {
+    /// Return the key of the Iptcdatum.
+    pub fn key(&self) -> Option<String> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_iptc_datum_key(data) };
+        if r.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(r) };
+        let s = s.to_owned();
+        unsafe { _exif::exif_free(r as *mut ::std::os::raw::c_void) };
+        s.into_string().ok()
+    }
+
+    /// Return a constant reference to the value.
+    pub fn value<'a>(&'a self) -> Option<&'a ExifValueRef> {
+        let data = unsafe { self.to_raw_handle() };
+        if data.is_null() {
+            return None;
+        }
+        let r = unsafe { _exif::exif_iptc_datum_value(data) };
+        if r.is_null() {
+            return None;
+        }
+        Some(unsafe { ExifValueRef::from_const_handle(r as *const ExifValueRef) })
+    }
+}
+
+impl ToRawHandle<IptcDatumRef> for IptcDatumRef {
+    unsafe fn to_raw_handle(&self) -> *mut IptcDatumRef {
+        self.to_const_handle() as *mut IptcDatumRef
+    }
+    unsafe fn to_const_handle(&self) -> *const IptcDatumRef {
+        self
+    }
+}
+
 /// An image
 pub struct ExifImage {
     img: *mut _exif::ExifImage,
@@ -920,6 +1409,68 @@ impl ExifImage {
         }
     }
 
+    /// Returns a read only [XmpData] ([XmpDataRef]) instance containing currently buffered XMP data.
+    pub fn xmp_data<'a>(&'a self) -> Option<&'a XmpDataRef> {
+        if self.img.is_null() {
+            return None;
+        }
+        let d = unsafe { _exif::exif_image_get_xmp_data(self.img) };
+        if d.is_null() {
+            return None;
+        }
+        unsafe { Some(XmpDataRef::from_const_handle(d as *const XmpDataRef)) }
+    }
+
+    /// Assign new XMP data.
+    /// The new XMP data is not written to the image until the [Self::write_metadata()] method is called.
+    /// * `data` - An [XmpData] instance holding XMP data to be copied
+    pub fn set_xmp_data(&mut self, data: &XmpData) -> Result<(), ()> {
+        if self.img.is_null() {
+            return Err(());
+        }
+        let d = unsafe { data.to_raw_handle() };
+        if d.is_null() {
+            return Err(());
+        }
+        let d = unsafe { _exif::exif_image_set_xmp_data(self.img, d) };
+        if d == 0 {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Returns a read only [IptcData] ([IptcDataRef]) instance containing currently buffered IPTC data.
+    pub fn iptc_data<'a>(&'a self) -> Option<&'a IptcDataRef> {
+        if self.img.is_null() {
+            return None;
+        }
+        let d = unsafe { _exif::exif_image_get_iptc_data(self.img) };
+        if d.is_null() {
+            return None;
+        }
+        unsafe { Some(IptcDataRef::from_const_handle(d as *const IptcDataRef)) }
+    }
+
+    /// Assign new IPTC data.
+    /// The new IPTC data is not written to the image until the [Self::write_metadata()] method is called.
+    /// * `data` - An [IptcData] instance holding IPTC data to be copied
+    pub fn set_iptc_data(&mut self, data: &IptcData) -> Result<(), ()> {
+        if self.img.is_null() {
+            return Err(());
+        }
+        let d = unsafe { data.to_raw_handle() };
+        if d.is_null() {
+            return Err(());
+        }
+        let d = unsafe { _exif::exif_image_set_iptc_data(self.img, d) };
+        if d == 0 {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
     /// Write metadata back to the image.
     ///
     /// All existing metadata sections in the image are either created, replaced, or erased.
@@ -1146,3 +1697,51 @@ fn test_exif_image() {
         img.write_metadata().unwrap();
     }
 }
+
+#[test]
+fn test_xmp_data() {
+    let mut d = XmpData::new().unwrap();
+    assert_eq!(Some(0), d.count());
+    let mut v = ExifValue::try_from(ExifTypeID::XmpBag).unwrap();
+    v.read("tag1".as_bytes(), None).unwrap();
+    v.read("tag2".as_bytes(), None).unwrap();
+    assert_eq!(v.count(), 2);
+    d.add("Xmp.dc.subject", &v).unwrap();
+    let mut v2 = ExifValue::try_from(ExifTypeID::LangAlt).unwrap();
+    v2.read("夏のチマメ隊🏖️".as_bytes(), None).unwrap();
+    d.add("Xmp.dc.title", &v2).unwrap();
+    assert_eq!(Some(2), d.count());
+    assert!(d.add("Invalid.key", &v2).is_err());
+    let keys: Vec<_> = d.iter().unwrap().filter_map(|d| d.key()).collect();
+    assert_eq!(keys, vec!["Xmp.dc.subject", "Xmp.dc.title"]);
+    let c = d.to_owned();
+    assert_eq!(Some(2), c.count());
+    assert_eq!(d.remove("Xmp.dc.subject"), Ok(1));
+    assert_eq!(d.remove("Xmp.dc.subject"), Ok(0));
+    assert_eq!(Some(1), d.count());
+    assert_eq!(Some(2), c.count());
+    d.clear().unwrap();
+    assert_eq!(Some(0), d.count());
+}
+
+#[test]
+fn test_iptc_data() {
+    let mut d = IptcData::new().unwrap();
+    for tag in ["tag1", "tag2"] {
+        let mut v = ExifValue::try_from(ExifTypeID::String).unwrap();
+        v.read(tag.as_bytes(), None).unwrap();
+        d.add("Iptc.Application2.Keywords", &v).unwrap();
+    }
+    assert_eq!(Some(2), d.count());
+    let values: Vec<_> = d
+        .iter()
+        .unwrap()
+        .filter_map(|d| d.value().and_then(|v| v.to_string().ok()))
+        .collect();
+    assert_eq!(
+        values,
+        vec![CString::new("tag1").unwrap(), CString::new("tag2").unwrap()]
+    );
+    assert_eq!(d.remove("Iptc.Application2.Keywords"), Ok(2));
+    assert_eq!(Some(0), d.count());
+}
@@ -1,7 +1,6 @@
 use super::check::CheckUnknown;
 use super::error::FanboxAPIError;
-#[cfg(feature = "exif")]
-use crate::data::exif::ExifDataSource;
+use crate::data::exif_data_source::ExifDataSource;
 use crate::ext::json::ToJson2;
 use crate::fanbox_api::FanboxClientInternal;
 use crate::parser::json::parse_u64;
@@ -303,7 +302,6 @@ impl Debug for FanboxCreator {
     }
 }
 
-#[cfg(feature = "exif")]
 impl ExifDataSource for FanboxCreator {
     fn image_author(&self) -> Option<String> {
         self.user_name().map(|s| s.to_owned())
@@ -3,11 +3,11 @@ use super::article::image::FanboxArticleImage;
 use super::check::CheckUnknown;
 use super::comment_list::FanboxCommentList;
 use super::error::FanboxAPIError;
-#[cfg(feature = "exif")]
-use crate::data::exif::ExifDataSource;
+use crate::data::exif_data_source::ExifDataSource;
 use crate::ext::json::ToJson2;
 use crate::fanbox_api::FanboxClientInternal;
 use crate::parser::json::parse_u64;
+use chrono::{DateTime, FixedOffset};
 use json::JsonValue;
 use proc_macros::check_json_keys;
 use proc_macros::create_fanbox_download_helper;
@@ -265,7 +265,6 @@ impl Debug for FanboxPostArticle {
     }
 }
 
-#[cfg(feature = "exif")]
 impl ExifDataSource for FanboxPostArticle {
     fn image_author(&self) -> Option<String> {
         match self.user_name() {
@@ -274,6 +273,15 @@ impl ExifDataSource for FanboxPostArticle {
         }
     }
 
+    fn image_author_id(&self) -> Option<String> {
+        self.user_id().map(|id| id.to_string())
+    }
+
+    fn image_author_url(&self) -> Option<String> {
+        self.creator_id()
+            .map(|id| format!("https://www.fanbox.cc/@{}", id))
+    }
+
     fn image_comment(&self) -> Option<String> {
         match self.excerpt() {
             Some(e) => Some(e.to_owned()),
@@ -281,6 +289,24 @@ impl ExifDataSource for FanboxPostArticle {
         }
     }
 
+    fn image_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.published_datetime()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+    }
+
+    fn image_hierarchical_tags(&self) -> Option<Vec<String>> {
+        self.tags().map(|tags| {
+            tags.into_iter()
+                .map(|s| format!("fanbox|{}", s.replace('|', "_")))
+                .collect()
+        })
+    }
+
+    fn image_tags(&self) -> Option<Vec<String>> {
+        self.tags()
+            .map(|tags| tags.into_iter().map(|s| s.to_owned()).collect())
+    }
+
     fn image_title(&self) -> Option<String> {
         match self.title() {
             Some(t) => Some(t.to_owned()),
@@ -678,6 +704,56 @@ impl Debug for FanboxPostFile {
     }
 }
 
+impl ExifDataSource for FanboxPostFile {
+    fn image_author(&self) -> Option<String> {
+        match self.user_name() {
+            Some(u) => Some(u.to_owned()),
+            None => None,
+        }
+    }
+
+    fn image_author_id(&self) -> Option<String> {
+        self.user_id().map(|id| id.to_string())
+    }
+
+    fn image_author_url(&self) -> Option<String> {
+        self.creator_id()
+            .map(|id| format!("https://www.fanbox.cc/@{}", id))
+    }
+
+    fn image_comment(&self) -> Option<String> {
+        match self.excerpt() {
+            Some(e) => Some(e.to_owned()),
+            None => None,
+        }
+    }
+
+    fn image_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.published_datetime()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+    }
+
+    fn image_hierarchical_tags(&self) -> Option<Vec<String>> {
+        self.tags().map(|tags| {
+            tags.into_iter()
+                .map(|s| format!("fanbox|{}", s.replace('|', "_")))
+                .collect()
+        })
+    }
+
+    fn image_tags(&self) -> Option<Vec<String>> {
+        self.tags()
+            .map(|tags| tags.into_iter().map(|s| s.to_owned()).collect())
+    }
+
+    fn image_title(&self) -> Option<String> {
+        match self.title() {
+            Some(t) => Some(t.to_owned()),
+            None => None,
+        }
+    }
+}
+
 pub struct FanboxImageBody {
     pub data: JsonValue,
     client: Arc<FanboxClientInternal>,
@@ -744,7 +820,6 @@ impl Debug for FanboxImageBody {
     }
 }
 
-#[cfg(feature = "exif")]
 impl ExifDataSource for FanboxImageBody {
     fn image_comment(&self) -> Option<String> {
         match self.text() {
@@ -999,7 +1074,6 @@ impl Debug for FanboxPostImage {
     }
 }
 
-#[cfg(feature = "exif")]
 impl ExifDataSource for FanboxPostImage {
     fn image_author(&self) -> Option<String> {
         match self.user_name() {
@@ -1008,6 +1082,15 @@ impl ExifDataSource for FanboxPostImage {
         }
     }
 
+    fn image_author_id(&self) -> Option<String> {
+        self.user_id().map(|id| id.to_string())
+    }
+
+    fn image_author_url(&self) -> Option<String> {
+        self.creator_id()
+            .map(|id| format!("https://www.fanbox.cc/@{}", id))
+    }
+
     fn image_comment(&self) -> Option<String> {
         match self.body() {
             Some(body) => body.image_comment(),
@@ -1015,6 +1098,24 @@ impl ExifDataSource for FanboxPostImage {
         }
     }
 
+    fn image_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.published_datetime()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+    }
+
+    fn image_hierarchical_tags(&self) -> Option<Vec<String>> {
+        self.tags().map(|tags| {
+            tags.into_iter()
+                .map(|s| format!("fanbox|{}", s.replace('|', "_")))
+                .collect()
+        })
+    }
+
+    fn image_tags(&self) -> Option<Vec<String>> {
+        self.tags()
+            .map(|tags| tags.into_iter().map(|s| s.to_owned()).collect())
+    }
+
     fn image_title(&self) -> Option<String> {
         match self.title() {
             Some(t) => Some(t.to_owned()),
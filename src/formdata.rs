@@ -1,10 +1,16 @@
+use crate::bandwidth::BandwidthLimiter;
 use derive_builder::Builder;
 use derive_getters::Getters;
 use derive_setters::Setters;
+use futures_util::StreamExt;
 use wreq::header::HeaderMap;
 use wreq::multipart::{Form, Part};
+use wreq::Body;
 use std::path::{Path, PathBuf};
 
+/// The size of the chunks when sending the parts with a bandwidth limit.
+const LIMITED_CHUNK_SIZE: usize = 65536;
+
 #[derive(derive_more::From)]
 pub enum FormDataBody {
     Data(Vec<u8>),
@@ -79,6 +85,8 @@ impl std::fmt::Debug for FormDataPart {
 /// Form
 pub struct FormData {
     fields: Vec<(String, FormDataPart)>,
+    /// The bandwidth limiter used when sending the bodies
+    limiter: Option<&'static BandwidthLimiter>,
 }
 
 impl std::fmt::Debug for FormData {
@@ -134,12 +142,25 @@ impl FormData {
         &mut self.fields.last_mut().unwrap().1
     }
 
+    /// Send the bodies of all parts in small chunks which respect the bandwidth limit.
+    /// * `limiter` - The bandwidth limiter
+    pub fn set_limiter(&mut self, limiter: &'static BandwidthLimiter) {
+        self.limiter.replace(limiter);
+    }
+
     pub async fn to_form(&self) -> Result<Form, FormDataError> {
         let mut f = Form::new();
         for (k, v) in self.fields.iter() {
-            let mut part = match &v.body {
-                FormDataBody::Data(d) => Part::bytes(d.clone()),
-                FormDataBody::File(f) => Part::bytes(tokio::fs::read(f).await?),
+            let data = match &v.body {
+                FormDataBody::Data(d) => d.clone(),
+                FormDataBody::File(f) => tokio::fs::read(f).await?,
+            };
+            let mut part = match self.limiter {
+                Some(limiter) => {
+                    let len = data.len() as u64;
+                    Part::stream_with_length(limited_body(data, limiter), len)
+                }
+                None => Part::bytes(data),
             };
             match &v.mime {
                 Some(m) => {
@@ -162,10 +183,28 @@ impl FormData {
 
 impl Default for FormData {
     fn default() -> Self {
-        Self { fields: Vec::new() }
+        Self {
+            fields: Vec::new(),
+            limiter: None,
+        }
     }
 }
 
+/// Create a body which waits for the bandwidth limiter before sending each chunk.
+/// * `data` - The data
+/// * `limiter` - The bandwidth limiter
+fn limited_body(data: Vec<u8>, limiter: &'static BandwidthLimiter) -> Body {
+    let chunks: Vec<Vec<u8>> = data
+        .chunks(LIMITED_CHUNK_SIZE)
+        .map(|c| c.to_vec())
+        .collect();
+    let stream = futures_util::stream::iter(chunks).then(move |chunk| async move {
+        limiter.acquire(chunk.len() as u64).await;
+        Ok::<_, std::io::Error>(chunk)
+    });
+    Body::wrap_stream(stream)
+}
+
 #[proc_macros::async_timeout_test(120s)]
 #[tokio::test(flavor = "multi_thread")]
 async fn test_formdata() {
@@ -181,4 +220,8 @@ async fn test_formdata() {
         .filename("formdata.txt")
         .mime("text/plain");
     f.to_form().await.unwrap();
+    f.set_limiter(crate::bandwidth::get_limiter(
+        crate::bandwidth::BandwidthLimitType::Telegram,
+    ));
+    f.to_form().await.unwrap();
 }
@@ -1,18 +1,32 @@
+use crate::ext::atomic::AtomicQuick;
 use crate::ext::rw_lock::GetRwLock;
 use log::LevelFilter;
-use log4rs::append::console::ConsoleAppender;
+use log4rs::append::console::{ConsoleAppender, Target};
 use log4rs::config::{Appender, Logger, Root};
 use log4rs::encode::pattern::PatternEncoder;
 use log4rs::{init_config, Config, Handle};
+use std::sync::atomic::AtomicBool;
 use std::sync::RwLock;
 
 lazy_static! {
     #[doc(hidden)]
     static ref HANDLE: RwLock<Option<Handle>> = RwLock::new(None);
+    #[doc(hidden)]
+    static ref USE_STDERR: AtomicBool = AtomicBool::new(false);
+}
+
+/// Write logs to stderr instead of stdout. Takes effect on next initialization.
+pub fn set_use_stderr(use_stderr: bool) {
+    USE_STDERR.qstore(use_stderr);
 }
 
 pub fn init_with_level(level: LevelFilter) {
     let stdout = ConsoleAppender::builder()
+        .target(if USE_STDERR.qload() {
+            Target::Stderr
+        } else {
+            Target::Stdout
+        })
         .encoder(Box::new(PatternEncoder::new("{m}{n}")))
         .build();
     let config = Config::builder()
@@ -17,6 +17,7 @@ mod _ugoira;
 #[cfg(feature = "avdict")]
 /// A rust wrapper for [FFMPEG](https://ffmpeg.org/)'s [AVDictionary](https://ffmpeg.org/doxygen/trunk/group__lavu__dict.html)
 mod avdict;
+mod bandwidth;
 mod cookies;
 mod data;
 #[cfg(feature = "db")]
@@ -25,6 +26,8 @@ mod download;
 mod downloader;
 mod dur;
 mod error;
+/// Machine-readable event stream
+mod events;
 #[cfg(feature = "exif")]
 /// Used to read/modify image's exif data
 mod exif;
@@ -45,6 +48,7 @@ mod pixiv_link;
 mod pixiv_web;
 mod pixivapp;
 mod push;
+mod rate_limiter;
 mod retry_interval;
 #[cfg(feature = "server")]
 mod server;
@@ -55,6 +59,8 @@ mod task_manager;
 mod tmp_cache;
 mod ugoira;
 mod utils;
+#[cfg(feature = "db")]
+mod verify;
 mod webclient;
 
 use crate::i18n::gettext;
@@ -134,13 +140,30 @@ impl Main {
             None => {}
         }
         get_helper().update(cmd.clone(), self.settings.as_ref().unwrap().clone());
+        if let Some(target) = get_helper().events() {
+            if target == "-" {
+                log_cfg::set_use_stderr(true);
+                log_cfg::init_default();
+            }
+            if let Err(e) = events::init_events(&target) {
+                log::error!(
+                    "{} {} {}",
+                    gettext("Failed to open events file:"),
+                    target,
+                    e
+                );
+                return 1;
+            }
+        }
         get_helper().init_log();
         match cmd.cmd {
             Command::Config => {
                 self.deal_config_cmd();
             }
             Command::Download => {
-                return self.download().await;
+                let re = self.download().await;
+                events::emit_summary(re);
+                return re;
             }
             #[cfg(feature = "server")]
             Command::Server => {
@@ -163,7 +186,15 @@ impl Main {
                 return 0;
             }
             Command::DownloadFile => {
-                return self.download_files().await;
+                let re = self.download_files().await;
+                events::emit_summary(re);
+                return re;
+            }
+            #[cfg(feature = "db")]
+            Command::Verify => {
+                let re = self.verify().await;
+                events::emit_summary(re);
+                return re;
             }
             Command::None => {
                 return 0;
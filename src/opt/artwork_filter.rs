@@ -0,0 +1,199 @@
+use crate::data::data::PixivData;
+use crate::gettext;
+use chrono::{DateTime, FixedOffset};
+use json::JsonValue;
+use std::str::FromStr;
+
+/// Filter artworks by age restriction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivAgeFilter {
+    /// Download all artworks
+    All,
+    /// Only download all-ages artworks
+    Safe,
+    /// Only download R-18 and R-18G artworks
+    R18,
+}
+
+impl PixivAgeFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Safe => "safe",
+            Self::R18 => "r18",
+        }
+    }
+}
+
+impl Default for PixivAgeFilter {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+impl FromStr for PixivAgeFilter {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "").as_str() {
+            "all" => Ok(Self::All),
+            "safe" => Ok(Self::Safe),
+            "r18" => Ok(Self::R18),
+            _ => Err(gettext("Unknown age restriction filter.")),
+        }
+    }
+}
+
+/// Rules to filter artworks before downloading.
+/// The rules which need unknown data are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct PixivArtworkFilter {
+    /// Skip AI-generated artworks
+    pub exclude_ai: bool,
+    /// Filter by age restriction
+    pub age: PixivAgeFilter,
+    /// Only download the artworks which have at least one of these tags
+    pub include_tags: Vec<String>,
+    /// Skip the artworks which have any of these tags
+    pub exclude_tags: Vec<String>,
+    /// The minimum count of bookmarks
+    pub min_bookmarks: Option<u64>,
+    /// Only download the artworks uploaded after this time
+    pub since: Option<DateTime<FixedOffset>>,
+    /// Only download the artworks uploaded before this time
+    pub until: Option<DateTime<FixedOffset>>,
+}
+
+impl PixivArtworkFilter {
+    /// Check the artwork. Returns the reason if the artwork should be skipped.
+    /// * `datas` - The artwork's data
+    pub fn check(&self, datas: &PixivData) -> Option<&'static str> {
+        if self.exclude_ai {
+            if let Some(ai_type) = &datas.ai_type {
+                if ai_type.is_ai() {
+                    return Some(gettext("AI-generated"));
+                }
+            }
+        }
+        if let Some(x_restrict) = datas.x_restrict {
+            match self.age {
+                PixivAgeFilter::Safe if x_restrict != 0 => {
+                    return Some(gettext("Age restricted"));
+                }
+                PixivAgeFilter::R18 if x_restrict == 0 => {
+                    return Some(gettext("All ages"));
+                }
+                _ => {}
+            }
+        }
+        if !self.include_tags.is_empty() || !self.exclude_tags.is_empty() {
+            if let Some(tags) = &datas.tags {
+                if !self.include_tags.is_empty()
+                    && !self.include_tags.iter().any(|t| has_tag(tags, t))
+                {
+                    return Some(gettext("No included tags"));
+                }
+                if self.exclude_tags.iter().any(|t| has_tag(tags, t)) {
+                    return Some(gettext("Excluded tag"));
+                }
+            }
+        }
+        if let (Some(min), Some(count)) = (self.min_bookmarks, datas.bookmark_count) {
+            if count < min {
+                return Some(gettext("Too few bookmarks"));
+            }
+        }
+        if let Some(date) = &datas.upload_date {
+            if self.since.as_ref().is_some_and(|since| date < since) {
+                return Some(gettext("Uploaded too early"));
+            }
+            if self.until.as_ref().is_some_and(|until| date > until) {
+                return Some(gettext("Uploaded too late"));
+            }
+        }
+        None
+    }
+}
+
+/// Returns true if the tag matches the original or translated name of any tag.
+/// Case is ignored.
+fn has_tag(tags: &[(String, Option<String>)], tag: &str) -> bool {
+    let tag = tag.to_lowercase();
+    tags.iter().any(|(ori, tra)| {
+        ori.to_lowercase() == tag || tra.as_ref().is_some_and(|t| t.to_lowercase() == tag)
+    })
+}
+
+/// Parse a tag list from command line. Tags are separated by commas.
+pub fn parse_tag_list<S: AsRef<str> + ?Sized>(s: &S) -> Vec<String> {
+    s.as_ref()
+        .split(',')
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_owned())
+        .collect()
+}
+
+/// Parse a tag list from settings.
+pub fn parse_tag_list_json(obj: &JsonValue) -> Option<Vec<String>> {
+    if !obj.is_array() {
+        return None;
+    }
+    let mut re = Vec::new();
+    for t in obj.members() {
+        re.push(t.as_str()?.to_owned());
+    }
+    Some(re)
+}
+
+pub fn check_tag_list(obj: &JsonValue) -> bool {
+    parse_tag_list_json(obj).is_some()
+}
+
+pub fn check_age_filter(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => PixivAgeFilter::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+#[test]
+fn test_artwork_filter() {
+    use crate::data::data::PixivAiType;
+    use crate::opt::date::parse_date_time;
+    let mut datas = PixivData::new(1u64).unwrap();
+    datas.ai_type = Some(PixivAiType::AI);
+    datas.x_restrict = Some(1);
+    datas.tags = Some(vec![
+        (String::from("オリジナル"), Some(String::from("original"))),
+        (String::from("風景"), Some(String::from("Scenery"))),
+    ]);
+    datas.bookmark_count = Some(100);
+    datas.upload_date = Some(parse_date_time("2024-01-02T03:04:05+09:00", false).unwrap());
+    let mut filter = PixivArtworkFilter::default();
+    assert_eq!(filter.check(&datas), None);
+    filter.exclude_ai = true;
+    assert!(filter.check(&datas).is_some());
+    filter.exclude_ai = false;
+    filter.age = PixivAgeFilter::from_str("R-18").unwrap();
+    assert_eq!(filter.check(&datas), None);
+    filter.age = PixivAgeFilter::Safe;
+    assert!(filter.check(&datas).is_some());
+    filter.age = PixivAgeFilter::All;
+    filter.include_tags = parse_tag_list("scenery, cat");
+    assert_eq!(filter.check(&datas), None);
+    filter.exclude_tags = vec![String::from("オリジナル")];
+    assert!(filter.check(&datas).is_some());
+    filter.exclude_tags.clear();
+    filter.include_tags = vec![String::from("cat")];
+    assert!(filter.check(&datas).is_some());
+    filter.include_tags.clear();
+    filter.min_bookmarks = Some(101);
+    assert!(filter.check(&datas).is_some());
+    filter.min_bookmarks = Some(100);
+    assert_eq!(filter.check(&datas), None);
+    filter.since = Some(parse_date_time("2024-01-03T00:00:00+09:00", false).unwrap());
+    assert!(filter.check(&datas).is_some());
+    filter.since = None;
+    filter.until = Some(parse_date_time("2024-01-01T00:00:00+09:00", false).unwrap());
+    assert!(filter.check(&datas).is_some());
+}
@@ -0,0 +1,36 @@
+use crate::gettext;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone};
+
+/// Parse a date time. RFC 3339 (e.g. `2024-01-02T03:04:05+09:00`) and `YYYY-MM-DD` are supported.
+/// `YYYY-MM-DD` is treated as local time.
+/// * `end_of_day` - If only date is given, use the end of the day instead of the start of the day.
+pub fn parse_date_time<S: AsRef<str> + ?Sized>(
+    s: &S,
+    end_of_day: bool,
+) -> Result<DateTime<FixedOffset>, String> {
+    let s = s.as_ref().trim();
+    if let Ok(d) = DateTime::parse_from_rfc3339(s) {
+        return Ok(d);
+    }
+    let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|e| format!("{} {} ({})", gettext("Failed to parse date:"), s, e))?;
+    let time = if end_of_day {
+        NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap()
+    } else {
+        NaiveTime::from_hms_opt(0, 0, 0).unwrap()
+    };
+    match Local.from_local_datetime(&date.and_time(time)).earliest() {
+        Some(d) => Ok(DateTime::<FixedOffset>::from(d)),
+        None => Err(format!("{} {}", gettext("Invalid local time:"), s)),
+    }
+}
+
+#[test]
+fn test_parse_date_time() {
+    let d = parse_date_time("2024-01-02T03:04:05+09:00", false).unwrap();
+    assert_eq!(d.timestamp(), 1704132245);
+    let start = parse_date_time("2024-01-02", false).unwrap();
+    let end = parse_date_time("2024-01-02", true).unwrap();
+    assert_eq!(end.timestamp() - start.timestamp(), 86399);
+    assert!(parse_date_time("2024/01/02", false).is_err());
+}
@@ -0,0 +1,114 @@
+use crate::gettext;
+use crate::pixivapp::image_urls::ImageUrls;
+use json::JsonValue;
+use std::str::FromStr;
+
+/// The size variant of the pictures to download
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivImageSize {
+    /// The original picture
+    Original,
+    /// The picture which is resized to 1200px
+    Regular,
+    /// The picture which is resized to 540px
+    Medium,
+    /// The square thumbnail
+    SquareMedium,
+}
+
+impl PixivImageSize {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Original => "original",
+            Self::Regular => "regular",
+            Self::Medium => "medium",
+            Self::SquareMedium => "square_medium",
+        }
+    }
+
+    /// Returns the link of the picture from app api's image urls.
+    /// * `urls` - The image urls
+    pub fn app_url<'a>(&self, urls: &'a ImageUrls) -> Option<&'a str> {
+        match self {
+            Self::Original => urls.original(),
+            Self::Regular => urls.large(),
+            Self::Medium => urls.medium(),
+            Self::SquareMedium => urls.square_medium(),
+        }
+    }
+
+    /// Returns the link of the picture from web api's `urls` object.
+    /// * `urls` - The `urls` object of the artwork or the page
+    pub fn web_url<'a>(&self, urls: &'a JsonValue) -> Option<&'a str> {
+        let keys: &[&str] = match self {
+            Self::Original => &["original"],
+            Self::Regular => &["regular"],
+            Self::Medium => &["small"],
+            Self::SquareMedium => &["thumb", "thumb_mini"],
+        };
+        keys.iter().find_map(|k| urls[*k].as_str())
+    }
+}
+
+impl Default for PixivImageSize {
+    fn default() -> Self {
+        Self::Original
+    }
+}
+
+impl FromStr for PixivImageSize {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "original" => Ok(Self::Original),
+            "regular" | "large" => Ok(Self::Regular),
+            "medium" => Ok(Self::Medium),
+            "square_medium" | "square" => Ok(Self::SquareMedium),
+            _ => Err(gettext("Unknown image size.")),
+        }
+    }
+}
+
+pub fn check_image_size(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => PixivImageSize::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+#[test]
+fn test_image_size() {
+    assert_eq!(
+        PixivImageSize::from_str("large"),
+        Ok(PixivImageSize::Regular)
+    );
+    assert_eq!(
+        PixivImageSize::from_str("Square-Medium"),
+        Ok(PixivImageSize::SquareMedium)
+    );
+    assert!(PixivImageSize::from_str("huge").is_err());
+    let urls = json::object! {
+        "thumb_mini": "https://i.pximg.net/c/128x128/p0_square1200.jpg",
+        "small": "https://i.pximg.net/c/540x540_70/p0_master1200.jpg",
+        "regular": "https://i.pximg.net/img-master/p0_master1200.jpg",
+        "original": "https://i.pximg.net/img-original/p0.png",
+    };
+    assert_eq!(
+        PixivImageSize::Medium.web_url(&urls),
+        Some("https://i.pximg.net/c/540x540_70/p0_master1200.jpg")
+    );
+    assert_eq!(
+        PixivImageSize::SquareMedium.web_url(&urls),
+        Some("https://i.pximg.net/c/128x128/p0_square1200.jpg")
+    );
+    let urls = ImageUrls::new(json::object! {
+        "square_medium": "https://i.pximg.net/c/360x360_70/p0_square1200.jpg",
+        "medium": "https://i.pximg.net/c/540x540_70/p0_master1200.jpg",
+        "large": "https://i.pximg.net/c/600x1200_90/p0_master1200.jpg",
+    });
+    assert_eq!(
+        PixivImageSize::Regular.app_url(&urls),
+        Some("https://i.pximg.net/c/600x1200_90/p0_master1200.jpg")
+    );
+    assert_eq!(PixivImageSize::Original.app_url(&urls), None);
+}
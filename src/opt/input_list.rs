@@ -0,0 +1,75 @@
+use crate::gettext;
+use std::fs::File;
+use std::io::{stdin, BufRead, BufReader, Result};
+
+/// Parse the entries of an input list. Blank lines and comments starting with `#` are skipped.
+/// Returns the line number (starts from 1) and the content of each entry.
+/// * `reader` - The input list
+pub fn parse_input_list<R: BufRead>(reader: R) -> Result<Vec<(usize, String)>> {
+    let mut entries = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        entries.push((i + 1, line.to_owned()));
+    }
+    Ok(entries)
+}
+
+/// Read an input list from file. `-` means stdin.
+/// * `path` - The path of the file
+pub fn read_input_list<S: AsRef<str> + ?Sized>(path: &S) -> Result<Vec<(usize, String)>> {
+    let path = path.as_ref();
+    if path == "-" {
+        parse_input_list(stdin().lock())
+    } else {
+        parse_input_list(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Read and parse entries from input lists. Invalid entries are reported with line numbers and skipped.
+/// Returns [None] if failed to read any of the lists.
+/// * `paths` - The paths of input lists
+/// * `parse` - Parse an entry. Returns [None] if the entry is invalid.
+pub fn load_input_lists<T, F: Fn(&str) -> Option<T>>(paths: &[String], parse: F) -> Option<Vec<T>> {
+    let mut re = Vec::new();
+    for path in paths {
+        let entries = match read_input_list(path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::error!("{} {} {}", gettext("Failed to read input list:"), path, e);
+                return None;
+            }
+        };
+        for (line, entry) in entries {
+            match parse(&entry) {
+                Some(v) => re.push(v),
+                None => {
+                    log::error!(
+                        "{} {}",
+                        gettext("Invalid entry at <file>:<line>:")
+                            .replace("<file>", path)
+                            .replace("<line>", &line.to_string()),
+                        entry
+                    );
+                }
+            }
+        }
+    }
+    Some(re)
+}
+
+#[test]
+fn test_parse_input_list() {
+    let list = "\u{feff}# artworks\n123\n\n  https://www.pixiv.net/artworks/456  \n#789\nhttps://example.fanbox.cc/posts/1\n";
+    assert_eq!(
+        parse_input_list(list.as_bytes()).unwrap(),
+        vec![
+            (2, String::from("123")),
+            (4, String::from("https://www.pixiv.net/artworks/456")),
+            (6, String::from("https://example.fanbox.cc/posts/1")),
+        ]
+    );
+}
@@ -0,0 +1,122 @@
+use crate::gettext;
+use json::JsonValue;
+use std::str::FromStr;
+
+/// The family of metadata written to image files
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataFamily {
+    /// Exif, including Windows `XP*` tags
+    Exif,
+    /// XMP, used by digiKam, Lightroom and most DAM software
+    Xmp,
+    /// IPTC-IIM
+    Iptc,
+}
+
+impl FromStr for MetadataFamily {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "exif" => Ok(Self::Exif),
+            "xmp" => Ok(Self::Xmp),
+            "iptc" => Ok(Self::Iptc),
+            _ => Err(gettext("Unknown metadata family.")),
+        }
+    }
+}
+
+/// A set of [MetadataFamily]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MetadataFamilies {
+    exif: bool,
+    xmp: bool,
+    iptc: bool,
+}
+
+impl MetadataFamilies {
+    /// Contains all families.
+    pub fn all() -> Self {
+        Self {
+            exif: true,
+            xmp: true,
+            iptc: true,
+        }
+    }
+
+    pub fn contains(&self, t: MetadataFamily) -> bool {
+        match t {
+            MetadataFamily::Exif => self.exif,
+            MetadataFamily::Xmp => self.xmp,
+            MetadataFamily::Iptc => self.iptc,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.exif && !self.xmp && !self.iptc
+    }
+}
+
+impl Default for MetadataFamilies {
+    /// Exif only, which is the behavior of older versions.
+    fn default() -> Self {
+        Self {
+            exif: true,
+            xmp: false,
+            iptc: false,
+        }
+    }
+}
+
+impl FromStr for MetadataFamilies {
+    type Err = &'static str;
+    /// Parse a comma separated list of families.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut re = Self {
+            exif: false,
+            xmp: false,
+            iptc: false,
+        };
+        for t in s.split(',') {
+            if t.trim().is_empty() {
+                continue;
+            }
+            match MetadataFamily::from_str(t)? {
+                MetadataFamily::Exif => re.exif = true,
+                MetadataFamily::Xmp => re.xmp = true,
+                MetadataFamily::Iptc => re.iptc = true,
+            }
+        }
+        if re.is_empty() {
+            return Err(gettext("No metadata family specified."));
+        }
+        Ok(re)
+    }
+}
+
+pub fn check_metadata_families(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => MetadataFamilies::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+#[test]
+fn test_metadata_families() {
+    let f = MetadataFamilies::from_str("XMP, iptc").unwrap();
+    assert!(!f.contains(MetadataFamily::Exif));
+    assert!(f.contains(MetadataFamily::Xmp));
+    assert!(f.contains(MetadataFamily::Iptc));
+    assert_eq!(
+        MetadataFamilies::from_str("exif,xmp,iptc").unwrap(),
+        MetadataFamilies::all()
+    );
+    assert_eq!(
+        MetadataFamilies::from_str("exif").unwrap(),
+        MetadataFamilies::default()
+    );
+    assert!(MetadataFamilies::from_str("jfif").is_err());
+    assert!(MetadataFamilies::from_str(" ,").is_err());
+    assert!(check_metadata_families(&JsonValue::from("xmp")));
+    assert!(!check_metadata_families(&JsonValue::from(1)));
+}
@@ -1,10 +1,37 @@
+/// Filters for artworks
+pub mod artwork_filter;
 /// Author name filters
 pub mod author_name_filter;
 /// libx264 Constant Rate Factor settings
 pub mod crf;
+/// Date time parser
+pub mod date;
 /// HTTP Header Map
 pub mod header_map;
+/// Size variants of pictures
+pub mod image_size;
+/// Read IDs and URLs from files
+pub mod input_list;
+#[cfg(feature = "exif")]
+/// Families of metadata written to images
+pub mod metadata_family;
+/// Page selection for multi-page artworks
+pub mod page_range;
+/// Path template for downloaded files
+pub mod path_template;
 /// Proxy settings
 pub mod proxy;
+/// Ranking options
+pub mod ranking;
+/// Per-host rate limits
+pub mod rate_limit;
+/// Artwork search options
+pub mod search;
 pub mod size;
+/// Time ranges in a day
+pub mod time_range;
+/// Output formats of ugoira(GIF)
+pub mod ugoira_format;
 pub mod use_progress_bar;
+/// Types of artworks
+pub mod work_type;
@@ -0,0 +1,161 @@
+use crate::gettext;
+use json::JsonValue;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// A page in the page range
+enum PageIndex {
+    /// Page number counted from the first page, starts from 1
+    Start(u64),
+    /// Page number counted from the last page, starts from 1
+    End(u64),
+}
+
+impl PageIndex {
+    /// Returns the index of the page, starts from 0.
+    /// [None] if the page is before the first page.
+    fn resolve(&self, total: u64) -> Option<u64> {
+        match self {
+            Self::Start(n) => Some(n - 1),
+            Self::End(n) => total.checked_sub(*n),
+        }
+    }
+}
+
+impl std::fmt::Display for PageIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Start(n) => write!(f, "{}", n),
+            Self::End(n) => write!(f, "-{}", n),
+        }
+    }
+}
+
+/// Select pages of multi-page artworks.
+///
+/// Ranges are separated by commas. Page numbers start from 1 and negative
+/// numbers are counted from the last page. For example, `1-3,7,-1` selects
+/// the first three pages, the seventh page and the last page.
+/// `5-` selects all pages from the fifth page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageRange {
+    ranges: Vec<(PageIndex, PageIndex)>,
+}
+
+impl PageRange {
+    /// Returns true if the page is selected.
+    /// * `np` - Number of page in artworks, starts from 0
+    /// * `total` - The count of pages
+    pub fn contains(&self, np: u16, total: u64) -> bool {
+        let np = np as u64;
+        if np >= total {
+            return false;
+        }
+        self.ranges.iter().any(|(start, end)| {
+            let start = start.resolve(total).unwrap_or(0);
+            match end.resolve(total) {
+                Some(end) => start <= np && np <= end,
+                None => false,
+            }
+        })
+    }
+
+    /// Returns all selected pages. Page numbers start from 0.
+    /// * `total` - The count of pages
+    pub fn select(&self, total: u64) -> Vec<u16> {
+        let total = total.min(u16::MAX as u64 + 1);
+        (0..total)
+            .map(|np| np as u16)
+            .filter(|np| self.contains(*np, total))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for PageRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (start, end)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            if start == end {
+                write!(f, "{}", start)?;
+            } else {
+                write!(f, "{}-{}", start, end)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a page number at the beginning of the string.
+fn parse_page_index(s: &str) -> Result<(PageIndex, &str), &'static str> {
+    let (neg, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let n = match s[..len].parse::<u64>() {
+        Ok(n) if n > 0 => n,
+        _ => return Err(gettext("Invalid page number.")),
+    };
+    let index = if neg {
+        PageIndex::End(n)
+    } else {
+        PageIndex::Start(n)
+    };
+    Ok((index, &s[len..]))
+}
+
+impl FromStr for PageRange {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = Vec::new();
+        for item in s.split(',') {
+            let item = item.trim();
+            let (start, rest) = parse_page_index(item)?;
+            if rest.is_empty() {
+                ranges.push((start, start));
+                continue;
+            }
+            let rest = rest
+                .strip_prefix('-')
+                .ok_or(gettext("Invalid page range."))?;
+            if rest.is_empty() {
+                ranges.push((start, PageIndex::End(1)));
+                continue;
+            }
+            let (end, rest) = parse_page_index(rest)?;
+            if !rest.is_empty() {
+                return Err(gettext("Invalid page range."));
+            }
+            ranges.push((start, end));
+        }
+        Ok(Self { ranges })
+    }
+}
+
+pub fn check_page_range(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => PageRange::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+#[test]
+fn test_page_range() {
+    let r = PageRange::from_str("1-3,7,-1").unwrap();
+    assert_eq!(r.select(10), vec![0, 1, 2, 6, 9]);
+    assert_eq!(r.select(2), vec![0, 1]);
+    assert_eq!(r.to_string(), "1-3,7,-1");
+    let r = PageRange::from_str(" 5- , -3--2").unwrap();
+    assert_eq!(r.select(8), vec![4, 5, 6, 7]);
+    assert_eq!(r.select(3), vec![0, 1]);
+    assert!(r.contains(1, 3));
+    assert!(!r.contains(3, 3));
+    let r = PageRange::from_str("-5").unwrap();
+    assert_eq!(r.select(3), Vec::<u16>::new());
+    assert!(PageRange::from_str("").is_err());
+    assert!(PageRange::from_str("0").is_err());
+    assert!(PageRange::from_str("1-2-3").is_err());
+    assert!(PageRange::from_str("a").is_err());
+}
@@ -0,0 +1,439 @@
+use crate::data::data::PixivData;
+use crate::gettext;
+use chrono::format::{Item, StrftimeItems};
+use json::JsonValue;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The maximum length (in bytes) of a path component.
+const MAX_COMPONENT_LEN: usize = 255;
+
+/// How to sanitize the components of the path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathSanitizeMode {
+    /// Follow the rules of current platform.
+    Auto,
+    /// Only `/` and NUL are not allowed.
+    Posix,
+    /// Also remove characters which are not allowed on NTFS/FAT and reserved names.
+    Windows,
+}
+
+impl PathSanitizeMode {
+    /// Returns true if the rules of Windows should be applied.
+    pub fn is_windows(&self) -> bool {
+        match self {
+            Self::Auto => cfg!(windows),
+            Self::Posix => false,
+            Self::Windows => true,
+        }
+    }
+}
+
+impl Default for PathSanitizeMode {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl FromStr for PathSanitizeMode {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        match s.as_str() {
+            "auto" => Ok(Self::Auto),
+            "posix" | "unix" => Ok(Self::Posix),
+            "windows" | "win" => Ok(Self::Windows),
+            _ => Err(gettext("Unknown path sanitize mode.")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The part of the path template
+enum PathTemplateToken {
+    /// Plain text
+    Text(String),
+    /// Path separator
+    Separator,
+    /// Placeholder with optional argument
+    Var(String, Option<String>),
+}
+
+/// A template used to generate the path of the downloaded files.
+///
+/// Available placeholders:
+/// * `{id}` - Artwork ID
+/// * `{title}` - Title
+/// * `{author}` - Author's name
+/// * `{uid}` - Author's user ID
+/// * `{page}` / `{page:WIDTH}` - Page index, starts from 0
+/// * `{name}` - Original file name without extension
+/// * `{ext}` - File extension
+/// * `{date}` / `{date:FORMAT}` - Upload date, FORMAT is a strftime format. Default: `%Y-%m-%d`
+/// * `{ai}` / `{ai:TEXT}` - `AI` (or TEXT) if the artwork is AI-generated, otherwise empty
+/// * `{tags}` / `{tags:COUNT}` - The first COUNT tags joined with `,`. Default: all tags
+/// * `{series}` - The title of the manga series, empty if not downloaded as a series
+/// * `{series_id}` - Manga series ID
+/// * `{chapter}` / `{chapter:WIDTH}` - Chapter number in the manga series, starts from 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathTemplate {
+    tokens: Vec<PathTemplateToken>,
+}
+
+/// The values used when rendering [PathTemplate]
+pub struct PathTemplateContext<'a> {
+    /// The artwork's data
+    pub data: &'a PixivData,
+    /// Page index. [None] for the files which are not belong to a page.
+    pub page: Option<u16>,
+    /// Original file name without extension
+    pub name: &'a str,
+    /// File extension
+    pub ext: &'a str,
+}
+
+impl PathTemplate {
+    fn render_var(name: &str, arg: Option<&str>, ctx: &PathTemplateContext) -> Option<String> {
+        let data = ctx.data;
+        match name {
+            "id" => {
+                let id: Result<u64, ()> = (&data.id).try_into();
+                id.ok().map(|id| format!("{}", id))
+            }
+            "title" => data.title.clone(),
+            "author" => data.author.clone(),
+            "uid" => data.author_id.map(|id| format!("{}", id)),
+            "page" => ctx.page.map(|p| match arg {
+                Some(w) => format!("{:0width$}", p, width = w.parse::<usize>().unwrap_or(0)),
+                None => format!("{}", p),
+            }),
+            "name" => Some(ctx.name.to_owned()),
+            "ext" => Some(ctx.ext.to_owned()),
+            "date" => data
+                .upload_date
+                .as_ref()
+                .map(|d| d.format(arg.unwrap_or("%Y-%m-%d")).to_string()),
+            "ai" => match &data.ai_type {
+                Some(t) if t.is_ai() => Some(arg.unwrap_or("AI").to_owned()),
+                _ => None,
+            },
+            "tags" => data.tags.as_ref().map(|tags| {
+                let count = arg
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .unwrap_or(tags.len());
+                tags.iter()
+                    .take(count)
+                    .map(|t| t.0.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+            "series" => data.series.as_ref().map(|s| s.series_title.clone()),
+            "series_id" => data.series.as_ref().map(|s| format!("{}", s.series_id)),
+            "chapter" => data.series.as_ref().map(|s| match arg {
+                Some(w) => format!(
+                    "{:0width$}",
+                    s.order,
+                    width = w.parse::<usize>().unwrap_or(0)
+                ),
+                None => format!("{}", s.order),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Render the template to a relative path.
+    /// * `ctx` - Values used in placeholders
+    /// * `mode` - How to sanitize the path
+    pub fn render(&self, ctx: &PathTemplateContext, mode: PathSanitizeMode) -> PathBuf {
+        let mut components = vec![String::new()];
+        for token in self.tokens.iter() {
+            match token {
+                PathTemplateToken::Text(s) => components.last_mut().unwrap().push_str(s),
+                PathTemplateToken::Separator => components.push(String::new()),
+                PathTemplateToken::Var(name, arg) => {
+                    if let Some(v) = Self::render_var(name, arg.as_ref().map(|s| s.as_str()), ctx) {
+                        components
+                            .last_mut()
+                            .unwrap()
+                            .push_str(&sanitize_value(&v, mode));
+                    }
+                }
+            }
+        }
+        let len = components.len();
+        let mut re = PathBuf::new();
+        for (i, c) in components.into_iter().enumerate() {
+            if let Some(c) = sanitize_component(&c, mode, i + 1 == len) {
+                re.push(c);
+            }
+        }
+        re
+    }
+}
+
+impl FromStr for PathTemplate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    if chars.peek() == Some(&'{') {
+                        chars.next();
+                        text.push('{');
+                        continue;
+                    }
+                    let mut var = String::new();
+                    let mut closed = false;
+                    while let Some(c) = chars.next() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        var.push(c);
+                    }
+                    if !closed {
+                        return Err(format!(
+                            "{} {}",
+                            gettext("Unclosed placeholder in path template:"),
+                            s
+                        ));
+                    }
+                    let (name, arg) = match var.split_once(':') {
+                        Some((name, arg)) => (name.trim().to_owned(), Some(arg.to_owned())),
+                        None => (var.trim().to_owned(), None),
+                    };
+                    match name.as_str() {
+                        "id" | "title" | "author" | "uid" | "name" | "ext" | "ai" | "series"
+                        | "series_id" => {}
+                        "page" | "tags" | "chapter" => {
+                            if let Some(arg) = &arg {
+                                if arg.parse::<usize>().is_err() {
+                                    return Err(format!(
+                                        "{} {}",
+                                        gettext("Invalid placeholder argument:"),
+                                        var
+                                    ));
+                                }
+                            }
+                        }
+                        "date" => {
+                            if let Some(arg) = &arg {
+                                if StrftimeItems::new(arg).any(|i| i == Item::Error) {
+                                    return Err(format!(
+                                        "{} {}",
+                                        gettext("Invalid date format:"),
+                                        arg
+                                    ));
+                                }
+                            }
+                        }
+                        _ => {
+                            return Err(format!("{} {}", gettext("Unknown placeholder:"), name));
+                        }
+                    }
+                    if !text.is_empty() {
+                        tokens.push(PathTemplateToken::Text(text));
+                        text = String::new();
+                    }
+                    tokens.push(PathTemplateToken::Var(name, arg));
+                }
+                '}' => {
+                    if chars.peek() == Some(&'}') {
+                        chars.next();
+                    }
+                    text.push('}');
+                }
+                '/' | '\\' => {
+                    if !text.is_empty() {
+                        tokens.push(PathTemplateToken::Text(text));
+                        text = String::new();
+                    }
+                    tokens.push(PathTemplateToken::Separator);
+                }
+                _ => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            tokens.push(PathTemplateToken::Text(text));
+        }
+        if tokens.is_empty() {
+            return Err(String::from(gettext("Path template is empty.")));
+        }
+        Ok(Self { tokens })
+    }
+}
+
+/// Replace characters which are not allowed in the value of a placeholder.
+fn sanitize_value(s: &str, mode: PathSanitizeMode) -> String {
+    let windows = mode.is_windows();
+    s.chars()
+        .map(|c| match c {
+            '/' | '\\' => '_',
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' if windows => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Sanitize a component of the path. Returns [None] if the component should be skipped.
+/// * `is_file` - Whether the component is the file name.
+fn sanitize_component(s: &str, mode: PathSanitizeMode, is_file: bool) -> Option<String> {
+    let windows = mode.is_windows();
+    let mut s: String = s
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' if windows => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if windows {
+        let trimed = s.trim_end_matches(|c| c == '.' || c == ' ');
+        s = trimed.to_owned();
+        let stem = s
+            .split('.')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_uppercase();
+        let reserved = match stem.as_str() {
+            "CON" | "PRN" | "AUX" | "NUL" => true,
+            _ => {
+                (stem.starts_with("COM") || stem.starts_with("LPT"))
+                    && stem.len() == 4
+                    && stem.as_bytes()[3].is_ascii_digit()
+            }
+        };
+        if reserved {
+            s.insert(0, '_');
+        }
+    }
+    let s = s.trim();
+    if s.is_empty() || s == "." {
+        return None;
+    }
+    if s == ".." {
+        return Some(String::from("_"));
+    }
+    Some(truncate_component(s, is_file))
+}
+
+/// Truncate the component if it is too long. The extension of file name is kept.
+fn truncate_component(s: &str, is_file: bool) -> String {
+    if s.len() <= MAX_COMPONENT_LEN {
+        return s.to_owned();
+    }
+    let (stem, ext) = match s.rfind('.') {
+        Some(i) if is_file && s.len() - i < 16 => (&s[..i], &s[i..]),
+        _ => (s, ""),
+    };
+    let mut end = MAX_COMPONENT_LEN - ext.len();
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &stem[..end], ext)
+}
+
+pub fn check_path_template(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => match PathTemplate::from_str(s) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("{}", e);
+                false
+            }
+        },
+        None => false,
+    }
+}
+
+pub fn check_path_sanitize_mode(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => PathSanitizeMode::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+#[test]
+fn test_path_template() {
+    use crate::data::data::{PixivAiType, PixivSeriesChapter};
+    use chrono::DateTime;
+    let mut data = PixivData::new(99014872u64).unwrap();
+    data.title = Some(String::from("a/b:c"));
+    data.author = Some(String::from("作者"));
+    data.author_id = Some(123);
+    data.upload_date = Some(DateTime::parse_from_rfc3339("2024-05-03T12:00:00+09:00").unwrap());
+    data.ai_type = Some(PixivAiType::AI);
+    data.tags = Some(vec![
+        (String::from("tag1"), None),
+        (String::from("tag2"), Some(String::from("t2"))),
+        (String::from("tag3"), None),
+    ]);
+    let ctx = PathTemplateContext {
+        data: &data,
+        page: Some(1),
+        name: "99014872_p1",
+        ext: "png",
+    };
+    let t = PathTemplate::from_str("{author} ({uid})/{date:%Y-%m}/{id}_p{page}.{ext}").unwrap();
+    assert_eq!(
+        t.render(&ctx, PathSanitizeMode::Posix),
+        PathBuf::from("作者 (123)/2024-05/99014872_p1.png")
+    );
+    let t = PathTemplate::from_str("{ai}/{title}_{tags:2}_{page:3}.{ext}").unwrap();
+    assert_eq!(
+        t.render(&ctx, PathSanitizeMode::Windows),
+        PathBuf::from("AI/a_b_c_tag1,tag2_001.png")
+    );
+    data.ai_type = Some(PixivAiType::NonAI);
+    let ctx = PathTemplateContext {
+        data: &data,
+        page: None,
+        name: "99014872",
+        ext: "json",
+    };
+    assert_eq!(
+        t.render(&ctx, PathSanitizeMode::Posix),
+        PathBuf::from("a_b:c_tag1,tag2_.json")
+    );
+    assert_eq!(
+        PathTemplate::from_str("../{{id}}/{id}")
+            .unwrap()
+            .render(&ctx, PathSanitizeMode::Posix),
+        PathBuf::from("_/{id}/99014872")
+    );
+    data.series = Some(PixivSeriesChapter {
+        series_id: 42,
+        series_title: String::from("series"),
+        order: 7,
+        total: 12,
+    });
+    let ctx = PathTemplateContext {
+        data: &data,
+        page: None,
+        name: "99014872",
+        ext: "json",
+    };
+    assert_eq!(
+        PathTemplate::from_str("{series} ({series_id})/{chapter:2}")
+            .unwrap()
+            .render(&ctx, PathSanitizeMode::Posix),
+        PathBuf::from("series (42)/07")
+    );
+    assert!(PathTemplate::from_str("{unknown}").is_err());
+    assert!(PathTemplate::from_str("{id").is_err());
+    assert!(PathTemplate::from_str("{date:%Q}").is_err());
+    assert_eq!(
+        sanitize_component("con.txt", PathSanitizeMode::Windows, true),
+        Some(String::from("_con.txt"))
+    );
+    assert_eq!(
+        sanitize_component("abc. ", PathSanitizeMode::Windows, false),
+        Some(String::from("abc"))
+    );
+}
@@ -0,0 +1,174 @@
+use crate::gettext;
+use std::str::FromStr;
+
+/// Pixiv ranking mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivRankingMode {
+    Daily,
+    Weekly,
+    Monthly,
+    Rookie,
+    Original,
+    DailyAI,
+    Male,
+    Female,
+    DailyR18,
+    WeeklyR18,
+    MaleR18,
+    FemaleR18,
+    R18G,
+    DailyR18AI,
+}
+
+impl PixivRankingMode {
+    /// The value of `mode` in web API.
+    pub fn web_value(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Rookie => "rookie",
+            Self::Original => "original",
+            Self::DailyAI => "daily_ai",
+            Self::Male => "male",
+            Self::Female => "female",
+            Self::DailyR18 => "daily_r18",
+            Self::WeeklyR18 => "weekly_r18",
+            Self::MaleR18 => "male_r18",
+            Self::FemaleR18 => "female_r18",
+            Self::R18G => "r18g",
+            Self::DailyR18AI => "daily_r18_ai",
+        }
+    }
+
+    /// The value of `mode` in app API.
+    /// * `content` - Content type. Most of modes in app API only support all types.
+    pub fn app_value(&self, content: PixivRankingContent) -> Option<&'static str> {
+        match content {
+            PixivRankingContent::All => Some(match self {
+                Self::Daily => "day",
+                Self::Weekly => "week",
+                Self::Monthly => "month",
+                Self::Rookie => "week_rookie",
+                Self::Original => "week_original",
+                Self::DailyAI => "day_ai",
+                Self::Male => "day_male",
+                Self::Female => "day_female",
+                Self::DailyR18 => "day_r18",
+                Self::WeeklyR18 => "week_r18",
+                Self::MaleR18 => "day_male_r18",
+                Self::FemaleR18 => "day_female_r18",
+                Self::R18G => "week_r18g",
+                Self::DailyR18AI => "day_r18_ai",
+            }),
+            PixivRankingContent::Manga => match self {
+                Self::Daily => Some("day_manga"),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns true if the ranking contains R-18 artworks.
+    pub fn is_r18(&self) -> bool {
+        match self {
+            Self::DailyR18
+            | Self::WeeklyR18
+            | Self::MaleR18
+            | Self::FemaleR18
+            | Self::R18G
+            | Self::DailyR18AI => true,
+            _ => false,
+        }
+    }
+}
+
+impl Default for PixivRankingMode {
+    fn default() -> Self {
+        Self::Daily
+    }
+}
+
+impl FromStr for PixivRankingMode {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "daily" | "day" => Ok(Self::Daily),
+            "weekly" | "week" => Ok(Self::Weekly),
+            "monthly" | "month" => Ok(Self::Monthly),
+            "rookie" => Ok(Self::Rookie),
+            "original" => Ok(Self::Original),
+            "daily_ai" => Ok(Self::DailyAI),
+            "male" => Ok(Self::Male),
+            "female" => Ok(Self::Female),
+            "daily_r18" | "r18" => Ok(Self::DailyR18),
+            "weekly_r18" => Ok(Self::WeeklyR18),
+            "male_r18" => Ok(Self::MaleR18),
+            "female_r18" => Ok(Self::FemaleR18),
+            "r18g" => Ok(Self::R18G),
+            "daily_r18_ai" => Ok(Self::DailyR18AI),
+            _ => Err(gettext("Unknown ranking mode.")),
+        }
+    }
+}
+
+/// The content type of pixiv ranking
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivRankingContent {
+    All,
+    Illust,
+    Manga,
+    Ugoira,
+}
+
+impl PixivRankingContent {
+    /// The value of `content` in web API. [None] for all types.
+    pub fn web_value(&self) -> Option<&'static str> {
+        match self {
+            Self::All => None,
+            Self::Illust => Some("illust"),
+            Self::Manga => Some("manga"),
+            Self::Ugoira => Some("ugoira"),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.web_value().unwrap_or("all")
+    }
+}
+
+impl Default for PixivRankingContent {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+impl FromStr for PixivRankingContent {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "all" => Ok(Self::All),
+            "illust" | "illusts" => Ok(Self::Illust),
+            "manga" => Ok(Self::Manga),
+            "ugoira" => Ok(Self::Ugoira),
+            _ => Err(gettext("Unknown ranking content type.")),
+        }
+    }
+}
+
+#[test]
+fn test_ranking_opts() {
+    let mode = PixivRankingMode::from_str("weekly-r18").unwrap();
+    assert_eq!(mode, PixivRankingMode::WeeklyR18);
+    assert!(mode.is_r18());
+    assert_eq!(mode.app_value(PixivRankingContent::All), Some("week_r18"));
+    assert_eq!(
+        PixivRankingMode::Original.app_value(PixivRankingContent::Manga),
+        None
+    );
+    assert_eq!(
+        PixivRankingContent::from_str("ugoira").unwrap().web_value(),
+        Some("ugoira")
+    );
+    assert!(PixivRankingMode::from_str("yearly").is_err());
+}
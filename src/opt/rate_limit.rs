@@ -0,0 +1,90 @@
+use crate::gettext;
+use json::JsonValue;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The minimum intervals between two requests to the same host.
+///
+/// In settings, it is an object which maps host names to intervals in
+/// milliseconds, for example `{"www.pixiv.net": 1000, "i.pximg.net": 200}`.
+/// In command line, it is a list like `www.pixiv.net=1000,i.pximg.net=200`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostRateLimits {
+    limits: HashMap<String, Duration>,
+}
+
+impl HostRateLimits {
+    /// Returns the minimum interval of the host.
+    /// * `host` - The host name
+    pub fn get(&self, host: &str) -> Option<Duration> {
+        self.limits.get(&host.to_ascii_lowercase()).cloned()
+    }
+
+    /// Add the limits from another one. Existing hosts are overwritten.
+    pub fn extend(&mut self, other: &Self) {
+        for (k, v) in other.limits.iter() {
+            self.limits.insert(k.clone(), v.clone());
+        }
+    }
+
+    pub fn from_json(obj: &JsonValue) -> Result<Self, &'static str> {
+        if !obj.is_object() {
+            return Err(gettext("Unsupported JSON type."));
+        }
+        let mut limits = HashMap::new();
+        for (k, v) in obj.entries() {
+            let ms = v
+                .as_u64()
+                .ok_or(gettext("The interval should be a non-negative integer."))?;
+            limits.insert(k.to_ascii_lowercase(), Duration::from_millis(ms));
+        }
+        Ok(Self { limits })
+    }
+}
+
+impl FromStr for HostRateLimits {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut limits = HashMap::new();
+        for item in s.split(',') {
+            let (host, ms) = item
+                .split_once('=')
+                .ok_or(gettext("Invalid rate limit. Format: HOST=MILLISECONDS"))?;
+            let host = host.trim();
+            if host.is_empty() {
+                return Err(gettext("Invalid rate limit. Format: HOST=MILLISECONDS"));
+            }
+            let ms = ms
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| gettext("The interval should be a non-negative integer."))?;
+            limits.insert(host.to_ascii_lowercase(), Duration::from_millis(ms));
+        }
+        Ok(Self { limits })
+    }
+}
+
+pub fn check_host_rate_limits(obj: &JsonValue) -> bool {
+    HostRateLimits::from_json(obj).is_ok()
+}
+
+#[test]
+fn test_host_rate_limits() {
+    let mut l = HostRateLimits::from_json(&json::object! {
+        "www.pixiv.net": 1000,
+        "i.pximg.net": 200,
+    })
+    .unwrap();
+    assert_eq!(l.get("WWW.pixiv.net"), Some(Duration::from_secs(1)));
+    assert_eq!(l.get("api.fanbox.cc"), None);
+    let o = HostRateLimits::from_str("i.pximg.net=0, api.fanbox.cc = 1500").unwrap();
+    l.extend(&o);
+    assert_eq!(l.get("i.pximg.net"), Some(Duration::ZERO));
+    assert_eq!(l.get("api.fanbox.cc"), Some(Duration::from_millis(1500)));
+    assert!(HostRateLimits::from_str("www.pixiv.net").is_err());
+    assert!(HostRateLimits::from_str("=100").is_err());
+    assert!(HostRateLimits::from_str("www.pixiv.net=-1").is_err());
+    assert!(HostRateLimits::from_json(&json::object! {"a": "b"}).is_err());
+    assert!(HostRateLimits::from_json(&json::array![]).is_err());
+}
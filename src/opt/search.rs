@@ -0,0 +1,234 @@
+use super::work_type::{PixivWorkType, PixivWorkTypes};
+use crate::gettext;
+use chrono::NaiveDate;
+use std::str::FromStr;
+
+/// How keywords are matched when searching artworks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivSearchMatch {
+    /// Tags which are exactly the same as keywords
+    Exact,
+    /// Tags which contain keywords
+    Partial,
+    /// Title and caption
+    Title,
+}
+
+impl PixivSearchMatch {
+    /// The value of `s_mode` in web API.
+    pub fn web_value(&self) -> &'static str {
+        match self {
+            Self::Exact => "s_tag_full",
+            Self::Partial => "s_tag",
+            Self::Title => "s_tc",
+        }
+    }
+
+    /// The value of `search_target` in app API.
+    pub fn app_value(&self) -> &'static str {
+        match self {
+            Self::Exact => "exact_match_for_tags",
+            Self::Partial => "partial_match_for_tags",
+            Self::Title => "title_and_caption",
+        }
+    }
+
+    /// Convert from `s_mode` in web API.
+    pub fn from_web_value(s: &str) -> Option<Self> {
+        match s {
+            "s_tag_full" => Some(Self::Exact),
+            "s_tag" => Some(Self::Partial),
+            "s_tc" => Some(Self::Title),
+            _ => None,
+        }
+    }
+}
+
+impl Default for PixivSearchMatch {
+    fn default() -> Self {
+        Self::Exact
+    }
+}
+
+impl FromStr for PixivSearchMatch {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "exact" | "full" => Ok(Self::Exact),
+            "partial" => Ok(Self::Partial),
+            "title" | "caption" => Ok(Self::Title),
+            _ => Err(gettext("Unknown search match type.")),
+        }
+    }
+}
+
+/// Filter search results by age restriction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivSearchMode {
+    /// All ages only
+    Safe,
+    /// R-18 only
+    R18,
+    /// Both
+    All,
+}
+
+impl PixivSearchMode {
+    /// The value of `mode` in web API.
+    pub fn web_value(&self) -> &'static str {
+        match self {
+            Self::Safe => "safe",
+            Self::R18 => "r18",
+            Self::All => "all",
+        }
+    }
+
+    /// Returns true if the artwork with `x_restrict` matches the mode.
+    pub fn matches(&self, x_restrict: u64) -> bool {
+        match self {
+            Self::Safe => x_restrict == 0,
+            Self::R18 => x_restrict > 0,
+            Self::All => true,
+        }
+    }
+}
+
+impl Default for PixivSearchMode {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+impl FromStr for PixivSearchMode {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "safe" => Ok(Self::Safe),
+            "r18" | "r-18" => Ok(Self::R18),
+            "all" => Ok(Self::All),
+            _ => Err(gettext("Unknown search mode.")),
+        }
+    }
+}
+
+/// The order of search results
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivSearchSort {
+    Newest,
+    Oldest,
+    /// Most popular first. Only available for premium users.
+    Popular,
+}
+
+impl PixivSearchSort {
+    /// The value of `order` in web API.
+    pub fn web_value(&self) -> &'static str {
+        match self {
+            Self::Newest => "date_d",
+            Self::Oldest => "date",
+            Self::Popular => "popular_d",
+        }
+    }
+
+    /// The value of `sort` in app API.
+    pub fn app_value(&self) -> &'static str {
+        match self {
+            Self::Newest => "date_desc",
+            Self::Oldest => "date_asc",
+            Self::Popular => "popular_desc",
+        }
+    }
+
+    /// Convert from `order` in web API.
+    pub fn from_web_value(s: &str) -> Option<Self> {
+        match s {
+            "date_d" => Some(Self::Newest),
+            "date" => Some(Self::Oldest),
+            "popular_d" => Some(Self::Popular),
+            _ => None,
+        }
+    }
+}
+
+impl Default for PixivSearchSort {
+    fn default() -> Self {
+        Self::Newest
+    }
+}
+
+impl FromStr for PixivSearchSort {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "newest" | "date_desc" => Ok(Self::Newest),
+            "oldest" | "date_asc" => Ok(Self::Oldest),
+            "popular" | "popular_desc" => Ok(Self::Popular),
+            _ => Err(gettext("Unknown search sort order.")),
+        }
+    }
+}
+
+/// The parameters used to search artworks
+#[derive(Clone, Debug)]
+pub struct PixivSearchParams {
+    /// Keywords, separated by space
+    pub keywords: String,
+    pub match_type: PixivSearchMatch,
+    pub mode: PixivSearchMode,
+    pub sort: PixivSearchSort,
+    pub types: PixivWorkTypes,
+    /// Only artworks uploaded after this date
+    pub since: Option<NaiveDate>,
+    /// Only artworks uploaded before this date
+    pub until: Option<NaiveDate>,
+}
+
+/// Get the value of `type` in web API. Returns `all` if the types can not be represented.
+pub fn get_web_search_type(types: &PixivWorkTypes) -> &'static str {
+    let illust = types.contains(PixivWorkType::Illust);
+    let manga = types.contains(PixivWorkType::Manga);
+    let ugoira = types.contains(PixivWorkType::Ugoira);
+    match (illust, manga, ugoira) {
+        (true, false, true) => "illust_and_ugoira",
+        (true, false, false) => "illust",
+        (false, true, false) => "manga",
+        (false, false, true) => "ugoira",
+        _ => "all",
+    }
+}
+
+/// Convert from `type` in web API.
+pub fn parse_web_search_type(s: &str) -> Option<PixivWorkTypes> {
+    match s {
+        "all" => Some(PixivWorkTypes::all()),
+        "illust_and_ugoira" => Some(PixivWorkTypes::illustrations()),
+        "manga" => Some(PixivWorkTypes::manga()),
+        _ => PixivWorkTypes::from_str(s).ok(),
+    }
+}
+
+#[test]
+fn test_search_opts() {
+    assert_eq!(
+        PixivSearchMatch::from_str("partial").unwrap().web_value(),
+        "s_tag"
+    );
+    assert_eq!(
+        PixivSearchSort::from_web_value("popular_d"),
+        Some(PixivSearchSort::Popular)
+    );
+    assert!(PixivSearchMode::Safe.matches(0));
+    assert!(!PixivSearchMode::R18.matches(0));
+    assert_eq!(
+        get_web_search_type(&PixivWorkTypes::illustrations()),
+        "illust_and_ugoira"
+    );
+    assert_eq!(
+        get_web_search_type(&PixivWorkTypes::from_str("illust,manga").unwrap()),
+        "all"
+    );
+    assert_eq!(
+        parse_web_search_type("ugoira"),
+        Some(PixivWorkTypes::from_str("ugoira").unwrap())
+    );
+}
@@ -0,0 +1,74 @@
+use crate::gettext;
+use chrono::NaiveTime;
+use json::JsonValue;
+use std::str::FromStr;
+
+/// Parse a time of day. Format: `HH:MM` or `HH:MM:SS`.
+fn parse_time(s: &str) -> Result<NaiveTime, &'static str> {
+    let s = s.trim();
+    NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .map_err(|_| gettext("Invalid time of day."))
+}
+
+/// Time ranges in a day.
+///
+/// Ranges are separated by commas, for example `08:00-12:00,13:30-18:00`.
+/// The start is included and the end is excluded. A range whose end is
+/// earlier than the start wraps around midnight, like `22:00-06:00`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeRanges {
+    ranges: Vec<(NaiveTime, NaiveTime)>,
+}
+
+impl TimeRanges {
+    /// Returns true if the time is in any range.
+    /// * `time` - The time of day
+    pub fn contains(&self, time: &NaiveTime) -> bool {
+        self.ranges.iter().any(|(start, end)| {
+            if start <= end {
+                start <= time && time < end
+            } else {
+                start <= time || time < end
+            }
+        })
+    }
+}
+
+impl FromStr for TimeRanges {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = Vec::new();
+        for item in s.split(',') {
+            let (start, end) = item.split_once('-').ok_or(gettext("Invalid time range."))?;
+            ranges.push((parse_time(start)?, parse_time(end)?));
+        }
+        Ok(Self { ranges })
+    }
+}
+
+pub fn check_time_ranges(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => TimeRanges::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+#[test]
+fn test_time_ranges() {
+    let t = |s| NaiveTime::parse_from_str(s, "%H:%M:%S").unwrap();
+    let r = TimeRanges::from_str("08:00-12:00, 13:30-18:00").unwrap();
+    assert!(r.contains(&t("08:00:00")));
+    assert!(r.contains(&t("11:59:59")));
+    assert!(!r.contains(&t("12:00:00")));
+    assert!(r.contains(&t("17:00:00")));
+    assert!(!r.contains(&t("20:00:00")));
+    let r = TimeRanges::from_str("22:00-06:30:30").unwrap();
+    assert!(r.contains(&t("23:00:00")));
+    assert!(r.contains(&t("06:30:00")));
+    assert!(!r.contains(&t("06:30:30")));
+    assert!(!r.contains(&t("12:00:00")));
+    assert!(TimeRanges::from_str("").is_err());
+    assert!(TimeRanges::from_str("08:00").is_err());
+    assert!(TimeRanges::from_str("25:00-08:00").is_err());
+}
@@ -0,0 +1,229 @@
+use crate::gettext;
+use json::JsonValue;
+use std::str::FromStr;
+
+/// The output format when converting ugoira(GIF)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UgoiraFormat {
+    /// H.264 video in MP4 container
+    Mp4,
+    /// Animated GIF with per-frame optimised palettes
+    Gif,
+    /// Animated WebP
+    Webp,
+    /// Animated PNG
+    Apng,
+}
+
+impl UgoiraFormat {
+    /// Returns the name used by ugoira cli.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Apng => "apng",
+        }
+    }
+
+    /// Returns the extension of the output file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Apng => "png",
+        }
+    }
+}
+
+impl FromStr for UgoiraFormat {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "mp4" | "video" => Ok(Self::Mp4),
+            "gif" => Ok(Self::Gif),
+            "webp" => Ok(Self::Webp),
+            "apng" | "png" => Ok(Self::Apng),
+            _ => Err(gettext("Unknown ugoira format.")),
+        }
+    }
+}
+
+/// A set of [UgoiraFormat]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UgoiraFormats {
+    mp4: bool,
+    gif: bool,
+    webp: bool,
+    apng: bool,
+}
+
+impl UgoiraFormats {
+    pub fn contains(&self, t: UgoiraFormat) -> bool {
+        match t {
+            UgoiraFormat::Mp4 => self.mp4,
+            UgoiraFormat::Gif => self.gif,
+            UgoiraFormat::Webp => self.webp,
+            UgoiraFormat::Apng => self.apng,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.mp4 && !self.gif && !self.webp && !self.apng
+    }
+
+    /// Returns the formats in the set. MP4 is always the first one.
+    pub fn to_vec(&self) -> Vec<UgoiraFormat> {
+        [
+            UgoiraFormat::Mp4,
+            UgoiraFormat::Gif,
+            UgoiraFormat::Webp,
+            UgoiraFormat::Apng,
+        ]
+        .into_iter()
+        .filter(|t| self.contains(*t))
+        .collect()
+    }
+}
+
+impl Default for UgoiraFormats {
+    /// MP4 only, which is the behavior of older versions.
+    fn default() -> Self {
+        Self {
+            mp4: true,
+            gif: false,
+            webp: false,
+            apng: false,
+        }
+    }
+}
+
+impl FromStr for UgoiraFormats {
+    type Err = &'static str;
+    /// Parse a comma separated list of formats.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut re = Self {
+            mp4: false,
+            gif: false,
+            webp: false,
+            apng: false,
+        };
+        for t in s.split(',') {
+            if t.trim().is_empty() {
+                continue;
+            }
+            match UgoiraFormat::from_str(t)? {
+                UgoiraFormat::Mp4 => re.mp4 = true,
+                UgoiraFormat::Gif => re.gif = true,
+                UgoiraFormat::Webp => re.webp = true,
+                UgoiraFormat::Apng => re.apng = true,
+            }
+        }
+        if re.is_empty() {
+            return Err(gettext("No ugoira format specified."));
+        }
+        Ok(re)
+    }
+}
+
+/// The dither algorithm used by the palette filter when converting ugoira(GIF) to GIF.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GifDither {
+    Bayer,
+    Heckbert,
+    FloydSteinberg,
+    Sierra2,
+    Sierra2_4a,
+    Sierra3,
+    Burkes,
+    Atkinson,
+    None,
+}
+
+impl GifDither {
+    /// Returns the name used by ffmpeg's paletteuse filter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bayer => "bayer",
+            Self::Heckbert => "heckbert",
+            Self::FloydSteinberg => "floyd_steinberg",
+            Self::Sierra2 => "sierra2",
+            Self::Sierra2_4a => "sierra2_4a",
+            Self::Sierra3 => "sierra3",
+            Self::Burkes => "burkes",
+            Self::Atkinson => "atkinson",
+            Self::None => "none",
+        }
+    }
+}
+
+impl Default for GifDither {
+    fn default() -> Self {
+        Self::Sierra2_4a
+    }
+}
+
+impl FromStr for GifDither {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "bayer" => Ok(Self::Bayer),
+            "heckbert" => Ok(Self::Heckbert),
+            "floyd_steinberg" => Ok(Self::FloydSteinberg),
+            "sierra2" => Ok(Self::Sierra2),
+            "sierra2_4a" => Ok(Self::Sierra2_4a),
+            "sierra3" => Ok(Self::Sierra3),
+            "burkes" => Ok(Self::Burkes),
+            "atkinson" => Ok(Self::Atkinson),
+            "none" => Ok(Self::None),
+            _ => Err(gettext("Unknown dither algorithm.")),
+        }
+    }
+}
+
+pub fn check_gif_dither(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => GifDither::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+pub fn check_ugoira_formats(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => UgoiraFormats::from_str(s).is_ok(),
+        None => false,
+    }
+}
+
+#[test]
+fn test_ugoira_formats() {
+    let f = UgoiraFormats::from_str("WebP, gif").unwrap();
+    assert!(!f.contains(UgoiraFormat::Mp4));
+    assert!(f.contains(UgoiraFormat::Gif));
+    assert!(f.contains(UgoiraFormat::Webp));
+    assert_eq!(f.to_vec(), vec![UgoiraFormat::Gif, UgoiraFormat::Webp]);
+    assert_eq!(
+        UgoiraFormats::from_str("mp4").unwrap(),
+        UgoiraFormats::default()
+    );
+    assert_eq!(UgoiraFormat::Apng.extension(), "png");
+    assert!(UgoiraFormats::from_str("mkv").is_err());
+    assert!(UgoiraFormats::from_str(" ,").is_err());
+    assert!(check_ugoira_formats(&JsonValue::from("apng,mp4")));
+    assert!(!check_ugoira_formats(&JsonValue::from(1)));
+}
+
+#[test]
+fn test_gif_dither() {
+    assert_eq!(
+        GifDither::from_str("Floyd_Steinberg").unwrap(),
+        GifDither::FloydSteinberg
+    );
+    assert_eq!(GifDither::default().as_str(), "sierra2_4a");
+    assert!(GifDither::from_str("random").is_err());
+    assert!(check_gif_dither(&JsonValue::from("none")));
+    assert!(!check_gif_dither(&JsonValue::from(1)));
+}
@@ -0,0 +1,148 @@
+use crate::gettext;
+use std::str::FromStr;
+
+/// The type of an artwork
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixivWorkType {
+    Illust,
+    Manga,
+    Ugoira,
+}
+
+impl PixivWorkType {
+    /// Convert from `illustType` in web API.
+    pub fn from_illust_type(t: i64) -> Option<Self> {
+        match t {
+            0 => Some(Self::Illust),
+            1 => Some(Self::Manga),
+            2 => Some(Self::Ugoira),
+            _ => None,
+        }
+    }
+
+    /// Returns the name used by app API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Illust => "illust",
+            Self::Manga => "manga",
+            Self::Ugoira => "ugoira",
+        }
+    }
+}
+
+impl FromStr for PixivWorkType {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "illust" | "illusts" | "illustration" | "illustrations" => Ok(Self::Illust),
+            "manga" => Ok(Self::Manga),
+            "ugoira" => Ok(Self::Ugoira),
+            _ => Err(gettext("Unknown work type.")),
+        }
+    }
+}
+
+/// A set of [PixivWorkType]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixivWorkTypes {
+    illust: bool,
+    manga: bool,
+    ugoira: bool,
+}
+
+impl PixivWorkTypes {
+    /// Contains all types.
+    pub fn all() -> Self {
+        Self {
+            illust: true,
+            manga: true,
+            ugoira: true,
+        }
+    }
+
+    /// Contains illusts and ugoira, the same as `illustrations` page of the user.
+    pub fn illustrations() -> Self {
+        Self {
+            illust: true,
+            manga: false,
+            ugoira: true,
+        }
+    }
+
+    /// Contains manga only.
+    pub fn manga() -> Self {
+        Self {
+            illust: false,
+            manga: true,
+            ugoira: false,
+        }
+    }
+
+    pub fn contains(&self, t: PixivWorkType) -> bool {
+        match t {
+            PixivWorkType::Illust => self.illust,
+            PixivWorkType::Manga => self.manga,
+            PixivWorkType::Ugoira => self.ugoira,
+        }
+    }
+
+    /// Returns the types which are contained in both sets.
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self {
+            illust: self.illust && other.illust,
+            manga: self.manga && other.manga,
+            ugoira: self.ugoira && other.ugoira,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.illust && !self.manga && !self.ugoira
+    }
+}
+
+impl Default for PixivWorkTypes {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromStr for PixivWorkTypes {
+    type Err = &'static str;
+    /// Parse a comma separated list of types.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut re = Self {
+            illust: false,
+            manga: false,
+            ugoira: false,
+        };
+        for t in s.split(',') {
+            if t.trim().is_empty() {
+                continue;
+            }
+            match PixivWorkType::from_str(t)? {
+                PixivWorkType::Illust => re.illust = true,
+                PixivWorkType::Manga => re.manga = true,
+                PixivWorkType::Ugoira => re.ugoira = true,
+            }
+        }
+        if re.is_empty() {
+            return Err(gettext("No work type specified."));
+        }
+        Ok(re)
+    }
+}
+
+#[test]
+fn test_pixiv_work_types() {
+    let t = PixivWorkTypes::from_str("illust, Ugoira").unwrap();
+    assert_eq!(t, PixivWorkTypes::illustrations());
+    assert!(!t.contains(PixivWorkType::Manga));
+    assert!(t.intersect(&PixivWorkTypes::manga()).is_empty());
+    assert_eq!(
+        PixivWorkTypes::from_str("manga").unwrap(),
+        PixivWorkTypes::manga()
+    );
+    assert!(PixivWorkTypes::from_str("novel").is_err());
+    assert!(PixivWorkTypes::from_str("").is_err());
+}
@@ -1,3 +1,4 @@
+use crate::bandwidth::BandwidthLimitType;
 #[cfg(feature = "db")]
 use crate::db::PixivDownloaderDbConfig;
 use crate::ext::json::FromJson;
@@ -6,12 +7,26 @@ use crate::ext::rw_lock::GetRwLock;
 use crate::ext::use_or_not::ToBool;
 use crate::ext::use_or_not::UseOrNot;
 use crate::list::NonTailList;
+use crate::opt::artwork_filter::{parse_tag_list_json, PixivAgeFilter, PixivArtworkFilter};
 use crate::opt::author_name_filter::AuthorNameFilter;
+use crate::opt::date::parse_date_time;
 use crate::opt::header_map::HeaderMap;
+use crate::opt::image_size::PixivImageSize;
+#[cfg(feature = "exif")]
+use crate::opt::metadata_family::MetadataFamilies;
+use crate::opt::page_range::PageRange;
+use crate::opt::path_template::{PathSanitizeMode, PathTemplate};
 use crate::opt::proxy::ProxyChain;
-use crate::opt::size::parse_u32_size;
+use crate::opt::ranking::PixivRankingContent;
+use crate::opt::rate_limit::HostRateLimits;
+use crate::opt::search::{PixivSearchMatch, PixivSearchMode, PixivSearchSort};
+use crate::opt::size::{parse_size, parse_u32_size};
+use crate::opt::time_range::TimeRanges;
+use crate::opt::ugoira_format::{GifDither, UgoiraFormats};
 use crate::opt::use_progress_bar::UseProgressBar;
+use crate::opt::work_type::PixivWorkTypes;
 use crate::opts::CommandOpts;
+use crate::pixiv_app::PixivRestrictType;
 use crate::retry_interval::parse_retry_interval_from_json;
 #[cfg(feature = "server")]
 use crate::server::cors::parse_cors_entries;
@@ -19,6 +34,7 @@ use crate::server::cors::parse_cors_entries;
 use crate::server::cors::CorsEntry;
 use crate::settings::SettingStore;
 use crate::ugoira::X264Profile;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
 use is_terminal::IsTerminal;
 #[cfg(feature = "server")]
 use std::net::IpAddr;
@@ -49,6 +65,8 @@ pub struct OptHelper {
     #[cfg(feature = "server")]
     _cors_entries: RwLock<Vec<CorsEntry>>,
     _fanbox_http_headers: RwLock<HeaderMap>,
+    _bandwidth_schedule: RwLock<Option<TimeRanges>>,
+    _rate_limits: RwLock<HostRateLimits>,
 }
 
 impl OptHelper {
@@ -130,6 +148,38 @@ impl OptHelper {
         return String::from("./");
     }
 
+    /// Whether to put artworks into per-author folders.
+    pub fn artwork_group_by_author(&self) -> bool {
+        match self.opt.get_ref().artwork_group_by_author {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("artwork-group-by-author") {
+            return self
+                .settings
+                .get_ref()
+                .get_bool("artwork-group-by-author")
+                .unwrap();
+        }
+        false
+    }
+
+    /// The template of the path of downloaded artworks' files.
+    pub fn artwork_path_template(&self) -> Option<PathTemplate> {
+        match self.opt.get_ref().artwork_path_template.as_ref() {
+            Some(t) => {
+                return Some(t.clone());
+            }
+            None => {}
+        }
+        match self.settings.get_ref().get_str("artwork-path-template") {
+            Some(s) => PathTemplate::from_str(&s).ok(),
+            None => None,
+        }
+    }
+
     /// Whether to download multiple posts/artworks at the same time.
     pub fn download_multiple_posts(&self) -> bool {
         match self.opt.get_ref().download_multiple_posts {
@@ -354,6 +404,26 @@ impl OptHelper {
                 HeaderMap::from_json(&settings.get("fanbox-http-headers").unwrap()).unwrap(),
             );
         }
+        self._bandwidth_schedule
+            .replace_with2(if opt.bandwidth_schedule.is_some() {
+                opt.bandwidth_schedule.clone()
+            } else if settings.have_str("bandwidth-schedule") {
+                Some(
+                    TimeRanges::from_str(&settings.get_str("bandwidth-schedule").unwrap()).unwrap(),
+                )
+            } else {
+                None
+            });
+        let mut rate_limits = if settings.have("rate-limit") {
+            HostRateLimits::from_json(&settings.get("rate-limit").unwrap()).unwrap()
+        } else {
+            HostRateLimits::default()
+        };
+        match &opt.rate_limit {
+            Some(r) => rate_limits.extend(r),
+            None => {}
+        }
+        self._rate_limits.replace_with2(rate_limits);
         self.opt.replace_with2(opt);
         self.settings.replace_with2(settings);
     }
@@ -369,6 +439,30 @@ impl OptHelper {
         false
     }
 
+    /// How to sanitize the path of downloaded files.
+    pub fn path_sanitize(&self) -> PathSanitizeMode {
+        match self.opt.get_ref().path_sanitize {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        match self.settings.get_ref().get_str("path-sanitize") {
+            Some(s) => PathSanitizeMode::from_str(&s).unwrap_or_default(),
+            None => PathSanitizeMode::default(),
+        }
+    }
+
+    /// Return whether to print what would be downloaded without writing anything to disk.
+    pub fn dry_run(&self) -> bool {
+        self.opt.get_ref().dry_run
+    }
+
+    /// The location to write machine-readable events. `-` means stdout.
+    pub fn events(&self) -> Option<String> {
+        self.opt.get_ref().events.clone()
+    }
+
     pub fn overwrite(&self) -> Option<bool> {
         self.opt.get_ref().overwrite
     }
@@ -378,6 +472,81 @@ impl OptHelper {
         self._proxy_chain.get_ref().deref().clone()
     }
 
+    /// The types of works to download when downloading user's works.
+    pub fn user_work_types(&self) -> PixivWorkTypes {
+        self.opt.get_ref().user_work_types.unwrap_or_default()
+    }
+
+    /// The maximum number of the newest works to download when downloading user's works.
+    pub fn user_max_works(&self) -> Option<usize> {
+        self.opt.get_ref().user_max_works
+    }
+
+    /// Download public or private bookmarks. [None] if not specified.
+    pub fn bookmarks_restrict(&self) -> Option<PixivRestrictType> {
+        self.opt.get_ref().bookmarks_restrict
+    }
+
+    /// Only download bookmarks with this tag.
+    pub fn bookmarks_tag(&self) -> Option<String> {
+        self.opt.get_ref().bookmarks_tag.clone()
+    }
+
+    /// The maximum number of bookmarks to download.
+    pub fn bookmarks_max(&self) -> Option<usize> {
+        self.opt.get_ref().bookmarks_max
+    }
+
+    /// Stop at the first bookmark which is already downloaded.
+    pub fn bookmarks_stop_at_downloaded(&self) -> bool {
+        match self.opt.get_ref().bookmarks_stop_at_downloaded {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self
+            .settings
+            .get_ref()
+            .have_bool("bookmarks-stop-at-downloaded")
+        {
+            return self
+                .settings
+                .get_ref()
+                .get_bool("bookmarks-stop-at-downloaded")
+                .unwrap();
+        }
+        false
+    }
+
+    /// Only download fanbox posts published after this time.
+    pub fn fanbox_since(&self) -> Option<DateTime<FixedOffset>> {
+        self.opt.get_ref().fanbox_since
+    }
+
+    /// Skip the fanbox posts which are restricted.
+    pub fn fanbox_skip_restricted(&self) -> bool {
+        match self.opt.get_ref().fanbox_skip_restricted {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("fanbox-skip-restricted") {
+            return self
+                .settings
+                .get_ref()
+                .get_bool("fanbox-skip-restricted")
+                .unwrap();
+        }
+        true
+    }
+
+    /// Only download fanbox posts published before this time.
+    pub fn fanbox_until(&self) -> Option<DateTime<FixedOffset>> {
+        self.opt.get_ref().fanbox_until
+    }
+
     pub fn init_log(&self) {
         if self.opt.get_ref().verbose {
             crate::log_cfg::init_with_level(log::LevelFilter::Debug);
@@ -514,6 +683,21 @@ impl OptHelper {
         false
     }
 
+    #[cfg(feature = "exif")]
+    /// Return the families of metadata written to image files.
+    pub fn metadata_families(&self) -> MetadataFamilies {
+        match self.opt.get_ref().metadata_families {
+            Some(f) => {
+                return f;
+            }
+            None => {}
+        }
+        match self.settings.get_ref().get_str("metadata-families") {
+            Some(s) => MetadataFamilies::from_str(&s).unwrap_or_default(),
+            None => MetadataFamilies::default(),
+        }
+    }
+
     /// Return whether to use progress bar.
     pub fn use_progress_bar(&self) -> bool {
         if self._use_progress_bar.get_ref().is_some() {
@@ -567,6 +751,34 @@ impl OptHelper {
         60f32
     }
 
+    /// Return the output formats when converting ugoira(GIF).
+    pub fn ugoira_formats(&self) -> UgoiraFormats {
+        match self.opt.get_ref().ugoira_formats {
+            Some(f) => {
+                return f;
+            }
+            None => {}
+        }
+        match self.settings.get_ref().get_str("ugoira-formats") {
+            Some(s) => UgoiraFormats::from_str(&s).unwrap_or_default(),
+            None => UgoiraFormats::default(),
+        }
+    }
+
+    /// Return the dither algorithm used when converting ugoira(GIF) to GIF.
+    pub fn ugoira_gif_dither(&self) -> GifDither {
+        match self.opt.get_ref().ugoira_gif_dither {
+            Some(d) => {
+                return d;
+            }
+            None => {}
+        }
+        match self.settings.get_ref().get_str("ugoira-gif-dither") {
+            Some(s) => GifDither::from_str(&s).unwrap_or_default(),
+            None => GifDither::default(),
+        }
+    }
+
     /// The Constant Rate Factor when converting ugoira(GIF) to video.
     pub fn x264_crf(&self) -> Option<f32> {
         match self.opt.get_ref().x264_crf {
@@ -597,6 +809,185 @@ impl OptHelper {
         X264Profile::default()
     }
 
+    /// Download all posts when downloading a fanbox creator.
+    pub fn fanbox_creator_posts(&self) -> bool {
+        match self.opt.get_ref().fanbox_creator_posts {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("fanbox-creator-posts") {
+            return self
+                .settings
+                .get_ref()
+                .get_bool("fanbox-creator-posts")
+                .unwrap();
+        }
+        false
+    }
+
+    /// The maximum number of the newest fanbox posts to download.
+    pub fn fanbox_max_posts(&self) -> Option<usize> {
+        self.opt.get_ref().fanbox_max_posts
+    }
+
+    /// How keywords are matched when searching artworks. [None] if not specified.
+    pub fn search_match(&self) -> Option<PixivSearchMatch> {
+        self.opt.get_ref().search_match
+    }
+
+    /// Age restriction filter when searching artworks. [None] if not specified.
+    pub fn search_mode(&self) -> Option<PixivSearchMode> {
+        self.opt.get_ref().search_mode
+    }
+
+    /// The order of search results. [None] if not specified.
+    pub fn search_sort(&self) -> Option<PixivSearchSort> {
+        self.opt.get_ref().search_sort
+    }
+
+    /// The types of works to download when searching artworks. [None] if not specified.
+    pub fn search_types(&self) -> Option<PixivWorkTypes> {
+        self.opt.get_ref().search_types
+    }
+
+    /// Only search artworks uploaded after this date.
+    pub fn search_since(&self) -> Option<NaiveDate> {
+        self.opt.get_ref().search_since
+    }
+
+    /// Only search artworks uploaded before this date.
+    pub fn search_until(&self) -> Option<NaiveDate> {
+        self.opt.get_ref().search_until
+    }
+
+    /// The maximum number of search results to download.
+    pub fn search_max(&self) -> Option<usize> {
+        self.opt.get_ref().search_max
+    }
+
+    /// The content type of ranking. [None] if not specified.
+    pub fn ranking_content(&self) -> Option<PixivRankingContent> {
+        self.opt.get_ref().ranking_content
+    }
+
+    /// The date of ranking. [None] means the latest one.
+    pub fn ranking_date(&self) -> Option<NaiveDate> {
+        self.opt.get_ref().ranking_date
+    }
+
+    /// Download rankings from [Self::ranking_date] to this date day by day.
+    pub fn ranking_end_date(&self) -> Option<NaiveDate> {
+        self.opt.get_ref().ranking_end_date
+    }
+
+    /// The maximum number of artworks to download from each ranking.
+    pub fn ranking_max(&self) -> Option<usize> {
+        self.opt.get_ref().ranking_max
+    }
+
+    #[cfg(feature = "db")]
+    /// Skip the artworks which are already recorded in download history.
+    pub fn archive(&self) -> bool {
+        match self.opt.get_ref().archive {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("archive") {
+            return self.settings.get_ref().get_bool("archive").unwrap();
+        }
+        false
+    }
+
+    /// The rules to filter artworks before downloading.
+    pub fn artwork_filter(&self) -> PixivArtworkFilter {
+        let opt = self.opt.get_ref();
+        let settings = self.settings.get_ref();
+        let exclude_ai = match opt.exclude_ai {
+            Some(r) => r,
+            None => {
+                if settings.have_bool("exclude-ai") {
+                    settings.get_bool("exclude-ai").unwrap()
+                } else {
+                    false
+                }
+            }
+        };
+        let age = match opt.age_filter {
+            Some(r) => r,
+            None => match settings.get_str("age-filter") {
+                Some(s) => PixivAgeFilter::from_str(&s).unwrap(),
+                None => PixivAgeFilter::default(),
+            },
+        };
+        let include_tags = match &opt.include_tags {
+            Some(r) => r.clone(),
+            None => match settings.get("include-tags") {
+                Some(v) => parse_tag_list_json(&v).unwrap(),
+                None => Vec::new(),
+            },
+        };
+        let exclude_tags = match &opt.exclude_tags {
+            Some(r) => r.clone(),
+            None => match settings.get("exclude-tags") {
+                Some(v) => parse_tag_list_json(&v).unwrap(),
+                None => Vec::new(),
+            },
+        };
+        let min_bookmarks = match opt.min_bookmarks {
+            Some(r) => Some(r),
+            None => settings.get("min-bookmarks").map(|v| v.as_u64().unwrap()),
+        };
+        let since = match opt.upload_since {
+            Some(r) => Some(r),
+            None => settings
+                .get_str("upload-since")
+                .map(|s| parse_date_time(&s, false).unwrap()),
+        };
+        let until = match opt.upload_until {
+            Some(r) => Some(r),
+            None => settings
+                .get_str("upload-until")
+                .map(|s| parse_date_time(&s, true).unwrap()),
+        };
+        PixivArtworkFilter {
+            exclude_ai,
+            age,
+            include_tags,
+            exclude_tags,
+            min_bookmarks,
+            since,
+            until,
+        }
+    }
+
+    /// The pages to download in multi-page artworks. [None] if all pages should be downloaded.
+    pub fn page_range(&self) -> Option<PageRange> {
+        match &self.opt.get_ref().page_range {
+            Some(r) => return Some(r.clone()),
+            None => {}
+        }
+        match self.settings.get_ref().get_str("pages") {
+            Some(s) => Some(PageRange::from_str(&s).unwrap()),
+            None => None,
+        }
+    }
+
+    /// The size variant of the pictures to download.
+    pub fn image_size(&self) -> PixivImageSize {
+        match self.opt.get_ref().image_size {
+            Some(r) => return r,
+            None => {}
+        }
+        match self.settings.get_ref().get_str("image-size") {
+            Some(s) => PixivImageSize::from_str(&s).unwrap(),
+            None => PixivImageSize::default(),
+        }
+    }
+
     /// Use page number for pictures' file name in fanbox.
     pub fn fanbox_page_number(&self) -> bool {
         match self.opt.get_ref().fanbox_page_number {
@@ -740,6 +1131,124 @@ impl OptHelper {
         )
     }
 
+    /// The timeout when waiting for the next chunk of data in downloader.
+    pub fn download_chunk_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.opt
+                .get_ref()
+                .download_chunk_timeout
+                .or_else(|| self.settings.get_ref().get_u64("download-chunk-timeout"))
+                .unwrap_or(10_000),
+        )
+    }
+
+    /// Whether to verify the files after downloaded.
+    pub fn download_verify(&self) -> bool {
+        match self.opt.get_ref().download_verify {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("download-verify") {
+            return self.settings.get_ref().get_bool("download-verify").unwrap();
+        }
+        true
+    }
+
+    /// Whether to check the whole structure of the files when verifying.
+    pub fn download_verify_full(&self) -> bool {
+        match self.opt.get_ref().download_verify_full {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("download-verify-full") {
+            return self
+                .settings
+                .get_ref()
+                .get_bool("download-verify-full")
+                .unwrap();
+        }
+        false
+    }
+
+    /// Whether to write the SHA-256 hash of the downloaded files to `.sha256` files.
+    pub fn sha256_file(&self) -> bool {
+        match self.opt.get_ref().sha256_file {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("sha256-file") {
+            return self.settings.get_ref().get_bool("sha256-file").unwrap();
+        }
+        false
+    }
+
+    /// Whether to write the metadata of the downloaded files to `.xmp` sidecar files.
+    pub fn xmp_sidecar(&self) -> bool {
+        match self.opt.get_ref().xmp_sidecar {
+            Some(r) => {
+                return r;
+            }
+            None => {}
+        }
+        if self.settings.get_ref().have_bool("xmp-sidecar") {
+            return self.settings.get_ref().get_bool("xmp-sidecar").unwrap();
+        }
+        false
+    }
+
+    /// Return the minimum interval between two requests to the host.
+    /// * `host` - The host name
+    pub fn rate_limit(&self, host: &str) -> Option<Duration> {
+        self._rate_limits.get_ref().get(host)
+    }
+
+    /// Return the bandwidth limit in bytes per second at current time.
+    /// [None] if the bandwidth is unlimited.
+    /// * `typ` - The type of the traffic
+    pub fn bandwidth_limit(&self, typ: BandwidthLimitType) -> Option<u64> {
+        match self._bandwidth_schedule.get_ref().as_ref() {
+            Some(schedule) => {
+                if !schedule.contains(&Local::now().time()) {
+                    return None;
+                }
+            }
+            None => {}
+        }
+        let (limit, key) = match typ {
+            BandwidthLimitType::Download => (
+                self.opt.get_ref().download_bandwidth_limit,
+                "download-bandwidth-limit",
+            ),
+            #[cfg(feature = "server")]
+            BandwidthLimitType::Proxy => (
+                self.opt.get_ref().proxy_bandwidth_limit,
+                "proxy-bandwidth-limit",
+            ),
+            BandwidthLimitType::Telegram => (
+                self.opt.get_ref().telegram_bandwidth_limit,
+                "telegram-bandwidth-limit",
+            ),
+        };
+        let limit = match limit {
+            Some(r) => r,
+            None => match self.settings.get_ref().get(key) {
+                Some(r) => parse_size(&r).unwrap(),
+                None => 0,
+            },
+        };
+        if limit == 0 {
+            None
+        } else {
+            Some(limit)
+        }
+    }
+
     /// The path to ffprobe executable.
     pub fn ffprobe(&self) -> Option<String> {
         match &self.opt.get_ref().ffprobe {
@@ -785,6 +1294,8 @@ impl Default for OptHelper {
             #[cfg(feature = "server")]
             _cors_entries: RwLock::new(Vec::new()),
             _fanbox_http_headers: RwLock::new(HeaderMap::new()),
+            _bandwidth_schedule: RwLock::new(None),
+            _rate_limits: RwLock::new(HostRateLimits::default()),
         };
         s.init_log();
         s
@@ -1,11 +1,27 @@
 use crate::ext::use_or_not::UseOrNot;
 use crate::gettext;
 use crate::list::NonTailList;
-use crate::pixiv_link::PixivID;
+use crate::opt::artwork_filter::{parse_tag_list, PixivAgeFilter};
+use crate::opt::date::parse_date_time;
+use crate::opt::image_size::PixivImageSize;
+use crate::opt::input_list::load_input_lists;
+#[cfg(feature = "exif")]
+use crate::opt::metadata_family::MetadataFamilies;
+use crate::opt::page_range::PageRange;
+use crate::opt::path_template::{PathSanitizeMode, PathTemplate};
+use crate::opt::ranking::{PixivRankingContent, PixivRankingMode};
+use crate::opt::rate_limit::HostRateLimits;
+use crate::opt::search::{PixivSearchMatch, PixivSearchMode, PixivSearchSort};
+use crate::opt::time_range::TimeRanges;
+use crate::opt::ugoira_format::{GifDither, UgoiraFormats};
+use crate::opt::work_type::PixivWorkTypes;
+use crate::pixiv_app::PixivRestrictType;
+use crate::pixiv_link::{PixivID, PixivRankingID, PixivSearchID};
 use crate::retry_interval::parse_retry_interval_from_str;
 use crate::ugoira::X264Profile;
 use crate::utils::check_file_exists;
 use crate::utils::get_exe_path_else_current;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use getopts::HasArg;
 use getopts::Options;
 use std::env;
@@ -18,6 +34,7 @@ use std::num::TryFromIntError;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
+use url::Url;
 
 /// Command Line command
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -31,6 +48,9 @@ pub enum Command {
     #[cfg(feature = "server")]
     /// Run as a server
     Server,
+    #[cfg(feature = "db")]
+    /// Verify the downloaded files in download history
+    Verify,
     /// Already handled when parsing options, just need return 0.
     None,
 }
@@ -73,11 +93,20 @@ pub struct CommandOpts {
     pub retry: Option<i64>,
     /// Retry interval
     pub retry_interval: Option<NonTailList<Duration>>,
+    /// The minimum intervals between two requests to the same host
+    pub rate_limit: Option<HostRateLimits>,
     /// Use data from webpage first
     pub use_webpage: bool,
+    /// Resolve everything but do not write anything to disk
+    pub dry_run: bool,
+    /// The location to write machine-readable events
+    pub events: Option<String>,
     #[cfg(feature = "exif")]
     /// Add/Update exif information to image files even when overwrite are disabled
     pub update_exif: bool,
+    #[cfg(feature = "exif")]
+    /// The families of metadata written to image files
+    pub metadata_families: Option<MetadataFamilies>,
     /// Whether to enable progress bar
     pub use_progress_bar: Option<UseOrNot>,
     /// Whether to download multiple files at the same time
@@ -115,6 +144,10 @@ pub struct CommandOpts {
     /// The Constant Rate Factor when converting ugoira(GIF) to video.
     pub x264_crf: Option<f32>,
     pub ugoira_max_fps: Option<f32>,
+    /// The output formats when converting ugoira(GIF)
+    pub ugoira_formats: Option<UgoiraFormats>,
+    /// The dither algorithm used when converting ugoira(GIF) to GIF.
+    pub ugoira_gif_dither: Option<GifDither>,
     pub fanbox_page_number: Option<bool>,
     /// Pixiv's refresh token. Used to login.
     pub refresh_token: Option<String>,
@@ -144,6 +177,25 @@ pub struct CommandOpts {
     /// The timeout is applied from when the request starts connecting until the response body
     /// has finished. Not used for downloader.
     pub client_timeout: Option<u64>,
+    /// The timeout in milliseconds when waiting for the next chunk of data in downloader.
+    pub download_chunk_timeout: Option<u64>,
+    /// Whether to verify the files after downloaded.
+    pub download_verify: Option<bool>,
+    /// Whether to check the whole structure of the files when verifying.
+    pub download_verify_full: Option<bool>,
+    /// Whether to write the SHA-256 hash of the downloaded files to `.sha256` files.
+    pub sha256_file: Option<bool>,
+    /// Whether to write the metadata of the downloaded files to `.xmp` sidecar files.
+    pub xmp_sidecar: Option<bool>,
+    /// The maximum download speed of all downloads in bytes per second.
+    pub download_bandwidth_limit: Option<u64>,
+    #[cfg(feature = "server")]
+    /// The maximum speed of the pixiv proxy in bytes per second.
+    pub proxy_bandwidth_limit: Option<u64>,
+    /// The maximum upload speed to Telegram in bytes per second.
+    pub telegram_bandwidth_limit: Option<u64>,
+    /// The time ranges in which the bandwidth limits are applied.
+    pub bandwidth_schedule: Option<TimeRanges>,
     /// The path to ffprobe executable.
     pub ffprobe: Option<String>,
     /// The path to ffmpeg executable.
@@ -152,6 +204,77 @@ pub struct CommandOpts {
     pub browser: Option<wreq_util::Emulation>,
     /// OS emulation
     pub os: Option<wreq_util::EmulationOS>,
+    /// The template of the path of downloaded artworks' files.
+    pub artwork_path_template: Option<PathTemplate>,
+    /// Whether to put artworks into per-author folders.
+    pub artwork_group_by_author: Option<bool>,
+    /// How to sanitize the path of downloaded files.
+    pub path_sanitize: Option<PathSanitizeMode>,
+    /// The types of works to download when downloading user's works.
+    pub user_work_types: Option<PixivWorkTypes>,
+    /// The maximum number of the newest works to download when downloading user's works.
+    pub user_max_works: Option<usize>,
+    /// Download public or private bookmarks.
+    pub bookmarks_restrict: Option<PixivRestrictType>,
+    /// Only download bookmarks with this tag.
+    pub bookmarks_tag: Option<String>,
+    /// The maximum number of bookmarks to download.
+    pub bookmarks_max: Option<usize>,
+    /// Stop at the first bookmark which is already downloaded.
+    pub bookmarks_stop_at_downloaded: Option<bool>,
+    /// Download all posts of the fanbox creator.
+    pub fanbox_creator_posts: Option<bool>,
+    /// Skip the fanbox posts which are restricted.
+    pub fanbox_skip_restricted: Option<bool>,
+    /// Only download fanbox posts published after this time.
+    pub fanbox_since: Option<DateTime<FixedOffset>>,
+    /// Only download fanbox posts published before this time.
+    pub fanbox_until: Option<DateTime<FixedOffset>>,
+    /// The maximum number of the newest fanbox posts to download.
+    pub fanbox_max_posts: Option<usize>,
+    /// How keywords are matched when searching artworks.
+    pub search_match: Option<PixivSearchMatch>,
+    /// Age restriction filter when searching artworks.
+    pub search_mode: Option<PixivSearchMode>,
+    /// The order of search results.
+    pub search_sort: Option<PixivSearchSort>,
+    /// The types of works to download when searching artworks.
+    pub search_types: Option<PixivWorkTypes>,
+    /// Only search artworks uploaded after this date.
+    pub search_since: Option<NaiveDate>,
+    /// Only search artworks uploaded before this date.
+    pub search_until: Option<NaiveDate>,
+    /// The maximum number of search results to download.
+    pub search_max: Option<usize>,
+    /// The content type of ranking.
+    pub ranking_content: Option<PixivRankingContent>,
+    /// The date of ranking.
+    pub ranking_date: Option<NaiveDate>,
+    /// Download rankings from `ranking_date` to this date day by day.
+    pub ranking_end_date: Option<NaiveDate>,
+    /// The maximum number of artworks to download from each ranking.
+    pub ranking_max: Option<usize>,
+    #[cfg(feature = "db")]
+    /// Skip the artworks which are already recorded in download history.
+    pub archive: Option<bool>,
+    /// Skip AI-generated artworks.
+    pub exclude_ai: Option<bool>,
+    /// Filter artworks by age restriction.
+    pub age_filter: Option<PixivAgeFilter>,
+    /// Only download the artworks which have at least one of these tags.
+    pub include_tags: Option<Vec<String>>,
+    /// Skip the artworks which have any of these tags.
+    pub exclude_tags: Option<Vec<String>>,
+    /// The minimum count of bookmarks.
+    pub min_bookmarks: Option<u64>,
+    /// Only download the artworks uploaded after this time.
+    pub upload_since: Option<DateTime<FixedOffset>>,
+    /// Only download the artworks uploaded before this time.
+    pub upload_until: Option<DateTime<FixedOffset>>,
+    /// The pages to download in multi-page artworks.
+    pub page_range: Option<PageRange>,
+    /// The size variant of the pictures to download.
+    pub image_size: Option<PixivImageSize>,
 }
 
 impl CommandOpts {
@@ -167,9 +290,14 @@ impl CommandOpts {
             overwrite: None,
             retry: None,
             retry_interval: None,
+            rate_limit: None,
             use_webpage: false,
+            dry_run: false,
+            events: None,
             #[cfg(feature = "exif")]
             update_exif: false,
+            #[cfg(feature = "exif")]
+            metadata_families: None,
             use_progress_bar: None,
             download_multiple_files: None,
             download_retry: None,
@@ -190,6 +318,8 @@ impl CommandOpts {
             urls: None,
             x264_crf: None,
             ugoira_max_fps: None,
+            ugoira_formats: None,
+            ugoira_gif_dither: None,
             fanbox_page_number: None,
             refresh_token: None,
             use_app_api: None,
@@ -206,10 +336,56 @@ impl CommandOpts {
             ugoira_cli: None,
             connect_timeout: None,
             client_timeout: None,
+            download_chunk_timeout: None,
+            download_verify: None,
+            download_verify_full: None,
+            sha256_file: None,
+            xmp_sidecar: None,
+            download_bandwidth_limit: None,
+            #[cfg(feature = "server")]
+            proxy_bandwidth_limit: None,
+            telegram_bandwidth_limit: None,
+            bandwidth_schedule: None,
             ffprobe: None,
             ffmpeg: None,
             browser: None,
             os: None,
+            artwork_path_template: None,
+            artwork_group_by_author: None,
+            path_sanitize: None,
+            user_work_types: None,
+            user_max_works: None,
+            bookmarks_restrict: None,
+            bookmarks_tag: None,
+            bookmarks_max: None,
+            bookmarks_stop_at_downloaded: None,
+            fanbox_creator_posts: None,
+            fanbox_skip_restricted: None,
+            fanbox_since: None,
+            fanbox_until: None,
+            fanbox_max_posts: None,
+            search_match: None,
+            search_mode: None,
+            search_sort: None,
+            search_types: None,
+            search_since: None,
+            search_until: None,
+            search_max: None,
+            ranking_content: None,
+            ranking_date: None,
+            ranking_end_date: None,
+            ranking_max: None,
+            #[cfg(feature = "db")]
+            archive: None,
+            exclude_ai: None,
+            age_filter: None,
+            include_tags: None,
+            exclude_tags: None,
+            min_bookmarks: None,
+            upload_since: None,
+            upload_until: None,
+            page_range: None,
+            image_size: None,
         }
     }
 
@@ -228,6 +404,10 @@ impl CommandOpts {
         if cmd == "download-file" || cmd == "df" {
             return Some(CommandOpts::new(Command::DownloadFile));
         }
+        #[cfg(feature = "db")]
+        if cmd == "verify" {
+            return Some(CommandOpts::new(Command::Verify));
+        }
         None
     }
 
@@ -290,6 +470,15 @@ pub fn print_usage(prog: &str, opts: &Options) {
         )
         .as_str();
     }
+    #[cfg(feature = "db")]
+    {
+        brief += format!(
+            "\n{} verify [options]  {}",
+            prog,
+            gettext("Verify downloaded files and download broken files again")
+        )
+        .as_str();
+    }
     log::error!("{}", opts.usage(brief.as_str()));
 }
 
@@ -363,6 +552,14 @@ pub fn parse_u32_size<T: AsRef<str>>(s: Option<T>) -> Result<Option<u32>, ParseS
     }
 }
 
+/// Prase size as [u64] from string
+pub fn parse_u64_size<T: AsRef<str>>(s: Option<T>) -> Result<Option<u64>, ParseSizeError> {
+    match s {
+        Some(s) => Ok(Some(parse_size::parse_size(s.as_ref())?)),
+        None => Ok(None),
+    }
+}
+
 /// Prase [u64] from string
 pub fn parse_u64<T: AsRef<str>>(s: Option<T>) -> Result<Option<u64>, ParseIntError> {
     match s {
@@ -453,6 +650,18 @@ pub fn parse_cmd() -> Option<CommandOpts> {
         gettext("The location of cookies file. Used for web API."),
         "FILE",
     );
+    opts.optmulti(
+        "i",
+        "input-list",
+        gettext("Read IDs or URLs from file, one per line. Use - to read from stdin. Blank lines and lines starting with # are ignored."),
+        "FILE",
+    );
+    opts.optopt(
+        "",
+        "events",
+        gettext("Write machine-readable events to file, one JSON object per line. Use - to write to stdout, and logs and dry run output will be written to stderr."),
+        "FILE",
+    );
     opts.optopt(
         "l",
         "language",
@@ -474,13 +683,33 @@ pub fn parse_cmd() -> Option<CommandOpts> {
         gettext("The interval (in seconds) between two retries."),
         "LIST",
     );
+    opts.optmulti(
+        "",
+        "rate-limit",
+        gettext("The minimum interval (in milliseconds) between two requests to the host."),
+        "HOST=INTERVAL",
+    );
     opts.optflag("", "use-webpage", gettext("Use data from webpage first."));
+    opts.optflag(
+        "",
+        "dry-run",
+        gettext(
+            "Resolve links and print what would be downloaded without writing anything to disk.",
+        ),
+    );
     #[cfg(feature = "exif")]
     opts.optflag(
         "",
         "update-exif",
         gettext("Add/Update exif information to image files even when overwrite are disabled."),
     );
+    #[cfg(feature = "exif")]
+    opts.optopt(
+        "",
+        "metadata-families",
+        gettext("The families of metadata written to image files. Available values: exif, xmp, iptc. Separated by commas."),
+        "LIST",
+    );
     opts.optopt(
         "",
         "use-progress-bar",
@@ -644,6 +873,30 @@ pub fn parse_cmd() -> Option<CommandOpts> {
         HasArg::Maybe,
         getopts::Occur::Optional,
     );
+    opts.optopt(
+        "",
+        "ugoira-formats",
+        format!(
+            "{} ({} {})",
+            gettext("The output formats when converting ugoira(GIF). Available values: mp4, gif, webp, apng. Separated by commas."),
+            gettext("Default:"),
+            "mp4"
+        )
+        .as_str(),
+        "LIST",
+    );
+    opts.optopt(
+        "",
+        "ugoira-gif-dither",
+        format!(
+            "{} ({} {})",
+            gettext("The dither algorithm used when converting ugoira(GIF) to GIF. Available values: bayer, heckbert, floyd_steinberg, sierra2, sierra2_4a, sierra3, burkes, atkinson, none."),
+            gettext("Default:"),
+            "sierra2_4a"
+        )
+        .as_str(),
+        "DITHER",
+    );
     opts.opt(
         "",
         "fanbox-page-number",
@@ -749,6 +1002,93 @@ pub fn parse_cmd() -> Option<CommandOpts> {
         "TIME",
     );
     opts.optopt("", "client-timeout", gettext("Set request timeout in milliseconds. The timeout is applied from when the request starts connecting until the response body has finished. Not used for downloader."), "TIME");
+    opts.optopt(
+        "",
+        "download-chunk-timeout",
+        gettext("Set the timeout in milliseconds when waiting for the next chunk of data in downloader."),
+        "TIME",
+    );
+    opts.opt(
+        "",
+        "download-verify",
+        format!(
+            "{} ({} {})",
+            gettext("Verify the files after downloaded by checking the size and the file type."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.opt(
+        "",
+        "download-verify-full",
+        format!(
+            "{} ({} {})",
+            gettext("Check the whole structure of JPEG, PNG, GIF, WebP and ZIP files when verifying to find truncated files."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.opt(
+        "",
+        "sha256-file",
+        format!(
+            "{} ({} {})",
+            gettext("Write the SHA-256 hash of downloaded files to .sha256 files."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.opt(
+        "",
+        "xmp-sidecar",
+        format!(
+            "{} ({} {})",
+            gettext("Write the metadata of downloaded files to .xmp sidecar files."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.optopt(
+        "",
+        "download-bandwidth-limit",
+        gettext("The maximum download speed of all downloads per second. 0 means unlimited."),
+        "SIZE",
+    );
+    #[cfg(feature = "server")]
+    opts.optopt(
+        "",
+        "proxy-bandwidth-limit",
+        gettext("The maximum speed of the pixiv proxy per second. 0 means unlimited."),
+        "SIZE",
+    );
+    opts.optopt(
+        "",
+        "telegram-bandwidth-limit",
+        gettext("The maximum upload speed to Telegram per second. 0 means unlimited."),
+        "SIZE",
+    );
+    opts.optopt(
+        "",
+        "bandwidth-schedule",
+        gettext("Only apply the bandwidth limits in these time ranges. For example: 08:00-12:00,22:00-06:00"),
+        "RANGES",
+    );
     opts.optopt(
         "",
         "ffprobe",
@@ -763,516 +1103,1556 @@ pub fn parse_cmd() -> Option<CommandOpts> {
     );
     opts.optopt("", "browser", gettext("The browser emulation"), "BROWSER");
     opts.optopt("", "os", gettext("The OS emulation"), "OS");
-    let result = match opts.parse(&argv[1..]) {
-        Ok(m) => m,
-        Err(err) => {
-            log::error!("{}", err.to_string());
-            return None;
-        }
-    };
-    if result.opt_present("h") || result.free.len() == 0 {
-        print_usage(&argv[0], &opts);
-        return Some(CommandOpts::new(Command::None));
-    }
-    let mut re = CommandOpts::new_with_command(&result.free[0]);
-    if re.is_none() {
-        log::error!("{}", gettext("Unknown command."));
-        print_usage(&argv[0], &opts);
-        return None;
-    }
-    match re.as_ref().unwrap().cmd {
-        Command::Download => {
-            let mut ids = Vec::new();
-            for url in result.free.iter().skip(1) {
-                let id = PixivID::parse(url);
-                if id.is_none() {
-                    log::error!("{} {}", gettext("Failed to parse ID:"), url);
-                    return None;
-                }
-                ids.push(id.unwrap());
-            }
-            if ids.is_empty() {
-                log::error!("{}", gettext("No URL or ID specified."));
-                print_usage(&argv[0], &opts);
-                return None;
-            }
-            re.as_mut().unwrap().ids = ids;
-        }
-        Command::Config => {
-            if result.free.len() < 2 {
-                log::error!("{}", gettext("No detailed command specified."));
-                print_usage(&argv[0], &opts);
-                return None;
-            }
-            let subcmd = &result.free[1];
-            re.as_mut().unwrap().config_cmd = if subcmd == "fix" {
-                Some(ConfigCommand::Fix)
-            } else if subcmd == "help" {
-                Some(ConfigCommand::Help)
-            } else {
-                None
-            };
-            if re.as_ref().unwrap().config_cmd.is_none() {
-                log::error!("{}", gettext("Unknown config subcommand."));
-                print_usage(&argv[0], &opts);
-                return None;
-            }
-        }
-        #[cfg(feature = "server")]
-        Command::Server => {
-            if result.free.len() >= 2 {
-                let address = &result.free[1];
-                match SocketAddr::from_str(address) {
-                    Ok(address) => re.as_mut().unwrap().server = Some(address),
-                    Err(e) => {
-                        log::error!("{} {}", gettext("Failed to parse address:"), e);
-                        return None;
-                    }
-                }
-            }
-        }
-        Command::DownloadFile => {
-            let mut urls = Vec::new();
-            for url in result.free.iter().skip(1) {
-                urls.push(url.to_owned());
-            }
-            if urls.is_empty() {
-                log::error!("{}", gettext("No URL specified."));
-                print_usage(&argv[0], &opts);
-                return None;
-            }
-            re.as_mut().unwrap().urls.replace(urls);
-        }
-        Command::None => {}
-    }
-    if result.opt_present("config") {
-        re.as_mut().unwrap()._config = Some(result.opt_str("config").unwrap());
-    }
-    if result.opt_present("cookies") {
-        re.as_mut().unwrap().cookies = Some(result.opt_str("cookies").unwrap());
-    }
-    if result.opt_present("language") {
-        re.as_mut().unwrap().language = Some(result.opt_str("language").unwrap());
-    }
-    re.as_mut().unwrap().verbose = result.opt_present("verbose");
-    let yes = result.opt_present("yes");
-    let no = result.opt_present("no");
-    re.as_mut().unwrap().overwrite = if yes && no {
-        if result.opt_positions("yes").last().unwrap() > result.opt_positions("no").last().unwrap()
-        {
-            Some(true)
-        } else {
-            Some(false)
-        }
-    } else if yes {
-        Some(true)
-    } else if no {
-        Some(false)
-    } else {
-        None
-    };
-    match parse_i64(result.opt_str("retry")) {
-        Ok(r) => {
-            re.as_mut().unwrap().retry = r;
-        }
-        Err(e) => {
-            log::error!("{} {}", gettext("Failed to parse retry count:"), e);
-            return None;
-        }
-    }
-    if result.opt_present("retry-interval") {
-        let s = result.opt_str("retry-interval").unwrap();
-        let r = parse_retry_interval_from_str(s.as_str());
-        if r.is_err() {
-            log::error!(
-                "{} {}",
-                gettext("Failed to parse retry interval:"),
-                r.unwrap_err()
-            );
-            return None;
-        }
-        re.as_mut().unwrap().retry_interval = Some(r.unwrap());
-    }
-    re.as_mut().unwrap().use_webpage = result.opt_present("use-webpage");
-    #[cfg(feature = "exif")]
-    {
-        re.as_mut().unwrap().update_exif = result.opt_present("update-exif");
-    }
-    if result.opt_present("use-progress-bar") {
-        let s = result.opt_str("use-progress-bar").unwrap();
-        let r = UseOrNot::from_str(s.as_str());
+    opts.optopt(
+        "",
+        "artwork-path-template",
+        gettext("The template of the path of downloaded artworks' files. Relative to the download base."),
+        "TEMPLATE",
+    );
+    opts.opt(
+        "",
+        "artwork-group-by-author",
+        format!(
+            "{} ({} {})",
+            gettext("Put artworks into per-author folders."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.optopt(
+        "",
+        "path-sanitize",
+        gettext("How to sanitize the path of downloaded files."),
+        "auto/posix/windows",
+    );
+    opts.optopt(
+        "",
+        "user-work-types",
+        gettext("The types of works to download when downloading user's works. Comma separated list of illust, manga and ugoira."),
+        "TYPES",
+    );
+    opts.optopt(
+        "",
+        "user-max-works",
+        gettext("Only download the newest works when downloading user's works."),
+        "COUNT",
+    );
+    opts.optopt(
+        "",
+        "bookmarks-restrict",
+        gettext("Download public or private bookmarks, or both."),
+        "public/private/all",
+    );
+    opts.optopt(
+        "",
+        "bookmarks-tag",
+        gettext("Only download bookmarks with this tag."),
+        "TAG",
+    );
+    opts.optopt(
+        "",
+        "bookmarks-max",
+        gettext("Stop after downloading this number of bookmarks."),
+        "COUNT",
+    );
+    opts.opt(
+        "",
+        "bookmarks-stop-at-downloaded",
+        format!(
+            "{} ({} {})",
+            gettext("Stop at the first bookmark which is already downloaded."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.opt(
+        "",
+        "fanbox-creator-posts",
+        format!(
+            "{} ({} {})",
+            gettext("Download all posts when downloading a fanbox creator."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.opt(
+        "",
+        "fanbox-skip-restricted",
+        format!(
+            "{} ({} {})",
+            gettext("Skip the fanbox posts which are restricted. If disabled, the data of restricted posts will be saved."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.optopt(
+        "",
+        "fanbox-since",
+        gettext(
+            "Only download fanbox posts published after this time. Format: YYYY-MM-DD or RFC 3339.",
+        ),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "fanbox-until",
+        gettext("Only download fanbox posts published before this time. Format: YYYY-MM-DD or RFC 3339."),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "fanbox-max-posts",
+        gettext("Only download the newest fanbox posts."),
+        "COUNT",
+    );
+    opts.optmulti(
+        "",
+        "search",
+        gettext("Search artworks with keywords and download them."),
+        "KEYWORDS",
+    );
+    opts.optopt(
+        "",
+        "search-match",
+        gettext("How keywords are matched when searching artworks."),
+        "exact/partial/title",
+    );
+    opts.optopt(
+        "",
+        "search-mode",
+        gettext("Only search all-ages or R-18 artworks, or both."),
+        "safe/r18/all",
+    );
+    opts.optopt(
+        "",
+        "search-sort",
+        gettext("The order of search results. Popular order is only available for premium users."),
+        "newest/oldest/popular",
+    );
+    opts.optopt(
+        "",
+        "search-types",
+        gettext("The types of works to download when searching artworks. Comma separated list of illust, manga and ugoira."),
+        "TYPES",
+    );
+    opts.optopt(
+        "",
+        "search-since",
+        gettext("Only search artworks uploaded after this date. Format: YYYY-MM-DD."),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "search-until",
+        gettext("Only search artworks uploaded before this date. Format: YYYY-MM-DD."),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "search-max",
+        gettext("Stop after downloading this number of search results."),
+        "COUNT",
+    );
+    opts.optmulti(
+        "",
+        "ranking",
+        gettext("Download artworks from ranking. Modes: daily, weekly, monthly, rookie, original, daily_ai, male, female, daily_r18, weekly_r18, male_r18, female_r18, r18g, daily_r18_ai."),
+        "MODE",
+    );
+    opts.optopt(
+        "",
+        "ranking-content",
+        gettext("The content type of ranking."),
+        "all/illust/manga/ugoira",
+    );
+    opts.optopt(
+        "",
+        "ranking-date",
+        gettext("The date of ranking. Format: YYYY-MM-DD. The latest ranking is used by default."),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "ranking-end-date",
+        gettext("Download rankings from ranking date to this date day by day. Format: YYYY-MM-DD."),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "ranking-max",
+        gettext("Only download the top artworks of each ranking."),
+        "COUNT",
+    );
+    #[cfg(feature = "db")]
+    opts.opt(
+        "",
+        "archive",
+        format!(
+            "{} ({} {})",
+            gettext("Skip the artworks which are already recorded in download history before fetching their data."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.opt(
+        "",
+        "exclude-ai",
+        format!(
+            "{} ({} {})",
+            gettext("Skip AI-generated artworks."),
+            gettext("Default:"),
+            "yes"
+        )
+        .as_str(),
+        "yes/no",
+        HasArg::Maybe,
+        getopts::Occur::Optional,
+    );
+    opts.optopt(
+        "",
+        "age-filter",
+        gettext("Filter artworks by age restriction."),
+        "all/safe/r18",
+    );
+    opts.optmulti(
+        "",
+        "include-tags",
+        gettext("Only download the artworks which have at least one of these tags. Tags are separated by commas. Translated tags are also matched. Artworks with unknown tags are not filtered."),
+        "TAGS",
+    );
+    opts.optmulti(
+        "",
+        "exclude-tags",
+        gettext("Skip the artworks which have any of these tags. Tags are separated by commas. Translated tags are also matched. Artworks with unknown tags are not filtered."),
+        "TAGS",
+    );
+    opts.optopt(
+        "",
+        "min-bookmarks",
+        gettext("Skip the artworks which have fewer bookmarks than this. Artworks with unknown bookmark count are not filtered."),
+        "COUNT",
+    );
+    opts.optopt(
+        "",
+        "upload-since",
+        gettext("Only download artworks uploaded after this time. Format: YYYY-MM-DD or RFC 3339. Artworks with unknown upload date are not filtered."),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "upload-until",
+        gettext("Only download artworks uploaded before this time. Format: YYYY-MM-DD or RFC 3339. Artworks with unknown upload date are not filtered."),
+        "DATE",
+    );
+    opts.optopt(
+        "",
+        "pages",
+        gettext("The pages to download in multi-page artworks. Page numbers start from 1 and negative numbers are counted from the last page. For example: 1-3,7,-1"),
+        "RANGE",
+    );
+    opts.optopt(
+        "",
+        "image-size",
+        gettext("The size variant of the pictures to download."),
+        "original/regular/medium/square_medium",
+    );
+    let result = match opts.parse(&argv[1..]) {
+        Ok(m) => m,
+        Err(err) => {
+            log::error!("{}", err.to_string());
+            return None;
+        }
+    };
+    if result.opt_present("h") || result.free.len() == 0 {
+        print_usage(&argv[0], &opts);
+        return Some(CommandOpts::new(Command::None));
+    }
+    let mut re = CommandOpts::new_with_command(&result.free[0]);
+    if re.is_none() {
+        log::error!("{}", gettext("Unknown command."));
+        print_usage(&argv[0], &opts);
+        return None;
+    }
+    match re.as_ref().unwrap().cmd {
+        Command::Download => {
+            let mut ids = Vec::new();
+            for url in result.free.iter().skip(1) {
+                let id = PixivID::parse(url);
+                if id.is_none() {
+                    log::error!("{} {}", gettext("Failed to parse ID:"), url);
+                    return None;
+                }
+                ids.push(id.unwrap());
+            }
+            match load_input_lists(&result.opt_strs("input-list"), PixivID::parse) {
+                Some(list) => ids.extend(list),
+                None => return None,
+            }
+            for keywords in result.opt_strs("search") {
+                ids.push(PixivID::Search(PixivSearchID::new(&keywords)));
+            }
+            for mode in result.opt_strs("ranking") {
+                match PixivRankingMode::from_str(&mode) {
+                    Ok(mode) => ids.push(PixivID::Ranking(PixivRankingID::new(Some(mode)))),
+                    Err(e) => {
+                        log::error!(
+                            "{} {}",
+                            gettext("Failed to parse <opt>:")
+                                .replace("<opt>", "ranking")
+                                .as_str(),
+                            e
+                        );
+                        return None;
+                    }
+                }
+            }
+            if ids.is_empty() {
+                log::error!("{}", gettext("No URL or ID specified."));
+                print_usage(&argv[0], &opts);
+                return None;
+            }
+            re.as_mut().unwrap().ids = ids;
+        }
+        Command::Config => {
+            if result.free.len() < 2 {
+                log::error!("{}", gettext("No detailed command specified."));
+                print_usage(&argv[0], &opts);
+                return None;
+            }
+            let subcmd = &result.free[1];
+            re.as_mut().unwrap().config_cmd = if subcmd == "fix" {
+                Some(ConfigCommand::Fix)
+            } else if subcmd == "help" {
+                Some(ConfigCommand::Help)
+            } else {
+                None
+            };
+            if re.as_ref().unwrap().config_cmd.is_none() {
+                log::error!("{}", gettext("Unknown config subcommand."));
+                print_usage(&argv[0], &opts);
+                return None;
+            }
+        }
+        #[cfg(feature = "server")]
+        Command::Server => {
+            if result.free.len() >= 2 {
+                let address = &result.free[1];
+                match SocketAddr::from_str(address) {
+                    Ok(address) => re.as_mut().unwrap().server = Some(address),
+                    Err(e) => {
+                        log::error!("{} {}", gettext("Failed to parse address:"), e);
+                        return None;
+                    }
+                }
+            }
+        }
+        #[cfg(feature = "db")]
+        Command::Verify => {}
+        Command::DownloadFile => {
+            let mut urls = Vec::new();
+            for url in result.free.iter().skip(1) {
+                urls.push(url.to_owned());
+            }
+            match load_input_lists(&result.opt_strs("input-list"), |s| {
+                Url::parse(s).ok().map(|_| s.to_owned())
+            }) {
+                Some(list) => urls.extend(list),
+                None => return None,
+            }
+            if urls.is_empty() {
+                log::error!("{}", gettext("No URL specified."));
+                print_usage(&argv[0], &opts);
+                return None;
+            }
+            re.as_mut().unwrap().urls.replace(urls);
+        }
+        Command::None => {}
+    }
+    if result.opt_present("config") {
+        re.as_mut().unwrap()._config = Some(result.opt_str("config").unwrap());
+    }
+    if result.opt_present("cookies") {
+        re.as_mut().unwrap().cookies = Some(result.opt_str("cookies").unwrap());
+    }
+    if result.opt_present("language") {
+        re.as_mut().unwrap().language = Some(result.opt_str("language").unwrap());
+    }
+    re.as_mut().unwrap().verbose = result.opt_present("verbose");
+    let yes = result.opt_present("yes");
+    let no = result.opt_present("no");
+    re.as_mut().unwrap().overwrite = if yes && no {
+        if result.opt_positions("yes").last().unwrap() > result.opt_positions("no").last().unwrap()
+        {
+            Some(true)
+        } else {
+            Some(false)
+        }
+    } else if yes {
+        Some(true)
+    } else if no {
+        Some(false)
+    } else {
+        None
+    };
+    #[cfg(feature = "db")]
+    if re.as_ref().unwrap().cmd == Command::Verify && re.as_ref().unwrap().overwrite.is_none() {
+        // Broken files are removed before downloading again, so never ask for other files.
+        re.as_mut().unwrap().overwrite = Some(false);
+    }
+    match parse_i64(result.opt_str("retry")) {
+        Ok(r) => {
+            re.as_mut().unwrap().retry = r;
+        }
+        Err(e) => {
+            log::error!("{} {}", gettext("Failed to parse retry count:"), e);
+            return None;
+        }
+    }
+    if result.opt_present("retry-interval") {
+        let s = result.opt_str("retry-interval").unwrap();
+        let r = parse_retry_interval_from_str(s.as_str());
+        if r.is_err() {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse retry interval:"),
+                r.unwrap_err()
+            );
+            return None;
+        }
+        re.as_mut().unwrap().retry_interval = Some(r.unwrap());
+    }
+    for s in result.opt_strs("rate-limit") {
+        match HostRateLimits::from_str(&s) {
+            Ok(r) => {
+                re.as_mut()
+                    .unwrap()
+                    .rate_limit
+                    .get_or_insert_with(HostRateLimits::default)
+                    .extend(&r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "rate-limit")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        }
+    }
+    re.as_mut().unwrap().use_webpage = result.opt_present("use-webpage");
+    re.as_mut().unwrap().dry_run = result.opt_present("dry-run");
+    if result.opt_present("events") {
+        re.as_mut().unwrap().events = Some(result.opt_str("events").unwrap());
+    }
+    #[cfg(feature = "exif")]
+    {
+        re.as_mut().unwrap().update_exif = result.opt_present("update-exif");
+        match result.opt_str("metadata-families") {
+            Some(s) => match MetadataFamilies::from_str(&s) {
+                Ok(f) => {
+                    re.as_mut().unwrap().metadata_families.replace(f);
+                }
+                Err(e) => {
+                    log::error!(
+                        "{} {}",
+                        gettext("Failed to parse <opt>:")
+                            .replace("<opt>", "metadata-families")
+                            .as_str(),
+                        e
+                    );
+                    return None;
+                }
+            },
+            None => {}
+        }
+    }
+    if result.opt_present("use-progress-bar") {
+        let s = result.opt_str("use-progress-bar").unwrap();
+        let r = UseOrNot::from_str(s.as_str());
+        if r.is_err() {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "use-progress-bar")
+                    .as_str(),
+                r.unwrap_err()
+            );
+            return None;
+        }
+        re.as_mut().unwrap().use_progress_bar = Some(r.unwrap());
+    }
+    match parse_optional_opt(&result, "download-multiple-files", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().download_multiple_files = b,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "download-multiple-files")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    match parse_i64(result.opt_str("download-retry")) {
+        Ok(r) => {
+            re.as_mut().unwrap().download_retry = r;
+        }
+        Err(e) => {
+            log::error!("{} {}", gettext("Failed to parse retry count:"), e);
+            return None;
+        }
+    }
+    if result.opt_present("download-retry-interval") {
+        let s = result.opt_str("download-retry-interval").unwrap();
+        let r = parse_retry_interval_from_str(s.as_str());
         if r.is_err() {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse retry interval:"),
+                r.unwrap_err()
+            );
+            return None;
+        }
+        re.as_mut().unwrap().download_retry_interval = Some(r.unwrap());
+    }
+    match parse_i64(result.opt_str("download-part-retry")) {
+        Ok(r) => {
+            re.as_mut().unwrap().download_part_retry = r;
+        }
+        Err(e) => {
+            log::error!("{} {}", gettext("Failed to parse retry count:"), e);
+            return None;
+        }
+    }
+    match parse_optional_opt(&result, "multiple-threads-download", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().multiple_threads_download = b,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "multiple-threads-download")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    match parse_u64(result.opt_str("max-threads")) {
+        Ok(r) => {
+            re.as_mut().unwrap().max_threads = r;
+        }
+        Err(e) => {
+            log::error!("{} {}", gettext("Failed to parse max threads:"), e);
+            return None;
+        }
+    }
+    match parse_u32_size(result.opt_str("part-size")) {
+        Ok(r) => {
+            re.as_mut().unwrap().part_size = r;
+        }
+        Err(e) => {
+            log::error!("{} {}", gettext("Failed to parse part size:"), e);
+            return None;
+        }
+    }
+    match parse_optional_opt(&result, "max-download-tasks", 5, parse_nonempty_usize) {
+        Ok(r) => re.as_mut().unwrap().max_download_tasks = r,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:").replace("<opt>", "max-download-tasks"),
+                e
+            );
+            return None;
+        }
+    }
+    match parse_optional_opt(&result, "download-multiple-posts", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().download_multiple_posts = b,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "download-multiple-posts")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    match parse_optional_opt(&result, "max-download-post-tasks", 3, parse_nonempty_usize) {
+        Ok(r) => re.as_mut().unwrap().max_download_post_tasks = r,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "max-download-post-tasks")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    match parse_optional_opt(&result, "force-yuv420p", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().force_yuv420p = b,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "force-yuv420p")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    match parse_optional_opt(
+        &result,
+        "x264-profile",
+        X264Profile::default(),
+        parse_x264_profile,
+    ) {
+        Ok(r) => re.as_mut().unwrap().x264_profile = r,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "x264-profile")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    re.as_mut().unwrap().download_base = result.opt_str("download-base");
+    re.as_mut().unwrap().user_agent = result.opt_str("user-agent");
+    match parse_optional_opt(&result, "x264-crf", -1f32, parse_f32) {
+        Ok(r) => match r {
+            Some(crf) => {
+                if crf < -1f32 {
+                    log::error!("{}", gettext("x264-crf can not less than -1."));
+                    return None;
+                } else {
+                    re.as_mut().unwrap().x264_crf.replace(crf);
+                }
+            }
+            None => {}
+        },
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                ("Failed to parse <opt>:")
+                    .replace("<opt>", "x264-crf")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    match parse_optional_opt(&result, "ugoira-max-fps", 60f32, parse_f32) {
+        Ok(r) => match r {
+            Some(crf) => {
+                if crf <= 0f32 || crf > 1000f32 {
+                    log::error!(
+                        "{}",
+                        gettext("ugoira-max-fps can not less than 0 or greater than 1000.")
+                    );
+                    return None;
+                } else {
+                    re.as_mut().unwrap().ugoira_max_fps.replace(crf);
+                }
+            }
+            None => {}
+        },
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                ("Failed to parse <opt>:")
+                    .replace("<opt>", "ugoira-max-fps")
+                    .as_str(),
+                e
+            );
+            return None;
+        }
+    }
+    match result.opt_str("ugoira-formats") {
+        Some(s) => match UgoiraFormats::from_str(&s) {
+            Ok(f) => {
+                re.as_mut().unwrap().ugoira_formats.replace(f);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "ugoira-formats")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("ugoira-gif-dither") {
+        Some(s) => match GifDither::from_str(&s) {
+            Ok(d) => {
+                re.as_mut().unwrap().ugoira_gif_dither.replace(d);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "ugoira-gif-dither")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match parse_optional_opt(&result, "fanbox-page-number", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().fanbox_page_number = b,
+        Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "use-progress-bar")
+                    .replace("<opt>", "fanbox-page-number")
                     .as_str(),
-                r.unwrap_err()
+                e
             );
             return None;
         }
-        re.as_mut().unwrap().use_progress_bar = Some(r.unwrap());
     }
-    match parse_optional_opt(&result, "download-multiple-files", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().download_multiple_files = b,
+    re.as_mut().unwrap().refresh_token = result.opt_str("refresh-token");
+    match parse_optional_opt(&result, "use-app-api", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().use_app_api = b,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "download-multiple-files")
+                    .replace("<opt>", "use-app-api")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_i64(result.opt_str("download-retry")) {
-        Ok(r) => {
-            re.as_mut().unwrap().download_retry = r;
+    match parse_optional_opt(&result, "use-web-description", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().use_web_description = b,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "use-web-description")
+                    .as_str(),
+                e
+            );
+            return None;
         }
+    }
+    match parse_optional_opt(&result, "add-history", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().add_history = b,
         Err(e) => {
-            log::error!("{} {}", gettext("Failed to parse retry count:"), e);
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "add-history")
+                    .as_str(),
+                e
+            );
             return None;
         }
     }
-    if result.opt_present("download-retry-interval") {
-        let s = result.opt_str("download-retry-interval").unwrap();
-        let r = parse_retry_interval_from_str(s.as_str());
-        if r.is_err() {
+    #[cfg(feature = "server")]
+    match parse_nonempty_usize(result.opt_str("push-task-max-count")) {
+        Ok(r) => re.as_mut().unwrap().push_task_max_count = r,
+        Err(e) => {
             log::error!(
                 "{} {}",
-                gettext("Failed to parse retry interval:"),
-                r.unwrap_err()
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "push-task-max-count")
+                    .as_str(),
+                e
             );
             return None;
         }
-        re.as_mut().unwrap().download_retry_interval = Some(r.unwrap());
     }
-    match parse_i64(result.opt_str("download-part-retry")) {
-        Ok(r) => {
-            re.as_mut().unwrap().download_part_retry = r;
+    #[cfg(feature = "server")]
+    match parse_nonempty_usize(result.opt_str("push-task-max-push-count")) {
+        Ok(r) => re.as_mut().unwrap().push_task_max_push_count = r,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "push-task-max-push-count")
+                    .as_str(),
+                e
+            );
+            return None;
         }
+    }
+    #[cfg(feature = "server")]
+    {
+        re.as_mut().unwrap().disable_push_task = result.opt_present("disable-push-task");
+    }
+    re.as_mut().unwrap().ugoira = result.opt_str("ugoira");
+    #[cfg(feature = "ugoira")]
+    match parse_optional_opt(&result, "ugoira-cli", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().ugoira_cli = b,
         Err(e) => {
-            log::error!("{} {}", gettext("Failed to parse retry count:"), e);
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "ugoira-cli")
+                    .as_str(),
+                e
+            );
             return None;
         }
     }
-    match parse_optional_opt(&result, "multiple-threads-download", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().multiple_threads_download = b,
+    match parse_non_zero_u64(result.opt_str("connect-timeout")) {
+        Ok(r) => re.as_mut().unwrap().connect_timeout = r,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "multiple-threads-download")
+                    .replace("<opt>", "connect-timeout")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_u64(result.opt_str("max-threads")) {
-        Ok(r) => {
-            re.as_mut().unwrap().max_threads = r;
+    match parse_non_zero_u64(result.opt_str("client-timeout")) {
+        Ok(r) => re.as_mut().unwrap().client_timeout = r,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "client-timeout")
+                    .as_str(),
+                e
+            );
+            return None;
         }
+    }
+    match parse_non_zero_u64(result.opt_str("download-chunk-timeout")) {
+        Ok(r) => re.as_mut().unwrap().download_chunk_timeout = r,
         Err(e) => {
-            log::error!("{} {}", gettext("Failed to parse max threads:"), e);
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "download-chunk-timeout")
+                    .as_str(),
+                e
+            );
             return None;
         }
     }
-    match parse_u32_size(result.opt_str("part-size")) {
-        Ok(r) => {
-            re.as_mut().unwrap().part_size = r;
+    match parse_optional_opt(&result, "download-verify", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().download_verify = b,
+        Err(e) => {
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "download-verify")
+                    .as_str(),
+                e
+            );
+            return None;
         }
+    }
+    match parse_optional_opt(&result, "download-verify-full", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().download_verify_full = b,
         Err(e) => {
-            log::error!("{} {}", gettext("Failed to parse part size:"), e);
+            log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "download-verify-full")
+                    .as_str(),
+                e
+            );
             return None;
         }
     }
-    match parse_optional_opt(&result, "max-download-tasks", 5, parse_nonempty_usize) {
-        Ok(r) => re.as_mut().unwrap().max_download_tasks = r,
+    match parse_optional_opt(&result, "sha256-file", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().sha256_file = b,
         Err(e) => {
             log::error!(
                 "{} {}",
-                gettext("Failed to parse <opt>:").replace("<opt>", "max-download-tasks"),
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "sha256-file")
+                    .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(&result, "download-multiple-posts", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().download_multiple_posts = b,
+    match parse_optional_opt(&result, "xmp-sidecar", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().xmp_sidecar = b,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "download-multiple-posts")
+                    .replace("<opt>", "xmp-sidecar")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(&result, "max-download-post-tasks", 3, parse_nonempty_usize) {
-        Ok(r) => re.as_mut().unwrap().max_download_post_tasks = r,
+    match parse_u64_size(result.opt_str("download-bandwidth-limit")) {
+        Ok(r) => re.as_mut().unwrap().download_bandwidth_limit = r,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "max-download-post-tasks")
+                    .replace("<opt>", "download-bandwidth-limit")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(&result, "force-yuv420p", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().force_yuv420p = b,
+    #[cfg(feature = "server")]
+    match parse_u64_size(result.opt_str("proxy-bandwidth-limit")) {
+        Ok(r) => re.as_mut().unwrap().proxy_bandwidth_limit = r,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "force-yuv420p")
+                    .replace("<opt>", "proxy-bandwidth-limit")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(
-        &result,
-        "x264-profile",
-        X264Profile::default(),
-        parse_x264_profile,
-    ) {
-        Ok(r) => re.as_mut().unwrap().x264_profile = r,
+    match parse_u64_size(result.opt_str("telegram-bandwidth-limit")) {
+        Ok(r) => re.as_mut().unwrap().telegram_bandwidth_limit = r,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "x264-profile")
+                    .replace("<opt>", "telegram-bandwidth-limit")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    re.as_mut().unwrap().download_base = result.opt_str("download-base");
-    re.as_mut().unwrap().user_agent = result.opt_str("user-agent");
-    match parse_optional_opt(&result, "x264-crf", -1f32, parse_f32) {
-        Ok(r) => match r {
-            Some(crf) => {
-                if crf < -1f32 {
-                    log::error!("{}", gettext("x264-crf can not less than -1."));
-                    return None;
-                } else {
-                    re.as_mut().unwrap().x264_crf.replace(crf);
+    match result.opt_str("bandwidth-schedule") {
+        Some(s) => match TimeRanges::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().bandwidth_schedule.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "bandwidth-schedule")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    re.as_mut().unwrap().ffprobe = result.opt_str("ffprobe");
+    re.as_mut().unwrap().ffmpeg = result.opt_str("ffmpeg");
+    match result.opt_str("browser") {
+        Some(r) => {
+            match serde_json::from_str(&format!("\"{}\"", r)) {
+                Ok(r) => {
+                    re.as_mut().unwrap().browser = Some(r);
+                }
+                Err(e) => {
+                    log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "browser")
+                    .as_str(),
+                e
+            );
+            return None;
+                }
+            }
+        }
+        None => {}
+    }
+    match result.opt_str("os") {
+        Some(r) => {
+            match serde_json::from_str(&format!("\"{}\"", r)) {
+                Ok(r) => {
+                    re.as_mut().unwrap().os = Some(r);
+                }
+                Err(e) => {
+                    log::error!(
+                "{} {}",
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "os")
+                    .as_str(),
+                e
+            );
+            return None;
                 }
             }
-            None => {}
+        }
+        None => {}
+    }
+    match result.opt_str("artwork-path-template") {
+        Some(s) => match PathTemplate::from_str(&s) {
+            Ok(t) => {
+                re.as_mut().unwrap().artwork_path_template.replace(t);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "artwork-path-template")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
         },
+        None => {}
+    }
+    match parse_optional_opt(&result, "artwork-group-by-author", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().artwork_group_by_author = b,
         Err(e) => {
             log::error!(
                 "{} {}",
-                ("Failed to parse <opt>:")
-                    .replace("<opt>", "x264-crf")
+                gettext("Failed to parse <opt>:")
+                    .replace("<opt>", "artwork-group-by-author")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(&result, "ugoira-max-fps", 60f32, parse_f32) {
-        Ok(r) => match r {
-            Some(crf) => {
-                if crf <= 0f32 || crf > 1000f32 {
-                    log::error!(
-                        "{}",
-                        gettext("ugoira-max-fps can not less than 0 or greater than 1000.")
-                    );
-                    return None;
-                } else {
-                    re.as_mut().unwrap().ugoira_max_fps.replace(crf);
-                }
+    match result.opt_str("path-sanitize") {
+        Some(s) => match PathSanitizeMode::from_str(&s) {
+            Ok(m) => {
+                re.as_mut().unwrap().path_sanitize.replace(m);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "path-sanitize")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("user-work-types") {
+        Some(s) => match PixivWorkTypes::from_str(&s) {
+            Ok(t) => {
+                re.as_mut().unwrap().user_work_types.replace(t);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "user-work-types")
+                        .as_str(),
+                    e
+                );
+                return None;
             }
-            None => {}
         },
+        None => {}
+    }
+    match parse_nonempty_usize(result.opt_str("user-max-works")) {
+        Ok(r) => re.as_mut().unwrap().user_max_works = r,
         Err(e) => {
             log::error!(
                 "{} {}",
-                ("Failed to parse <opt>:")
-                    .replace("<opt>", "ugoira-max-fps")
-                    .as_str(),
+                gettext("Failed to parse <opt>:").replace("<opt>", "user-max-works"),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(&result, "fanbox-page-number", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().fanbox_page_number = b,
+    match result.opt_str("bookmarks-restrict") {
+        Some(s) => match PixivRestrictType::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().bookmarks_restrict.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "bookmarks-restrict")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    re.as_mut().unwrap().bookmarks_tag = result.opt_str("bookmarks-tag");
+    match parse_nonempty_usize(result.opt_str("bookmarks-max")) {
+        Ok(r) => re.as_mut().unwrap().bookmarks_max = r,
         Err(e) => {
             log::error!(
                 "{} {}",
-                gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "fanbox-page-number")
-                    .as_str(),
+                gettext("Failed to parse <opt>:").replace("<opt>", "bookmarks-max"),
                 e
             );
             return None;
         }
     }
-    re.as_mut().unwrap().refresh_token = result.opt_str("refresh-token");
-    match parse_optional_opt(&result, "use-app-api", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().use_app_api = b,
+    match parse_optional_opt(&result, "bookmarks-stop-at-downloaded", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().bookmarks_stop_at_downloaded = b,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "use-app-api")
+                    .replace("<opt>", "bookmarks-stop-at-downloaded")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(&result, "use-web-description", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().use_web_description = b,
+    match parse_optional_opt(&result, "fanbox-creator-posts", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().fanbox_creator_posts = b,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "use-web-description")
+                    .replace("<opt>", "fanbox-creator-posts")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_optional_opt(&result, "add-history", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().add_history = b,
+    match parse_optional_opt(&result, "fanbox-skip-restricted", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().fanbox_skip_restricted = b,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "add-history")
+                    .replace("<opt>", "fanbox-skip-restricted")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    #[cfg(feature = "server")]
-    match parse_nonempty_usize(result.opt_str("push-task-max-count")) {
-        Ok(r) => re.as_mut().unwrap().push_task_max_count = r,
+    match result.opt_str("fanbox-since") {
+        Some(s) => match parse_date_time(&s, false) {
+            Ok(d) => {
+                re.as_mut().unwrap().fanbox_since.replace(d);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "fanbox-since")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("fanbox-until") {
+        Some(s) => match parse_date_time(&s, true) {
+            Ok(d) => {
+                re.as_mut().unwrap().fanbox_until.replace(d);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "fanbox-until")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match parse_nonempty_usize(result.opt_str("fanbox-max-posts")) {
+        Ok(r) => re.as_mut().unwrap().fanbox_max_posts = r,
         Err(e) => {
             log::error!(
                 "{} {}",
-                gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "push-task-max-count")
-                    .as_str(),
+                gettext("Failed to parse <opt>:").replace("<opt>", "fanbox-max-posts"),
                 e
             );
             return None;
         }
     }
-    #[cfg(feature = "server")]
-    match parse_nonempty_usize(result.opt_str("push-task-max-push-count")) {
-        Ok(r) => re.as_mut().unwrap().push_task_max_push_count = r,
+    match result.opt_str("search-match") {
+        Some(s) => match PixivSearchMatch::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().search_match.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "search-match")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("search-mode") {
+        Some(s) => match PixivSearchMode::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().search_mode.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "search-mode")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("search-sort") {
+        Some(s) => match PixivSearchSort::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().search_sort.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "search-sort")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("search-types") {
+        Some(s) => match PixivWorkTypes::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().search_types.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "search-types")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("search-since") {
+        Some(s) => match NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d") {
+            Ok(d) => {
+                re.as_mut().unwrap().search_since.replace(d);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "search-since")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("search-until") {
+        Some(s) => match NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d") {
+            Ok(d) => {
+                re.as_mut().unwrap().search_until.replace(d);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "search-until")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match parse_nonempty_usize(result.opt_str("search-max")) {
+        Ok(r) => re.as_mut().unwrap().search_max = r,
         Err(e) => {
             log::error!(
                 "{} {}",
-                gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "push-task-max-push-count")
-                    .as_str(),
+                gettext("Failed to parse <opt>:").replace("<opt>", "search-max"),
                 e
             );
             return None;
         }
     }
-    #[cfg(feature = "server")]
-    {
-        re.as_mut().unwrap().disable_push_task = result.opt_present("disable-push-task");
+    match result.opt_str("ranking-content") {
+        Some(s) => match PixivRankingContent::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().ranking_content.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "ranking-content")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
     }
-    re.as_mut().unwrap().ugoira = result.opt_str("ugoira");
-    #[cfg(feature = "ugoira")]
-    match parse_optional_opt(&result, "ugoira-cli", true, parse_bool) {
-        Ok(b) => re.as_mut().unwrap().ugoira_cli = b,
+    match result.opt_str("ranking-date") {
+        Some(s) => match NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d") {
+            Ok(d) => {
+                re.as_mut().unwrap().ranking_date.replace(d);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "ranking-date")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("ranking-end-date") {
+        Some(s) => match NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d") {
+            Ok(d) => {
+                re.as_mut().unwrap().ranking_end_date.replace(d);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "ranking-end-date")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match parse_nonempty_usize(result.opt_str("ranking-max")) {
+        Ok(r) => re.as_mut().unwrap().ranking_max = r,
         Err(e) => {
             log::error!(
                 "{} {}",
-                gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "ugoira-cli")
-                    .as_str(),
+                gettext("Failed to parse <opt>:").replace("<opt>", "ranking-max"),
                 e
             );
             return None;
         }
     }
-    match parse_non_zero_u64(result.opt_str("connect-timeout")) {
-        Ok(r) => re.as_mut().unwrap().connect_timeout = r,
+    #[cfg(feature = "db")]
+    {
+        match parse_optional_opt(&result, "archive", true, parse_bool) {
+            Ok(b) => re.as_mut().unwrap().archive = b,
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "archive")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        }
+        if re.as_ref().unwrap().cmd == Command::Verify {
+            // The artworks with broken files are still in download history.
+            re.as_mut().unwrap().archive = Some(false);
+        }
+    }
+    match parse_optional_opt(&result, "exclude-ai", true, parse_bool) {
+        Ok(b) => re.as_mut().unwrap().exclude_ai = b,
         Err(e) => {
             log::error!(
                 "{} {}",
                 gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "connect-timeout")
+                    .replace("<opt>", "exclude-ai")
                     .as_str(),
                 e
             );
             return None;
         }
     }
-    match parse_non_zero_u64(result.opt_str("client-timeout")) {
-        Ok(r) => re.as_mut().unwrap().client_timeout = r,
+    match result.opt_str("age-filter") {
+        Some(s) => match PixivAgeFilter::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().age_filter.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "age-filter")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    if result.opt_present("include-tags") {
+        re.as_mut().unwrap().include_tags = Some(
+            result
+                .opt_strs("include-tags")
+                .iter()
+                .flat_map(parse_tag_list)
+                .collect(),
+        );
+    }
+    if result.opt_present("exclude-tags") {
+        re.as_mut().unwrap().exclude_tags = Some(
+            result
+                .opt_strs("exclude-tags")
+                .iter()
+                .flat_map(parse_tag_list)
+                .collect(),
+        );
+    }
+    match parse_u64(result.opt_str("min-bookmarks")) {
+        Ok(r) => re.as_mut().unwrap().min_bookmarks = r,
         Err(e) => {
             log::error!(
                 "{} {}",
-                gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "client-timeout")
-                    .as_str(),
+                gettext("Failed to parse <opt>:").replace("<opt>", "min-bookmarks"),
                 e
             );
             return None;
         }
     }
-    re.as_mut().unwrap().ffprobe = result.opt_str("ffprobe");
-    re.as_mut().unwrap().ffmpeg = result.opt_str("ffmpeg");
-    match result.opt_str("browser") {
-        Some(r) => {
-            match serde_json::from_str(&format!("\"{}\"", r)) {
-                Ok(r) => {
-                    re.as_mut().unwrap().browser = Some(r);
-                }
-                Err(e) => {
-                    log::error!(
-                "{} {}",
-                gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "browser")
-                    .as_str(),
-                e
-            );
-            return None;
-                }
+    match result.opt_str("upload-since") {
+        Some(s) => match parse_date_time(&s, false) {
+            Ok(d) => {
+                re.as_mut().unwrap().upload_since.replace(d);
             }
-        }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "upload-since")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
         None => {}
     }
-    match result.opt_str("os") {
-        Some(r) => {
-            match serde_json::from_str(&format!("\"{}\"", r)) {
-                Ok(r) => {
-                    re.as_mut().unwrap().os = Some(r);
-                }
-                Err(e) => {
-                    log::error!(
-                "{} {}",
-                gettext("Failed to parse <opt>:")
-                    .replace("<opt>", "os")
-                    .as_str(),
-                e
-            );
-            return None;
-                }
+    match result.opt_str("upload-until") {
+        Some(s) => match parse_date_time(&s, true) {
+            Ok(d) => {
+                re.as_mut().unwrap().upload_until.replace(d);
             }
-        }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "upload-until")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("pages") {
+        Some(s) => match PageRange::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().page_range.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "pages")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
+        None => {}
+    }
+    match result.opt_str("image-size") {
+        Some(s) => match PixivImageSize::from_str(&s) {
+            Ok(r) => {
+                re.as_mut().unwrap().image_size.replace(r);
+            }
+            Err(e) => {
+                log::error!(
+                    "{} {}",
+                    gettext("Failed to parse <opt>:")
+                        .replace("<opt>", "image-size")
+                        .as_str(),
+                    e
+                );
+                return None;
+            }
+        },
         None => {}
     }
     re
@@ -1,3 +1,4 @@
 pub mod description;
 pub mod json;
 pub mod metadata;
+pub mod novel;
@@ -0,0 +1,299 @@
+use fancy_regex::{Captures, Regex};
+
+lazy_static! {
+    #[doc(hidden)]
+    static ref RE: Regex = Regex::new(concat!(
+        "\\[newpage\\]",
+        "|\\[chapter:(?P<chapter>.*?)\\][ \\t]*(?=\\r?\\n|$)",
+        "|\\[\\[rb:(?P<rb>[^>\\]]*)>(?P<rt>[^\\]]*)\\]\\]",
+        "|\\[\\[jumpuri:(?P<jt>[^>\\]]*)>(?P<ju>[^\\]]*)\\]\\]",
+        "|\\[pixivimage:(?P<pid>\\d+)(-(?P<page>\\d+))?\\]",
+        "|\\[uploadedimage:(?P<uploaded>\\d+)\\]",
+        "|\\[jump:(?P<jump>\\d+)\\]",
+    )).unwrap();
+}
+
+/// An image referenced in novel's text
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NovelImage {
+    /// An illust on pixiv. `page` starts from 1.
+    Pixiv { id: u64, page: u16 },
+    /// An image uploaded with the novel. See `textEmbeddedImages` in web API.
+    Uploaded(u64),
+}
+
+/// A node of pixiv novel's markup
+#[derive(Clone, Debug, PartialEq)]
+pub enum NovelNode {
+    /// Plain text
+    Text(String),
+    /// `[newpage]`
+    NewPage,
+    /// `[chapter:title]`
+    Chapter(Vec<NovelNode>),
+    /// `[[rb:base > ruby]]`
+    Ruby { base: String, ruby: String },
+    /// `[[jumpuri:text > url]]`
+    JumpUri { text: String, url: String },
+    /// `[jump:page]`
+    Jump(u64),
+    /// `[pixivimage:id-page]` or `[uploadedimage:id]`
+    Image(NovelImage),
+}
+
+/// Parse the text of a pixiv novel.
+pub fn parse_novel<S: AsRef<str> + ?Sized>(text: &S) -> Vec<NovelNode> {
+    let text = text.as_ref();
+    let mut nodes = Vec::new();
+    let mut last = 0;
+    for cap in RE.captures_iter(text) {
+        let cap = match cap {
+            Ok(cap) => cap,
+            Err(e) => {
+                log::warn!("{}", e);
+                break;
+            }
+        };
+        let m = cap.get(0).unwrap();
+        if m.start() > last {
+            nodes.push(NovelNode::Text(text[last..m.start()].to_owned()));
+        }
+        last = m.end();
+        nodes.push(parse_node(&cap));
+    }
+    if last < text.len() {
+        nodes.push(NovelNode::Text(text[last..].to_owned()));
+    }
+    nodes
+}
+
+fn parse_node(cap: &Captures) -> NovelNode {
+    let get = |name| cap.name(name).map(|m| m.as_str());
+    if let Some(title) = get("chapter") {
+        return NovelNode::Chapter(parse_novel(title.trim()));
+    }
+    if let (Some(base), Some(ruby)) = (get("rb"), get("rt")) {
+        return NovelNode::Ruby {
+            base: base.trim().to_owned(),
+            ruby: ruby.trim().to_owned(),
+        };
+    }
+    if let (Some(text), Some(url)) = (get("jt"), get("ju")) {
+        return NovelNode::JumpUri {
+            text: text.trim().to_owned(),
+            url: url.trim().to_owned(),
+        };
+    }
+    if let Some(Ok(id)) = get("pid").map(|s| s.parse::<u64>()) {
+        let page = get("page").and_then(|s| s.parse::<u16>().ok()).unwrap_or(1);
+        return NovelNode::Image(NovelImage::Pixiv { id, page });
+    }
+    if let Some(Ok(id)) = get("uploaded").map(|s| s.parse::<u64>()) {
+        return NovelNode::Image(NovelImage::Uploaded(id));
+    }
+    if let Some(Ok(page)) = get("jump").map(|s| s.parse::<u64>()) {
+        return NovelNode::Jump(page);
+    }
+    NovelNode::NewPage
+}
+
+/// Returns all images referenced in the novel, without duplicates.
+pub fn get_novel_images(nodes: &[NovelNode]) -> Vec<NovelImage> {
+    let mut images = Vec::new();
+    for node in nodes {
+        if let NovelNode::Image(i) = node {
+            if !images.contains(i) {
+                images.push(i.clone());
+            }
+        }
+    }
+    images
+}
+
+/// Convert novel to plain text.
+/// * `image` - Returns the location of the image. If [None] is returned, the original markup is kept.
+pub fn convert_novel_to_text<F: Fn(&NovelImage) -> Option<String>>(
+    nodes: &[NovelNode],
+    image: F,
+) -> String {
+    let mut s = String::new();
+    for node in nodes {
+        match node {
+            NovelNode::Text(t) => s += t,
+            NovelNode::NewPage => {}
+            NovelNode::Chapter(title) => {
+                // Use trait object to avoid infinite instantiation.
+                let image: &dyn Fn(&NovelImage) -> Option<String> = &image;
+                s += &convert_novel_to_text(title, image)
+            }
+            NovelNode::Ruby { base, ruby } => {
+                s += &format!("{}({})", base, ruby);
+            }
+            NovelNode::JumpUri { text, url } => {
+                if text == url {
+                    s += url;
+                } else {
+                    s += &format!("{} ({})", text, url);
+                }
+            }
+            NovelNode::Jump(_) => {}
+            NovelNode::Image(i) => match image(i) {
+                Some(p) => s += &format!("[{}]", p),
+                None => s += &image_markup(i),
+            },
+        }
+    }
+    s
+}
+
+/// Convert novel to markdown. Ruby is kept as inline HTML.
+/// * `image` - Returns the location of the image. If [None] is returned, the original markup is kept.
+pub fn convert_novel_to_md<F: Fn(&NovelImage) -> Option<String>>(
+    nodes: &[NovelNode],
+    image: F,
+) -> String {
+    let mut s = String::new();
+    let mut page = 1u64;
+    let mut block_end = true;
+    // The anchors are only needed when the novel has multiple pages or links to pages.
+    if nodes
+        .iter()
+        .any(|n| matches!(n, NovelNode::NewPage | NovelNode::Jump(_)))
+    {
+        s += "<a id=\"page-1\"></a>\n\n";
+    }
+    for node in nodes {
+        match node {
+            NovelNode::Text(t) => {
+                let t = if block_end {
+                    t.trim_start_matches(|c| c == '\r' || c == '\n')
+                } else {
+                    t.as_str()
+                };
+                if t.is_empty() {
+                    continue;
+                }
+                let lines: Vec<_> = t
+                    .split('\n')
+                    .map(|l| escape_md(l.trim_end_matches('\r')))
+                    .collect();
+                s += &lines.join("  \n");
+                block_end = false;
+            }
+            NovelNode::NewPage => {
+                page += 1;
+                end_block(&mut s);
+                s += &format!("---\n\n<a id=\"page-{}\"></a>\n\n", page);
+                block_end = true;
+            }
+            NovelNode::Chapter(title) => {
+                end_block(&mut s);
+                s += "## ";
+                let image: &dyn Fn(&NovelImage) -> Option<String> = &image;
+                s += &convert_novel_to_md(title, image).replace("  \n", " ");
+                s += "\n\n";
+                block_end = true;
+            }
+            NovelNode::Ruby { base, ruby } => {
+                s += &format!(
+                    "<ruby>{}<rt>{}</rt></ruby>",
+                    escape_html(base),
+                    escape_html(ruby)
+                );
+                block_end = false;
+            }
+            NovelNode::JumpUri { text, url } => {
+                s += &format!("[{}](<{}>)", escape_md(text), url);
+                block_end = false;
+            }
+            NovelNode::Jump(p) => {
+                s += &format!("[{}](#page-{})", p, p);
+                block_end = false;
+            }
+            NovelNode::Image(i) => {
+                match image(i) {
+                    Some(p) => s += &format!("![](<{}>)", p),
+                    None => s += &escape_md(&image_markup(i)),
+                }
+                block_end = false;
+            }
+        }
+    }
+    s
+}
+
+fn end_block(s: &mut String) {
+    let len = s.trim_end().len();
+    s.truncate(len);
+    if !s.is_empty() {
+        *s += "\n\n";
+    }
+}
+
+fn image_markup(i: &NovelImage) -> String {
+    match i {
+        NovelImage::Pixiv { id, page } => {
+            if *page > 1 {
+                format!("[pixivimage:{}-{}]", id, page)
+            } else {
+                format!("[pixivimage:{}]", id)
+            }
+        }
+        NovelImage::Uploaded(id) => format!("[uploadedimage:{}]", id),
+    }
+}
+
+fn escape_md(s: &str) -> String {
+    let mut re = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~' => {
+                re.push('\\');
+                re.push(c);
+            }
+            _ => re.push(c),
+        }
+    }
+    re
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[test]
+fn test_parse_novel() {
+    let nodes = parse_novel(
+        "[chapter:[[rb:序章 > じょしょう]]]\nA[pixivimage:123-2]\n[newpage]\n[uploadedimage:45][jump:1]",
+    );
+    assert_eq!(
+        nodes,
+        vec![
+            NovelNode::Chapter(vec![NovelNode::Ruby {
+                base: String::from("序章"),
+                ruby: String::from("じょしょう"),
+            }]),
+            NovelNode::Text(String::from("\nA")),
+            NovelNode::Image(NovelImage::Pixiv { id: 123, page: 2 }),
+            NovelNode::Text(String::from("\n")),
+            NovelNode::NewPage,
+            NovelNode::Text(String::from("\n")),
+            NovelNode::Image(NovelImage::Uploaded(45)),
+            NovelNode::Jump(1),
+        ]
+    );
+    assert_eq!(get_novel_images(&nodes).len(), 2);
+    assert_eq!(
+        convert_novel_to_text(&nodes, |_| None),
+        "序章(じょしょう)\nA[pixivimage:123-2]\n\n[uploadedimage:45]"
+    );
+    assert_eq!(
+        convert_novel_to_md(&nodes, |i| match i {
+            NovelImage::Uploaded(id) => Some(format!("{}.png", id)),
+            _ => None,
+        }),
+        "<a id=\"page-1\"></a>\n\n## <ruby>序章<rt>じょしょう</rt></ruby>\n\nA\\[pixivimage:123-2\\]\n\n---\n\n<a id=\"page-2\"></a>\n\n![](<45.png>)[1](#page-1)"
+    );
+}
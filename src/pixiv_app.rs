@@ -4,22 +4,25 @@ use crate::error::PixivDownloaderError;
 use crate::ext::atomic::AtomicQuick;
 use crate::ext::replace::ReplaceWith2;
 use crate::ext::rw_lock::GetRwLock;
+use crate::opt::search::PixivSearchParams;
 use crate::pixivapp::error::handle_error;
 use crate::pixivapp::illust::PixivAppIllust;
 use crate::pixivapp::illusts::PixivAppIllusts;
+use crate::pixivapp::novel::PixivAppNovel;
 use crate::webclient::{ReqMiddleware, WebClient};
 use crate::{get_helper, gettext};
-use chrono::{DateTime, Local, SecondsFormat, Utc};
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, Utc};
 use json::JsonValue;
 use wreq::{Client, IntoUrl, Request, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::str::FromStr;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::sync::RwLock;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum PixivRestrictType {
     Public,
@@ -44,6 +47,18 @@ impl ToString for PixivRestrictType {
     }
 }
 
+impl FromStr for PixivRestrictType {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "public" | "show" => Ok(Self::Public),
+            "private" | "hide" => Ok(Self::Private),
+            "all" => Ok(Self::All),
+            _ => Err(gettext("Unknown restrict type.")),
+        }
+    }
+}
+
 impl ToString for PixivRestrictLessType {
     fn to_string(&self) -> String {
         match self {
@@ -125,6 +140,12 @@ impl PixivAppClientInternal {
         }
     }
 
+    #[cfg(feature = "db")]
+    /// The database used to store tokens and download history
+    pub fn db(&self) -> Option<Arc<Box<dyn PixivDownloaderDb + Send + Sync>>> {
+        self.db.clone()
+    }
+
     pub fn is_inited(&self) -> bool {
         self.inited.qload()
     }
@@ -307,6 +328,22 @@ impl PixivAppClientInternal {
         Ok(obj)
     }
 
+    pub async fn get_novel_details(&self, id: u64) -> Result<JsonValue, PixivDownloaderError> {
+        self.auto_handle().await?;
+        let re = self
+            .client
+            .get_with_param(
+                "https://app-api.pixiv.net/v2/novel/detail",
+                json::object! {"novel_id": id},
+                None,
+            )
+            .await
+            .ok_or(gettext("Failed to get novel details."))?;
+        let obj = handle_error(re).await?;
+        log::debug!("{}{}", gettext("Novel details:"), obj.pretty(2).as_str());
+        Ok(obj)
+    }
+
     pub async fn add_illust_to_browsing_history(
         &self,
         ids: Vec<u64>,
@@ -371,13 +408,74 @@ impl PixivAppClientInternal {
         Ok(obj)
     }
 
-    pub async fn get_user_illusts(&self, uid: u64) -> Result<JsonValue, PixivDownloaderError> {
+    /// Search illusts
+    /// * `params` - Search parameters
+    pub async fn search_illust(
+        &self,
+        params: &PixivSearchParams,
+    ) -> Result<JsonValue, PixivDownloaderError> {
+        self.auto_handle().await?;
+        let mut query = json::object! {
+            "word": params.keywords.as_str(),
+            "search_target": params.match_type.app_value(),
+            "sort": params.sort.app_value(),
+            "filter": "for_ios",
+            "include_translated_tag_results": "true",
+            "merge_plain_keyword_results": "true",
+        };
+        if let Some(d) = params.since {
+            query["start_date"] = d.format("%Y-%m-%d").to_string().into();
+        }
+        if let Some(d) = params.until {
+            query["end_date"] = d.format("%Y-%m-%d").to_string().into();
+        }
+        let re = self
+            .client
+            .get_with_param("https://app-api.pixiv.net/v1/search/illust", query, None)
+            .await
+            .ok_or(gettext("Failed to search illusts."))?;
+        let obj = handle_error(re).await?;
+        log::debug!("{}{}", gettext("Search results: "), obj.pretty(2).as_str());
+        Ok(obj)
+    }
+
+    /// Get illust ranking
+    /// * `mode` - Ranking mode in app API. See [crate::opt::ranking::PixivRankingMode::app_value].
+    /// * `date` - The date of ranking. [None] means the latest one.
+    pub async fn get_illust_ranking(
+        &self,
+        mode: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<JsonValue, PixivDownloaderError> {
+        self.auto_handle().await?;
+        let mut query = json::object! {"mode": mode, "filter": "for_ios"};
+        if let Some(d) = date {
+            query["date"] = d.format("%Y-%m-%d").to_string().into();
+        }
+        let re = self
+            .client
+            .get_with_param("https://app-api.pixiv.net/v1/illust/ranking", query, None)
+            .await
+            .ok_or(gettext("Failed to get illust ranking."))?;
+        let obj = handle_error(re).await?;
+        log::debug!("{}{}", gettext("Illust ranking: "), obj.pretty(2).as_str());
+        Ok(obj)
+    }
+
+    /// Get user's illusts
+    /// * `uid` - User's id
+    /// * `typ` - `illust` or `manga`. Ugoira is included in `illust`.
+    pub async fn get_user_illusts(
+        &self,
+        uid: u64,
+        typ: &str,
+    ) -> Result<JsonValue, PixivDownloaderError> {
         self.auto_handle().await?;
         let re = self
             .client
             .get_with_param(
                 "https://app-api.pixiv.net/v1/user/illusts",
-                json::object! {"type": "illust", "user_id": uid, "filter": "for_ios"},
+                json::object! {"type": typ, "user_id": uid, "filter": "for_ios"},
                 None,
             )
             .await
@@ -426,6 +524,11 @@ impl PixivAppClient {
         Ok(PixivAppIllust::new(obj["illust"].clone()))
     }
 
+    pub async fn get_novel_details(&self, id: u64) -> Result<PixivAppNovel, PixivDownloaderError> {
+        let obj = self.internal.get_novel_details(id).await?;
+        Ok(PixivAppNovel::new(obj["novel"].clone()))
+    }
+
     pub async fn get_follow(
         &self,
         restrict: &PixivRestrictType,
@@ -444,11 +547,37 @@ impl PixivAppClient {
         PixivAppIllusts::new(self.internal.clone(), obj)
     }
 
+    /// Search illusts
+    /// * `params` - Search parameters
+    pub async fn search_illust(
+        &self,
+        params: &PixivSearchParams,
+    ) -> Result<PixivAppIllusts, PixivDownloaderError> {
+        let obj = self.internal.search_illust(params).await?;
+        PixivAppIllusts::new(self.internal.clone(), obj)
+    }
+
+    /// Get illust ranking
+    /// * `mode` - Ranking mode in app API. See [crate::opt::ranking::PixivRankingMode::app_value].
+    /// * `date` - The date of ranking. [None] means the latest one.
+    pub async fn get_illust_ranking(
+        &self,
+        mode: &str,
+        date: Option<NaiveDate>,
+    ) -> Result<PixivAppIllusts, PixivDownloaderError> {
+        let obj = self.internal.get_illust_ranking(mode, date).await?;
+        PixivAppIllusts::new(self.internal.clone(), obj)
+    }
+
+    /// Get user's illusts
+    /// * `uid` - User's id
+    /// * `typ` - `illust` or `manga`. Ugoira is included in `illust`.
     pub async fn get_user_illusts(
         &self,
         uid: u64,
+        typ: &str,
     ) -> Result<PixivAppIllusts, PixivDownloaderError> {
-        let obj = self.internal.get_user_illusts(uid).await?;
+        let obj = self.internal.get_user_illusts(uid, typ).await?;
         PixivAppIllusts::new(self.internal.clone(), obj)
     }
 }
@@ -1,6 +1,14 @@
 use crate::ext::json::ToJson;
+use crate::opt::ranking::{PixivRankingContent, PixivRankingMode};
+use crate::opt::search::{
+    get_web_search_type, parse_web_search_type, PixivSearchMatch, PixivSearchMode, PixivSearchSort,
+};
+use crate::opt::work_type::PixivWorkTypes;
+use crate::pixiv_app::PixivRestrictType;
+use chrono::NaiveDate;
 use json::JsonValue;
 use regex::Regex;
+use std::str::FromStr;
 use wreq::IntoUrl;
 
 lazy_static! {
@@ -14,6 +22,20 @@ lazy_static! {
     static ref RE4: Regex = Regex::new("^(https?://)?(?P<creator>[^./]+)\\.fanbox\\.cc(/(\\?.*)?)?$").unwrap();
     #[doc(hidden)]
     static ref RE5: Regex = Regex::new("^(https?://)?(www\\.)?fanbox\\.cc/@(?P<creator>[^/?]+)(/(\\?.*)?)?$").unwrap();
+    #[doc(hidden)]
+    static ref RE8: Regex = Regex::new("^(https?://)?(www\\.)?fanbox\\.cc/home(/(?P<feed>supporting))?/?(\\?.*)?$").unwrap();
+    #[doc(hidden)]
+    static ref RE7: Regex = Regex::new("^(https?://)?(www\\.)?pixiv\\.net/(en/)?users/(?P<uid>\\d+)/bookmarks/artworks(/(?P<tag>[^/?]+))?/?(\\?(?P<query>.*))?$").unwrap();
+    #[doc(hidden)]
+    static ref RE6: Regex = Regex::new("^(https?://)?(www\\.)?pixiv\\.net/(en/)?users/(?P<uid>\\d+)(/(?P<works>illustrations|manga))?(/(\\?.*)?)?$").unwrap();
+    #[doc(hidden)]
+    static ref RE11: Regex = Regex::new("^(https?://)?(www\\.)?pixiv\\.net/(en/)?tags/(?P<kw>[^/?]+)(/(?P<kind>artworks|illustrations|manga))?/?(\\?(?P<query>.*))?$").unwrap();
+    #[doc(hidden)]
+    static ref RE10: Regex = Regex::new("^(https?://)?(www\\.)?pixiv\\.net/(en/)?user/(?P<uid>\\d+)/series/(?P<sid>\\d+)/?(\\?.*)?$").unwrap();
+    #[doc(hidden)]
+    static ref RE12: Regex = Regex::new("^(https?://)?(www\\.)?pixiv\\.net/ranking\\.php/?(\\?(?P<query>.*))?$").unwrap();
+    #[doc(hidden)]
+    static ref RE9: Regex = Regex::new("^(https?://)?(www\\.)?pixiv\\.net/(en/)?novel/show\\.php\\?(.*&)?id=(?P<id>\\d+)").unwrap();
 }
 
 #[derive(Clone, Debug)]
@@ -37,6 +59,139 @@ impl FanboxPostID {
     }
 }
 
+#[derive(Clone, Debug)]
+/// Pixiv user ID
+pub struct PixivUserID {
+    /// User ID
+    pub uid: u64,
+    /// The types of works in the link. [None] if all works.
+    pub types: Option<PixivWorkTypes>,
+}
+
+impl PixivUserID {
+    /// Create a new id.
+    /// * `uid` - User ID
+    /// * `types` - The types of works
+    pub fn new(uid: u64, types: Option<PixivWorkTypes>) -> Self {
+        Self { uid, types }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The bookmarks of a pixiv user
+pub struct PixivBookmarksID {
+    /// User ID
+    pub uid: u64,
+    /// Bookmark tag
+    pub tag: Option<String>,
+    /// Public or private bookmarks. [None] if not specified in link.
+    pub restrict: Option<PixivRestrictType>,
+}
+
+impl PixivBookmarksID {
+    /// Create a new id.
+    /// * `uid` - User ID
+    /// * `tag` - Bookmark tag
+    /// * `restrict` - Public or private bookmarks
+    pub fn new(uid: u64, tag: Option<String>, restrict: Option<PixivRestrictType>) -> Self {
+        Self { uid, tag, restrict }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Pixiv manga series ID
+pub struct PixivSeriesID {
+    /// The author's user ID
+    pub uid: u64,
+    /// Series ID
+    pub series_id: u64,
+}
+
+impl PixivSeriesID {
+    /// Create a new id.
+    /// * `uid` - The author's user ID
+    /// * `series_id` - Series ID
+    pub fn new(uid: u64, series_id: u64) -> Self {
+        Self { uid, series_id }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Artwork search. The options which are not specified in link are [None].
+pub struct PixivSearchID {
+    /// Keywords
+    pub keywords: String,
+    /// How keywords are matched
+    pub match_type: Option<PixivSearchMatch>,
+    /// Age restriction filter
+    pub mode: Option<PixivSearchMode>,
+    /// Sort order
+    pub sort: Option<PixivSearchSort>,
+    /// The types of works
+    pub types: Option<PixivWorkTypes>,
+    /// Only artworks uploaded after this date
+    pub since: Option<NaiveDate>,
+    /// Only artworks uploaded before this date
+    pub until: Option<NaiveDate>,
+}
+
+impl PixivSearchID {
+    /// Create a new search with keywords only.
+    /// * `keywords` - Keywords
+    pub fn new<S: AsRef<str> + ?Sized>(keywords: &S) -> Self {
+        Self {
+            keywords: keywords.as_ref().to_owned(),
+            match_type: None,
+            mode: None,
+            sort: None,
+            types: None,
+            since: None,
+            until: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Pixiv ranking. The options which are not specified in link are [None].
+pub struct PixivRankingID {
+    /// Ranking mode
+    pub mode: Option<PixivRankingMode>,
+    /// Content type
+    pub content: Option<PixivRankingContent>,
+    /// The date of ranking. [None] means the latest one.
+    pub date: Option<NaiveDate>,
+}
+
+impl PixivRankingID {
+    /// Create a new ranking id.
+    /// * `mode` - Ranking mode
+    pub fn new(mode: Option<PixivRankingMode>) -> Self {
+        Self {
+            mode,
+            content: None,
+            date: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Fanbox post feed
+pub enum FanboxFeed {
+    /// Posts of supported and followed creators
+    Home,
+    /// Posts of supported creators
+    Supporting,
+}
+
+impl FanboxFeed {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Home => "home",
+            Self::Supporting => "supporting",
+        }
+    }
+}
+
 /// Repesent an Pixiv ID
 #[derive(Clone, Debug)]
 pub enum PixivID {
@@ -46,6 +201,20 @@ pub enum PixivID {
     FanboxPost(FanboxPostID),
     /// Fanbox creator
     FanboxCreator(String),
+    /// Pixiv user
+    User(PixivUserID),
+    /// Bookmarks of a pixiv user
+    Bookmarks(PixivBookmarksID),
+    /// Fanbox post feed
+    FanboxFeed(FanboxFeed),
+    /// Novel Id
+    Novel(u64),
+    /// Manga series
+    Series(PixivSeriesID),
+    /// Artwork search
+    Search(PixivSearchID),
+    /// Artwork ranking
+    Ranking(PixivRankingID),
 }
 
 pub trait ToPixivID {
@@ -69,6 +238,86 @@ impl PixivID {
             },
             None => {}
         }
+        match RE9.captures(s) {
+            Some(re) => match re.name("id") {
+                Some(r) => match r.as_str().parse::<u64>() {
+                    Ok(r) => return Some(Self::Novel(r)),
+                    Err(_) => {}
+                },
+                None => {}
+            },
+            None => {}
+        }
+        match RE11.captures(s) {
+            Some(re) => match re.name("kw") {
+                Some(kw) => {
+                    let kw = urlparse::unquote(kw.as_str()).unwrap_or(kw.as_str().to_owned());
+                    let mut id = PixivSearchID::new(&kw);
+                    match re.name("kind").map(|k| k.as_str()) {
+                        Some("illustrations") => id.types = Some(PixivWorkTypes::illustrations()),
+                        Some("manga") => id.types = Some(PixivWorkTypes::manga()),
+                        _ => {}
+                    }
+                    if let Some(query) = re.name("query") {
+                        for q in query.as_str().split('&') {
+                            match q.split_once('=') {
+                                Some(("s_mode", v)) => {
+                                    id.match_type = PixivSearchMatch::from_web_value(v)
+                                }
+                                Some(("mode", v)) => id.mode = PixivSearchMode::from_str(v).ok(),
+                                Some(("order", v)) => id.sort = PixivSearchSort::from_web_value(v),
+                                Some(("type", v)) => id.types = parse_web_search_type(v),
+                                Some(("scd", v)) => {
+                                    id.since = NaiveDate::parse_from_str(v, "%Y-%m-%d").ok()
+                                }
+                                Some(("ecd", v)) => {
+                                    id.until = NaiveDate::parse_from_str(v, "%Y-%m-%d").ok()
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    return Some(Self::Search(id));
+                }
+                None => {}
+            },
+            None => {}
+        }
+        match RE12.captures(s) {
+            Some(re) => {
+                let mut id = PixivRankingID::new(None);
+                if let Some(query) = re.name("query") {
+                    for q in query.as_str().split('&') {
+                        match q.split_once('=') {
+                            Some(("mode", v)) => id.mode = PixivRankingMode::from_str(v).ok(),
+                            Some(("content", v)) => {
+                                id.content = PixivRankingContent::from_str(v).ok()
+                            }
+                            Some(("date", v)) => {
+                                id.date = NaiveDate::parse_from_str(v, "%Y%m%d").ok()
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                return Some(Self::Ranking(id));
+            }
+            None => {}
+        }
+        match RE10.captures(s) {
+            Some(re) => match (re.name("uid"), re.name("sid")) {
+                (Some(uid), Some(sid)) => {
+                    match (uid.as_str().parse::<u64>(), sid.as_str().parse::<u64>()) {
+                        (Ok(uid), Ok(sid)) => {
+                            return Some(Self::Series(PixivSeriesID::new(uid, sid)));
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            },
+            None => {}
+        }
         match RE2.captures(s) {
             Some(re) => match re.name("creator") {
                 Some(creator) => match re.name("id") {
@@ -99,6 +348,13 @@ impl PixivID {
             },
             None => {}
         }
+        match RE8.captures(s) {
+            Some(re) => match re.name("feed") {
+                Some(_) => return Some(Self::FanboxFeed(FanboxFeed::Supporting)),
+                None => return Some(Self::FanboxFeed(FanboxFeed::Home)),
+            },
+            None => {}
+        }
         match RE4.captures(s) {
             Some(re) => match re.name("creator") {
                 Some(creator) => match creator.as_str() {
@@ -118,6 +374,48 @@ impl PixivID {
             },
             None => {}
         }
+        match RE7.captures(s) {
+            Some(re) => match re.name("uid") {
+                Some(uid) => match uid.as_str().parse::<u64>() {
+                    Ok(uid) => {
+                        let tag = re.name("tag").map(|t| {
+                            urlparse::unquote(t.as_str()).unwrap_or(t.as_str().to_owned())
+                        });
+                        let mut restrict = None;
+                        if let Some(query) = re.name("query") {
+                            for q in query.as_str().split('&') {
+                                match q {
+                                    "rest=show" => restrict = Some(PixivRestrictType::Public),
+                                    "rest=hide" => restrict = Some(PixivRestrictType::Private),
+                                    _ => {}
+                                }
+                            }
+                        }
+                        return Some(Self::Bookmarks(PixivBookmarksID::new(uid, tag, restrict)));
+                    }
+                    Err(_) => {}
+                },
+                None => {}
+            },
+            None => {}
+        }
+        match RE6.captures(s) {
+            Some(re) => match re.name("uid") {
+                Some(uid) => match uid.as_str().parse::<u64>() {
+                    Ok(uid) => {
+                        let types = match re.name("works").map(|w| w.as_str()) {
+                            Some("illustrations") => Some(PixivWorkTypes::illustrations()),
+                            Some("manga") => Some(PixivWorkTypes::manga()),
+                            _ => None,
+                        };
+                        return Some(Self::User(PixivUserID::new(uid, types)));
+                    }
+                    Err(_) => {}
+                },
+                None => {}
+            },
+            None => {}
+        }
         None
     }
 
@@ -135,6 +433,86 @@ impl PixivID {
             Self::FanboxCreator(id) => {
                 format!("https://www.fanbox.cc/@{}", id)
             }
+            Self::User(id) => {
+                let works = match id.types {
+                    Some(t) if t == PixivWorkTypes::illustrations() => "/illustrations",
+                    Some(t) if t == PixivWorkTypes::manga() => "/manga",
+                    _ => "",
+                };
+                format!("https://www.pixiv.net/users/{}{}", id.uid, works)
+            }
+            Self::Bookmarks(id) => {
+                let mut link = format!("https://www.pixiv.net/users/{}/bookmarks/artworks", id.uid);
+                if let Some(tag) = &id.tag {
+                    link += "/";
+                    link += &urlparse::quote(tag, b"").unwrap_or(tag.clone());
+                }
+                if id.restrict == Some(PixivRestrictType::Private) {
+                    link += "?rest=hide";
+                }
+                link
+            }
+            Self::FanboxFeed(feed) => match feed {
+                FanboxFeed::Home => String::from("https://www.fanbox.cc/home"),
+                FanboxFeed::Supporting => String::from("https://www.fanbox.cc/home/supporting"),
+            },
+            Self::Novel(id) => {
+                format!("https://www.pixiv.net/novel/show.php?id={}", id)
+            }
+            Self::Series(id) => {
+                format!(
+                    "https://www.pixiv.net/user/{}/series/{}",
+                    id.uid, id.series_id
+                )
+            }
+            Self::Search(id) => {
+                let mut query = Vec::new();
+                if let Some(m) = id.match_type {
+                    query.push(format!("s_mode={}", m.web_value()));
+                }
+                if let Some(m) = id.mode {
+                    query.push(format!("mode={}", m.web_value()));
+                }
+                if let Some(s) = id.sort {
+                    query.push(format!("order={}", s.web_value()));
+                }
+                if let Some(t) = &id.types {
+                    query.push(format!("type={}", get_web_search_type(t)));
+                }
+                if let Some(d) = id.since {
+                    query.push(format!("scd={}", d.format("%Y-%m-%d")));
+                }
+                if let Some(d) = id.until {
+                    query.push(format!("ecd={}", d.format("%Y-%m-%d")));
+                }
+                let mut link = format!(
+                    "https://www.pixiv.net/tags/{}/artworks",
+                    urlparse::quote(&id.keywords, b"").unwrap_or(id.keywords.clone())
+                );
+                if !query.is_empty() {
+                    link += "?";
+                    link += &query.join("&");
+                }
+                link
+            }
+            Self::Ranking(id) => {
+                let mut query = Vec::new();
+                if let Some(m) = id.mode {
+                    query.push(format!("mode={}", m.web_value()));
+                }
+                if let Some(c) = id.content.as_ref().and_then(|c| c.web_value()) {
+                    query.push(format!("content={}", c));
+                }
+                if let Some(d) = id.date {
+                    query.push(format!("date={}", d.format("%Y%m%d")));
+                }
+                let mut link = String::from("https://www.pixiv.net/ranking.php");
+                if !query.is_empty() {
+                    link += "?";
+                    link += &query.join("&");
+                }
+                link
+            }
         }
     }
 }
@@ -151,6 +529,27 @@ impl ToJson for PixivID {
             &PixivID::FanboxCreator(id) => Some(
                 json::value!({"type": "fanbox_creator", "creator_id": id.clone(), "link": self.to_link()}),
             ),
+            &PixivID::User(id) => {
+                Some(json::value!({"type": "user", "uid": id.uid.clone(), "link": self.to_link()}))
+            }
+            &PixivID::FanboxFeed(feed) => Some(
+                json::value!({"type": "fanbox_feed", "feed": feed.as_str(), "link": self.to_link()}),
+            ),
+            &PixivID::Bookmarks(id) => Some(
+                json::value!({"type": "bookmarks", "uid": id.uid.clone(), "tag": id.tag.clone(), "link": self.to_link()}),
+            ),
+            &PixivID::Novel(id) => {
+                Some(json::value!({"type": "novel", "id": id.clone(), "link": self.to_link()}))
+            }
+            &PixivID::Search(id) => Some(
+                json::value!({"type": "search", "keywords": id.keywords.clone(), "link": self.to_link()}),
+            ),
+            &PixivID::Series(id) => Some(
+                json::value!({"type": "series", "series_id": id.series_id.clone(), "uid": id.uid.clone(), "link": self.to_link()}),
+            ),
+            &PixivID::Ranking(id) => Some(
+                json::value!({"type": "ranking", "mode": id.mode.map(|m| m.web_value()), "content": id.content.map(|c| c.as_str()), "date": id.date.map(|d| d.format("%Y-%m-%d").to_string()), "link": self.to_link()}),
+            ),
         }
     }
 }
@@ -198,6 +597,13 @@ impl TryInto<u64> for PixivID {
             Self::Artwork(id) => Ok(id),
             Self::FanboxPost(id) => Ok(id.post_id),
             Self::FanboxCreator(_) => Err(()),
+            Self::User(_) => Err(()),
+            Self::Bookmarks(_) => Err(()),
+            Self::FanboxFeed(_) => Err(()),
+            Self::Novel(id) => Ok(id),
+            Self::Series(_) => Err(()),
+            Self::Search(_) => Err(()),
+            Self::Ranking(_) => Err(()),
         }
     }
 }
@@ -209,6 +615,13 @@ impl TryInto<u64> for &PixivID {
             PixivID::Artwork(id) => Ok(id.clone()),
             PixivID::FanboxPost(id) => Ok(id.post_id.clone()),
             PixivID::FanboxCreator(_) => Err(()),
+            PixivID::User(_) => Err(()),
+            PixivID::Bookmarks(_) => Err(()),
+            PixivID::FanboxFeed(_) => Err(()),
+            PixivID::Novel(id) => Ok(id.clone()),
+            PixivID::Series(_) => Err(()),
+            PixivID::Search(_) => Err(()),
+            PixivID::Ranking(_) => Err(()),
         }
     }
 }
@@ -1,14 +1,49 @@
+use crate::error::PixivDownloaderError;
 use crate::ext::atomic::AtomicQuick;
 use crate::ext::rw_lock::GetRwLock;
+use crate::ext::try_err::TryErr;
 use crate::gettext;
+use crate::opt::ranking::{PixivRankingContent, PixivRankingMode};
+use crate::opt::search::{get_web_search_type, PixivSearchParams};
 use crate::opthelper::get_helper;
 use crate::parser::metadata::MetaDataParser;
 use crate::webclient::WebClient;
+use chrono::NaiveDate;
 use json::JsonValue;
-use wreq::Response;
+use wreq::{Response, StatusCode};
 use std::sync::atomic::AtomicBool;
 use std::sync::RwLock;
 
+/// The error returned by Pixiv's web API
+#[derive(Debug)]
+pub struct PixivWebError {
+    status: StatusCode,
+    message: Option<String>,
+}
+
+impl PixivWebError {
+    pub fn new(status: StatusCode, message: Option<String>) -> Self {
+        Self { status, message }
+    }
+
+    /// Returns true if the requested work is deleted or not exists.
+    pub fn is_not_found(&self) -> bool {
+        self.status == StatusCode::NOT_FOUND
+    }
+}
+
+impl std::fmt::Display for PixivWebError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP ERROR {}", self.status)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PixivWebError {}
+
 /// A client which use Pixiv's web API
 pub struct PixivWebClient {
     client: WebClient,
@@ -115,6 +150,11 @@ impl PixivWebClient {
     }
 
     pub async fn deal_json(&self, r: Response) -> Option<JsonValue> {
+        self.try_deal_json(r).await.ok()
+    }
+
+    /// Returns the body of the response, or the error returned by server.
+    pub async fn try_deal_json(&self, r: Response) -> Result<JsonValue, PixivWebError> {
         let status = r.status();
         let code = status.as_u16();
         let is_status_err = code >= 400;
@@ -123,8 +163,9 @@ impl PixivWebClient {
             if is_status_err {
                 log::error!(target: "pixiv_web", "HTTP ERROR {}", status);
             }
-            log::error!(target: "pixiv_web","{} {}", gettext("Network error:"), data.unwrap_err());
-            return None;
+            let e = data.unwrap_err();
+            log::error!(target: "pixiv_web","{} {}", gettext("Network error:"), e);
+            return Err(PixivWebError::new(status, Some(e.to_string())));
         }
         let data = data.unwrap();
         let re = json::parse(data.as_str());
@@ -134,7 +175,7 @@ impl PixivWebClient {
             } else {
                 log::error!(target: "pixiv_web","{} {}", gettext("Failed to parse JSON:"), re.unwrap_err());
             }
-            return None;
+            return Err(PixivWebError::new(status, None));
         }
         let value = re.unwrap();
         let error = (&value["error"]).as_bool();
@@ -143,7 +184,7 @@ impl PixivWebClient {
                 log::error!(target: "pixiv_web","HTTP ERROR {}", status);
             }
             log::error!(target: "pixiv_web","{}", gettext("Failed to detect error."));
-            return None;
+            return Err(PixivWebError::new(status, None));
         }
         let error = error.unwrap();
         if error {
@@ -154,16 +195,22 @@ impl PixivWebClient {
             if message.is_some() {
                 log::error!(target: "pixiv_web","{}", message.unwrap());
             }
-            return None;
+            return Err(PixivWebError::new(status, message.map(|m| m.to_owned())));
         }
         let body = &value["body"];
         if body.is_empty() || body.is_null() {
-            return Some(value);
+            return Ok(value);
         }
-        Some(body.clone())
+        Ok(body.clone())
     }
 
     pub async fn get_artwork_ajax(&self, id: u64) -> Option<JsonValue> {
+        self.try_get_artwork_ajax(id).await.ok()
+    }
+
+    /// Get the artwork's data by ajax API.
+    /// Returns [PixivWebError] if the server returns an error. e.g. the artwork is deleted.
+    pub async fn try_get_artwork_ajax(&self, id: u64) -> Result<JsonValue, PixivDownloaderError> {
         self.auto_init();
         let r = self
             .client
@@ -172,21 +219,16 @@ impl PixivWebClient {
                 self.get_params(),
                 None,
             )
-            .await;
-        if r.is_none() {
-            return None;
-        }
-        let r = r.unwrap();
-        let v = self.deal_json(r).await;
-        if v.is_some() {
-            log::debug!(
-                target: "pixiv_web",
-                "{} {}",
-                gettext("Artwork's data:"),
-                v.as_ref().unwrap().pretty(2)
-            );
-        }
-        v
+            .await
+            .try_err(gettext("Failed to get artwork's data."))?;
+        let v = self.try_deal_json(r).await?;
+        log::debug!(
+            target: "pixiv_web",
+            "{} {}",
+            gettext("Artwork's data:"),
+            v.pretty(2)
+        );
+        Ok(v)
     }
 
     pub async fn get_artwork(&self, id: u64) -> Option<JsonValue> {
@@ -260,6 +302,182 @@ impl PixivWebClient {
         v
     }
 
+    /// Search artworks
+    /// * `params` - Search parameters
+    /// * `page` - Page number, starts from 1
+    pub async fn search_artworks(
+        &self,
+        params: &PixivSearchParams,
+        page: u64,
+    ) -> Option<JsonValue> {
+        self.auto_init();
+        let mut query = self.get_params().unwrap_or_else(|| json::object! {});
+        query["word"] = params.keywords.as_str().into();
+        query["s_mode"] = params.match_type.web_value().into();
+        query["mode"] = params.mode.web_value().into();
+        query["order"] = params.sort.web_value().into();
+        query["type"] = get_web_search_type(&params.types).into();
+        query["p"] = page.into();
+        if let Some(d) = params.since {
+            query["scd"] = d.format("%Y-%m-%d").to_string().into();
+        }
+        if let Some(d) = params.until {
+            query["ecd"] = d.format("%Y-%m-%d").to_string().into();
+        }
+        let r = self
+            .client
+            .get_with_param(
+                format!(
+                    "https://www.pixiv.net/ajax/search/artworks/{}",
+                    urlparse::quote(&params.keywords, b"").unwrap_or(params.keywords.clone())
+                ),
+                query,
+                None,
+            )
+            .await;
+        let r = match r {
+            Some(r) => r,
+            None => return None,
+        };
+        let v = self.deal_json(r).await;
+        if v.is_some() {
+            log::debug!(
+                target: "pixiv_web",
+                "{} {}",
+                gettext("Search results:"),
+                v.as_ref().unwrap().pretty(2)
+            );
+        }
+        v
+    }
+
+    /// Get a page of ranking. Every page contains 50 artworks.
+    /// * `mode` - Ranking mode
+    /// * `content` - Content type
+    /// * `date` - The date of ranking. [None] means the latest one.
+    /// * `page` - Page number, starts from 1
+    pub async fn get_ranking(
+        &self,
+        mode: PixivRankingMode,
+        content: PixivRankingContent,
+        date: Option<NaiveDate>,
+        page: u64,
+    ) -> Option<JsonValue> {
+        self.auto_init();
+        let mut params = self.get_params().unwrap_or_else(|| json::object! {});
+        params["format"] = "json".into();
+        params["mode"] = mode.web_value().into();
+        if let Some(c) = content.web_value() {
+            params["content"] = c.into();
+        }
+        if let Some(d) = date {
+            params["date"] = d.format("%Y%m%d").to_string().into();
+        }
+        params["p"] = page.into();
+        let r = self
+            .client
+            .get_with_param("https://www.pixiv.net/ranking.php", params, None)
+            .await;
+        let r = match r {
+            Some(r) => r,
+            None => return None,
+        };
+        let status = r.status();
+        let data = match r.text_with_charset("UTF-8").await {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!(target: "pixiv_web", "{} {}", gettext("Network error:"), e);
+                return None;
+            }
+        };
+        let v = match json::parse(data.as_str()) {
+            Ok(v) => v,
+            Err(e) => {
+                if status.as_u16() >= 400 {
+                    log::error!(target: "pixiv_web", "HTTP ERROR {}", status);
+                } else {
+                    log::error!(target: "pixiv_web", "{} {}", gettext("Failed to parse JSON:"), e);
+                }
+                return None;
+            }
+        };
+        // This API does not use the `{error, body}` format.
+        if let Some(e) = v["error"].as_str() {
+            log::error!(target: "pixiv_web", "{}", e);
+            return None;
+        }
+        if !v["contents"].is_array() {
+            log::error!(target: "pixiv_web", "{}", gettext("Failed to get ranking."));
+            return None;
+        }
+        log::debug!(
+            target: "pixiv_web",
+            "{} {}",
+            gettext("Ranking:"),
+            v.pretty(2)
+        );
+        Some(v)
+    }
+
+    /// Get a page of manga series
+    /// * `id` - Series's id
+    /// * `page` - Page number, starts from 1
+    pub async fn get_series(&self, id: u64, page: u64) -> Option<JsonValue> {
+        self.auto_init();
+        let mut params = self.get_params().unwrap_or_else(|| json::object! {});
+        params["p"] = page.into();
+        let r = self
+            .client
+            .get_with_param(
+                format!("https://www.pixiv.net/ajax/series/{}", id),
+                params,
+                None,
+            )
+            .await;
+        let r = match r {
+            Some(r) => r,
+            None => return None,
+        };
+        let v = self.deal_json(r).await;
+        if v.is_some() {
+            log::debug!(
+                target: "pixiv_web",
+                "{} {}",
+                gettext("Series's data:"),
+                v.as_ref().unwrap().pretty(2)
+            );
+        }
+        v
+    }
+
+    /// Get novel's data
+    /// * `id` - Novel's id
+    pub async fn get_novel_ajax(&self, id: u64) -> Option<JsonValue> {
+        self.auto_init();
+        let r = self
+            .client
+            .get_with_param(
+                format!("https://www.pixiv.net/ajax/novel/{}", id),
+                self.get_params(),
+                None,
+            )
+            .await;
+        if r.is_none() {
+            return None;
+        }
+        let r = r.unwrap();
+        let v = self.deal_json(r).await;
+        if v.is_some() {
+            log::debug!(
+                target: "pixiv_web",
+                "{} {}",
+                gettext("Novel's data:"),
+                v.as_ref().unwrap().pretty(2)
+            );
+        }
+        v
+    }
+
     pub fn get_params(&self) -> Option<JsonValue> {
         self.params.get_ref().clone()
     }
@@ -391,6 +609,48 @@ impl PixivWebClient {
         v
     }
 
+    /// Get the informations of user's illusts
+    /// * `uid` - User's id
+    /// * `ids` - The ids of illusts
+    pub async fn get_user_illusts_info(&self, uid: u64, ids: &[u64]) -> Option<JsonValue> {
+        self.auto_init();
+        let query = ids
+            .iter()
+            .map(|id| format!("ids%5B%5D={}", id))
+            .collect::<Vec<_>>()
+            .join("&");
+        let r = self
+            .client
+            .get_with_param(
+                format!("https://www.pixiv.net/ajax/user/{}/illusts?{}", uid, query),
+                self.get_params(),
+                None,
+            )
+            .await;
+        let r = match r {
+            Some(r) => r,
+            None => return None,
+        };
+        let v = self.deal_json(r).await;
+        if v.is_some() {
+            log::debug!(
+                target: "pixiv_web",
+                "{} {}",
+                gettext("User's illusts: "),
+                v.as_ref().unwrap().pretty(2)
+            );
+        }
+        v
+    }
+
+    /// Returns true if the logged in user is a premium user.
+    pub fn is_premium(&self) -> bool {
+        match self.data.get_ref().as_ref() {
+            Some(value) => value["userData"]["premium"].as_bool().unwrap_or(false),
+            None => false,
+        }
+    }
+
     pub fn logined(&self) -> bool {
         let data = self.data.get_ref();
         if data.is_none() {
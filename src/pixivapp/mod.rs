@@ -4,4 +4,5 @@ pub mod error;
 pub mod illust;
 pub mod illusts;
 pub mod image_urls;
+pub mod novel;
 pub mod tag;
@@ -0,0 +1,172 @@
+use super::check::CheckUnknown;
+use super::image_urls::ImageUrls;
+use super::tag::Tag;
+use json::JsonValue;
+use proc_macros::check_json_keys;
+
+#[derive(Clone)]
+pub struct PixivAppNovel {
+    data: JsonValue,
+}
+
+impl PixivAppNovel {
+    pub fn new(data: JsonValue) -> Self {
+        Self { data }
+    }
+
+    pub fn id(&self) -> Option<u64> {
+        self.data["id"].as_u64()
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.data["title"].as_str()
+    }
+
+    pub fn caption(&self) -> Option<&str> {
+        self.data["caption"].as_str()
+    }
+
+    pub fn restrict(&self) -> Option<u64> {
+        self.data["restrict"].as_u64()
+    }
+
+    pub fn x_restrict(&self) -> Option<u64> {
+        self.data["x_restrict"].as_u64()
+    }
+
+    pub fn is_original(&self) -> Option<bool> {
+        self.data["is_original"].as_bool()
+    }
+
+    pub fn image_urls(&self) -> ImageUrls {
+        ImageUrls::new(self.data["image_urls"].clone())
+    }
+
+    pub fn create_date(&self) -> Option<&str> {
+        self.data["create_date"].as_str()
+    }
+
+    pub fn tags(&self) -> Vec<Tag> {
+        let mut tags = Vec::new();
+        for tag in self.data["tags"].members() {
+            tags.push(Tag::new(tag.clone()));
+        }
+        tags
+    }
+
+    pub fn page_count(&self) -> Option<u64> {
+        self.data["page_count"].as_u64()
+    }
+
+    pub fn text_length(&self) -> Option<u64> {
+        self.data["text_length"].as_u64()
+    }
+
+    pub fn user_id(&self) -> Option<u64> {
+        self.data["user"]["id"].as_u64()
+    }
+
+    pub fn user_name(&self) -> Option<&str> {
+        self.data["user"]["name"].as_str()
+    }
+
+    pub fn user_account(&self) -> Option<&str> {
+        self.data["user"]["account"].as_str()
+    }
+
+    pub fn series_id(&self) -> Option<u64> {
+        self.data["series"]["id"].as_u64()
+    }
+
+    pub fn series_title(&self) -> Option<&str> {
+        self.data["series"]["title"].as_str()
+    }
+
+    pub fn total_bookmarks(&self) -> Option<u64> {
+        self.data["total_bookmarks"].as_u64()
+    }
+
+    pub fn total_view(&self) -> Option<u64> {
+        self.data["total_view"].as_u64()
+    }
+
+    pub fn total_comments(&self) -> Option<u64> {
+        self.data["total_comments"].as_u64()
+    }
+
+    pub fn visible(&self) -> Option<bool> {
+        self.data["visible"].as_bool()
+    }
+
+    pub fn novel_ai_type(&self) -> Option<u64> {
+        self.data["novel_ai_type"].as_u64()
+    }
+}
+
+impl CheckUnknown for PixivAppNovel {
+    fn check_unknown(&self) -> Result<(), String> {
+        check_json_keys!(
+            "id"+,
+            "title"+,
+            "caption"+,
+            "restrict"+,
+            "x_restrict"+,
+            "is_original"+,
+            "image_urls": ["square_medium", "medium", "large"],
+            "create_date"+,
+            "tags",
+            "page_count"+,
+            "text_length"+,
+            "user": [
+                "id"+,
+                "name"+,
+                "account"+,
+                "profile_image_urls": ["medium"],
+                "is_followed"
+            ],
+            "series",
+            "is_bookmarked",
+            "total_bookmarks"+,
+            "total_view"+,
+            "visible"+,
+            "total_comments"+,
+            "is_muted",
+            "is_mypixiv_only",
+            "is_x_restricted",
+            "novel_ai_type"+,
+            "comment_access_control"
+        );
+        for i in self.tags() {
+            i.check_unknown()?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for PixivAppNovel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PixivAppNovel")
+            .field("id", &self.id())
+            .field("title", &self.title())
+            .field("caption", &self.caption())
+            .field("restrict", &self.restrict())
+            .field("x_restrict", &self.x_restrict())
+            .field("is_original", &self.is_original())
+            .field("image_urls", &self.image_urls())
+            .field("create_date", &self.create_date())
+            .field("tags", &self.tags())
+            .field("page_count", &self.page_count())
+            .field("text_length", &self.text_length())
+            .field("user_id", &self.user_id())
+            .field("user_name", &self.user_name())
+            .field("user_account", &self.user_account())
+            .field("series_id", &self.series_id())
+            .field("series_title", &self.series_title())
+            .field("total_bookmarks", &self.total_bookmarks())
+            .field("total_view", &self.total_view())
+            .field("total_comments", &self.total_comments())
+            .field("visible", &self.visible())
+            .field("novel_ai_type", &self.novel_ai_type())
+            .finish_non_exhaustive()
+    }
+}
@@ -1,4 +1,5 @@
 use super::tg_type::*;
+use crate::bandwidth::{get_limiter, BandwidthLimitType};
 #[cfg(test)]
 use crate::formdata::FormDataPartBuilder;
 use crate::formdata::{FormData, FormDataPart};
@@ -63,6 +64,13 @@ impl BotapiClient {
         }
     }
 
+    /// Send a multipart request. The form is sent in chunks which respect
+    /// the bandwidth limit.
+    async fn post_multipart(&self, url: String, mut form: FormData) -> Option<wreq::Response> {
+        form.set_limiter(get_limiter(BandwidthLimitType::Telegram));
+        self.client.post_multipart(url, None, form).await
+    }
+
     pub fn is_custom(&self) -> bool {
         self.cfg.base != "https://api.telegram.org"
     }
@@ -180,10 +188,8 @@ impl BotapiClient {
         }
         log::debug!(target: "botapi_client", "Request Form: {:?}", form);
         let re = self
-            .client
             .post_multipart(
                 format!("{}/bot{}/sendAnimation", self.cfg.base, self.cfg.token),
-                None,
                 form,
             )
             .await
@@ -283,10 +289,8 @@ impl BotapiClient {
         }
         log::debug!(target: "botapi_client", "Request Form: {:?}", form);
         let re = self
-            .client
             .post_multipart(
                 format!("{}/bot{}/sendDocument", self.cfg.base, self.cfg.token),
-                None,
                 form,
             )
             .await
@@ -381,10 +385,8 @@ impl BotapiClient {
         }
         log::debug!(target: "botapi_client", "Request Form: {:?}", form);
         let re = self
-            .client
             .post_multipart(
                 format!("{}/bot{}/sendPhoto", self.cfg.base, self.cfg.token),
-                None,
                 form,
             )
             .await
@@ -448,10 +450,8 @@ impl BotapiClient {
         }
         log::debug!(target: "botapi_client", "Request Form: {:?}", form);
         let re = self
-            .client
             .post_multipart(
                 format!("{}/bot{}/sendMediaGroup", self.cfg.base, self.cfg.token),
-                None,
                 form,
             )
             .await
@@ -663,10 +663,8 @@ impl BotapiClient {
         }
         log::debug!(target: "botapi_client", "Request Form: {:?}", form);
         let re = self
-            .client
             .post_multipart(
                 format!("{}/bot{}/sendVideo", self.cfg.base, self.cfg.token),
-                None,
                 form,
             )
             .await
@@ -0,0 +1,212 @@
+use crate::ext::rw_lock::GetRwLock;
+use crate::gettext;
+use crate::opthelper::get_helper;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use wreq::header::RETRY_AFTER;
+use wreq::Response;
+
+lazy_static! {
+    #[doc(hidden)]
+    static ref RATE_LIMITER: RateLimiter = RateLimiter::new();
+}
+
+/// The first extra interval added after the host starts rejecting requests
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The maximum extra interval added between two requests
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// The count of 403 responses in a row which is treated as rate limiting
+const FORBIDDEN_BURST: u32 = 3;
+
+#[derive(Debug)]
+struct HostState {
+    /// The time when the next request can be sent
+    next: Instant,
+    /// The extra interval added because the host rejected requests recently
+    backoff: Duration,
+    /// The count of 403 responses in a row
+    forbidden: u32,
+}
+
+impl HostState {
+    fn new(now: Instant) -> Self {
+        Self {
+            next: now,
+            backoff: Duration::ZERO,
+            forbidden: 0,
+        }
+    }
+}
+
+/// Per-host request pacing shared by all web clients.
+#[derive(Debug)]
+struct RateLimiter {
+    hosts: RwLock<HashMap<String, HostState>>,
+}
+
+impl RateLimiter {
+    fn new() -> Self {
+        Self {
+            hosts: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Reserve a time slot for a request. Returns the time to wait.
+    /// * `host` - The host name
+    /// * `interval` - The minimum interval between two requests
+    /// * `now` - Current time
+    fn reserve(&self, host: &str, interval: Duration, now: Instant) -> Duration {
+        let mut hosts = self.hosts.get_mut();
+        let state = hosts
+            .entry(host.to_owned())
+            .or_insert_with(|| HostState::new(now));
+        let start = state.next.max(now);
+        state.next = start + interval + state.backoff;
+        start - now
+    }
+
+    /// Update the state of the host with the status of the response.
+    /// * `host` - The host name
+    /// * `status` - The HTTP status code
+    /// * `retry_after` - The time to wait which is required by the server
+    /// * `now` - Current time
+    fn update(&self, host: &str, status: u16, retry_after: Option<Duration>, now: Instant) {
+        let mut hosts = self.hosts.get_mut();
+        let state = hosts
+            .entry(host.to_owned())
+            .or_insert_with(|| HostState::new(now));
+        if status == 403 {
+            state.forbidden += 1;
+        } else {
+            state.forbidden = 0;
+        }
+        if status == 429 || state.forbidden >= FORBIDDEN_BURST {
+            state.backoff = if state.backoff.is_zero() {
+                INITIAL_BACKOFF
+            } else {
+                (state.backoff * 2).min(MAX_BACKOFF)
+            };
+            state.next = state.next.max(now + state.backoff);
+        } else if status < 400 && !state.backoff.is_zero() {
+            state.backoff /= 2;
+            if state.backoff < INITIAL_BACKOFF {
+                state.backoff = Duration::ZERO;
+            }
+        }
+        if let Some(retry_after) = retry_after {
+            state.next = state.next.max(now + retry_after);
+        }
+    }
+}
+
+/// Parse the value of `Retry-After` header.
+/// * `value` - Seconds or a HTTP date
+/// * `now` - Current time
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    match value.parse::<u64>() {
+        Ok(secs) => Some(Duration::from_secs(secs)),
+        Err(_) => match DateTime::parse_from_rfc2822(value) {
+            Ok(date) => Some(
+                (date.with_timezone(&Utc) - now)
+                    .to_std()
+                    .unwrap_or(Duration::ZERO),
+            ),
+            Err(_) => None,
+        },
+    }
+}
+
+/// Wait until a request to the host is allowed.
+/// * `host` - The host name
+pub async fn acquire(host: &str) {
+    let interval = get_helper().rate_limit(host).unwrap_or(Duration::ZERO);
+    let wait = RATE_LIMITER.reserve(host, interval, Instant::now());
+    if !wait.is_zero() {
+        log::debug!(target: "webclient", "Wait {}s before requesting {}", wait.as_secs_f64(), host);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Update the rate limit state of the host with the response.
+/// * `host` - The host name
+/// * `r` - The response
+pub fn handle_response(host: &str, r: &Response) {
+    let status = r.status().as_u16();
+    let retry_after = r
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| parse_retry_after(v, Utc::now()));
+    if status == 429 {
+        log::warn!("{} {}", gettext("Too many requests to the host:"), host);
+    }
+    RATE_LIMITER.update(host, status, retry_after, Instant::now());
+}
+
+/// Returns true if the server asks to send the request again later.
+/// * `r` - The response
+pub fn is_too_many_requests(r: &Option<Response>) -> bool {
+    match r {
+        Some(r) => r.status().as_u16() == 429,
+        None => false,
+    }
+}
+
+#[test]
+fn test_parse_retry_after() {
+    let now = DateTime::parse_from_rfc3339("2015-10-21T07:27:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    assert_eq!(
+        parse_retry_after(" 120", now),
+        Some(Duration::from_secs(120))
+    );
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+        Some(Duration::from_secs(60))
+    );
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_retry_after("soon", now), None);
+}
+
+#[test]
+fn test_rate_limiter() {
+    let l = RateLimiter::new();
+    let now = Instant::now();
+    let s = Duration::from_secs;
+    assert_eq!(l.reserve("a", Duration::ZERO, now), Duration::ZERO);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), Duration::ZERO);
+    assert_eq!(l.reserve("b", s(2), now), Duration::ZERO);
+    assert_eq!(l.reserve("b", s(2), now), s(2));
+    assert_eq!(l.reserve("b", s(2), now + s(1)), s(3));
+    // Retry-After is honoured.
+    l.update("a", 503, Some(s(30)), now);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), s(30));
+    // Too many requests doubles the backoff.
+    let now = now + s(100);
+    l.update("a", 429, None, now);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), s(1));
+    l.update("a", 429, None, now);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), s(2));
+    // Successful responses reduce the backoff.
+    let now = now + s(100);
+    l.update("a", 200, None, now);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), Duration::ZERO);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), s(1));
+    l.update("a", 200, None, now);
+    let now = now + s(100);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), Duration::ZERO);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), Duration::ZERO);
+    // Only a burst of 403 is treated as rate limiting.
+    l.update("a", 403, None, now);
+    l.update("a", 403, None, now);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), Duration::ZERO);
+    l.update("a", 403, None, now);
+    assert_eq!(l.reserve("a", Duration::ZERO, now), s(1));
+}
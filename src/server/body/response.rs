@@ -1,17 +1,38 @@
+use crate::bandwidth::BandwidthLimiter;
 use crate::error::PixivDownloaderError;
 use hyper::body::HttpBody;
-use wreq::Response;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use tokio::time::Sleep;
+use wreq::Response;
 
 pub struct ResponseBody {
     res: Response,
+    /// The bandwidth limiter
+    limiter: Option<&'static BandwidthLimiter>,
+    /// Wait before reading the next chunk if the bandwidth limit is exceeded.
+    delay: Option<Pin<Box<Sleep>>>,
 }
 
 impl ResponseBody {
     pub fn new(res: Response) -> Self {
-        Self { res }
+        Self {
+            res,
+            limiter: None,
+            delay: None,
+        }
+    }
+
+    /// Create a new body which respects the bandwidth limit.
+    /// * `res` - The response
+    /// * `limiter` - The bandwidth limiter
+    pub fn with_limiter(res: Response, limiter: &'static BandwidthLimiter) -> Self {
+        Self {
+            res,
+            limiter: Some(limiter),
+            delay: None,
+        }
     }
 }
 
@@ -23,9 +44,25 @@ impl HttpBody for ResponseBody {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        if let Some(delay) = self.delay.as_mut() {
+            match delay.as_mut().poll(cx) {
+                Poll::Ready(_) => {
+                    self.delay.take();
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
         match Pin::new(&mut Box::pin(self.res.chunk())).poll(cx) {
             Poll::Ready(f) => match f {
-                Ok(Some(data)) => Poll::Ready(Some(Ok(data))),
+                Ok(Some(data)) => {
+                    if let Some(limiter) = self.limiter {
+                        let wait = limiter.take(data.len() as u64);
+                        if !wait.is_zero() {
+                            self.delay.replace(Box::pin(tokio::time::sleep(wait)));
+                        }
+                    }
+                    Poll::Ready(Some(Ok(data)))
+                }
                 Ok(None) => Poll::Ready(None),
                 Err(e) => Poll::Ready(Some(Err(PixivDownloaderError::from(e)))),
             },
@@ -1,4 +1,5 @@
 use super::super::preclude::*;
+use crate::bandwidth::{get_limiter, BandwidthLimitType};
 use crate::webclient::WebClient;
 use http::Uri;
 
@@ -90,7 +91,8 @@ impl ResponseFor<Body, Pin<Box<HttpBodyType>>> for ProxyPixivContext {
                 None => {}
             }
         }
-        return Ok(builder.body::<Pin<Box<HttpBodyType>>>(Box::pin(ResponseBody::new(re)))?);
+        let body = ResponseBody::with_limiter(re, get_limiter(BandwidthLimitType::Proxy));
+        return Ok(builder.body::<Pin<Box<HttpBodyType>>>(Box::pin(body))?);
     }
 }
 
@@ -267,7 +267,7 @@ impl<'a> RunContext<'a> {
 
     pub async fn app_run(&self) -> Result<(), PixivDownloaderError> {
         let app = self.ctx.pixiv_app_client().await;
-        let app_data = app.get_user_illusts(self.uid).await?;
+        let app_data = app.get_user_illusts(self.uid, "illust").await?;
         match self.send_mode {
             Some(m) => {
                 if m.is_all() {
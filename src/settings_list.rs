@@ -3,11 +3,21 @@ use crate::db::check_db_config;
 use crate::ext::json::FromJson;
 use crate::ext::use_or_not::UseOrNot;
 use crate::gettext;
+use crate::opt::artwork_filter::{check_age_filter, check_tag_list};
+use crate::opt::image_size::check_image_size;
+#[cfg(feature = "exif")]
+use crate::opt::metadata_family::check_metadata_families;
+use crate::opt::page_range::check_page_range;
 use crate::opt::author_name_filter::check_author_name_filters;
 use crate::opt::crf::check_crf;
+use crate::opt::date::parse_date_time;
 use crate::opt::header_map::check_header_map;
+use crate::opt::path_template::{check_path_sanitize_mode, check_path_template};
 use crate::opt::proxy::check_proxy;
-use crate::opt::size::parse_u32_size;
+use crate::opt::rate_limit::check_host_rate_limits;
+use crate::opt::size::{parse_size, parse_u32_size};
+use crate::opt::time_range::check_time_ranges;
+use crate::opt::ugoira_format::{check_gif_dither, check_ugoira_formats};
 use crate::retry_interval::check_retry_interval;
 #[cfg(feature = "server")]
 use crate::server::cors::parse_cors_entries;
@@ -26,10 +36,13 @@ pub fn get_settings_list() -> Vec<SettingDes> {
         SettingDes::new("language", gettext("The language of translated tags."), JsonValueType::Str, None).unwrap(),
         SettingDes::new("retry", gettext("Max retry count if request failed."), JsonValueType::Number, Some(check_i64)).unwrap(),
         SettingDes::new("retry-interval", gettext("The interval (in seconds) between two retries."), JsonValueType::Multiple, Some(check_retry_interval)).unwrap(),
+        SettingDes::new("rate-limit", gettext("The minimum interval (in milliseconds) between two requests to the same host. For example: {\"www.pixiv.net\": 1000, \"app-api.pixiv.net\": 1000, \"api.fanbox.cc\": 1000, \"i.pximg.net\": 200}"), JsonValueType::Object, Some(check_host_rate_limits)).unwrap(),
         SettingDes::new("use-webpage", gettext("Use data from webpage first."), JsonValueType::Boolean, None).unwrap(),
         SettingDes::new("author-name-filters", gettext("Remove the part which after these parttens."), JsonValueType::Array, Some(check_author_name_filters)).unwrap(),
         #[cfg(feature = "exif")]
         SettingDes::new("update-exif", gettext("Add/Update exif information to image files even when overwrite are disabled."), JsonValueType::Boolean, None).unwrap(),
+        #[cfg(feature = "exif")]
+        SettingDes::new("metadata-families", gettext("The families of metadata written to image files. Available values: exif, xmp, iptc. Separated by commas."), JsonValueType::Str, Some(check_metadata_families)).unwrap(),
         SettingDes::new("progress-bar-template", gettext("Progress bar's template. See <here> for more informations.").replace("<here>", "https://docs.rs/indicatif/latest/indicatif/#templates").as_str(), JsonValueType::Str, Some(check_nonempty_str)).unwrap(),
         SettingDes::new("use-progress-bar", gettext("Whether to enable progress bar."), JsonValueType::Multiple, Some(check_user_or_not)).unwrap(),
         SettingDes::new("download-multiple-files", gettext("Download multiple files at the same time."), JsonValueType::Boolean, None).unwrap(),
@@ -55,6 +68,8 @@ pub fn get_settings_list() -> Vec<SettingDes> {
         SettingDes::new("user-agent", gettext("The User-Agent header."), JsonValueType::Str, None).unwrap(),
         SettingDes::new("x264-crf", gettext("The Constant Rate Factor when converting ugoira(GIF) to video."), JsonValueType::Number, Some(check_crf)).unwrap(),
         SettingDes::new("ugoira-max-fps", gettext("The max fps when converting ugoira(GIF) to video."), JsonValueType::Number, Some(check_ugoira_max_fps)).unwrap(),
+        SettingDes::new("ugoira-formats", gettext("The output formats when converting ugoira(GIF), separated by commas. Supported formats: mp4, gif, webp, apng."), JsonValueType::Str, Some(check_ugoira_formats)).unwrap(),
+        SettingDes::new("ugoira-gif-dither", gettext("The dither algorithm used when converting ugoira(GIF) to GIF. Supported values: bayer, heckbert, floyd_steinberg, sierra2, sierra2_4a, sierra3, burkes, atkinson, none."), JsonValueType::Str, Some(check_gif_dither)).unwrap(),
         SettingDes::new("fanbox-page-number", gettext("Use page number for pictures' file name in fanbox."), JsonValueType::Boolean, None).unwrap(),
         #[cfg(feature = "server")]
         SettingDes::new("cors-allow-all", gettext("Whether to allow all domains to send CORS requests."), JsonValueType::Boolean, None).unwrap(),
@@ -75,9 +90,36 @@ pub fn get_settings_list() -> Vec<SettingDes> {
         SettingDes::new("ugoira-cli", gettext("Whether to use ugoira cli."), JsonValueType::Boolean, None).unwrap(),
         SettingDes::new("connect-timeout", gettext("Set a timeout in milliseconds for only the connect phase of a client."), JsonValueType::Number, Some(check_nonzero_u64)).unwrap(),
         SettingDes::new("client-timeout", gettext("Set request timeout in milliseconds. The timeout is applied from when the request starts connecting until the response body has finished. Not used for downloader."), JsonValueType::Number, Some(check_nonzero_u64)).unwrap(),
+        SettingDes::new("download-chunk-timeout", gettext("Set the timeout in milliseconds when waiting for the next chunk of data in downloader."), JsonValueType::Number, Some(check_nonzero_u64)).unwrap(),
+        SettingDes::new("download-verify", gettext("Verify the files after downloaded by checking the size and the file type."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("download-verify-full", gettext("Check the whole structure of JPEG, PNG, GIF, WebP and ZIP files when verifying to find truncated files."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("sha256-file", gettext("Write the SHA-256 hash of downloaded files to .sha256 files."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("xmp-sidecar", gettext("Write the metadata of downloaded files to .xmp sidecar files."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("download-bandwidth-limit", gettext("The maximum download speed of all downloads per second. 0 means unlimited."), JsonValueType::Multiple, Some(check_parse_size)).unwrap(),
+        #[cfg(feature = "server")]
+        SettingDes::new("proxy-bandwidth-limit", gettext("The maximum speed of the pixiv proxy per second. 0 means unlimited."), JsonValueType::Multiple, Some(check_parse_size)).unwrap(),
+        SettingDes::new("telegram-bandwidth-limit", gettext("The maximum upload speed to Telegram per second. 0 means unlimited."), JsonValueType::Multiple, Some(check_parse_size)).unwrap(),
+        SettingDes::new("bandwidth-schedule", gettext("Only apply the bandwidth limits in these time ranges. For example: 08:00-12:00,22:00-06:00"), JsonValueType::Str, Some(check_time_ranges)).unwrap(),
         SettingDes::new("ffmpeg", gettext("The path to ffmpeg executable."), JsonValueType::Str, None).unwrap(),
         SettingDes::new("browser", gettext("The browser emulation"), JsonValueType::Str, Some(check_browser)).unwrap(),
         SettingDes::new("os", gettext("The OS emulation"), JsonValueType::Str, Some(check_os)).unwrap(),
+        SettingDes::new("artwork-path-template", gettext("The template of the path of downloaded artworks' files. Relative to the download base."), JsonValueType::Str, Some(check_path_template)).unwrap(),
+        SettingDes::new("artwork-group-by-author", gettext("Put artworks into per-author folders."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("bookmarks-stop-at-downloaded", gettext("Stop at the first bookmark which is already downloaded."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("fanbox-creator-posts", gettext("Download all posts when downloading a fanbox creator."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("fanbox-skip-restricted", gettext("Skip the fanbox posts which are restricted. If disabled, the data of restricted posts will be saved."), JsonValueType::Boolean, None).unwrap(),
+        #[cfg(feature = "db")]
+        SettingDes::new("archive", gettext("Skip the artworks which are already recorded in download history before fetching their data."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("path-sanitize", gettext("How to sanitize the path of downloaded files."), JsonValueType::Str, Some(check_path_sanitize_mode)).unwrap(),
+        SettingDes::new("exclude-ai", gettext("Skip AI-generated artworks."), JsonValueType::Boolean, None).unwrap(),
+        SettingDes::new("age-filter", gettext("Filter artworks by age restriction. Available values: all, safe, r18."), JsonValueType::Str, Some(check_age_filter)).unwrap(),
+        SettingDes::new("include-tags", gettext("Only download the artworks which have at least one of these tags. Translated tags are also matched. Artworks with unknown tags are not filtered."), JsonValueType::Array, Some(check_tag_list)).unwrap(),
+        SettingDes::new("exclude-tags", gettext("Skip the artworks which have any of these tags. Translated tags are also matched. Artworks with unknown tags are not filtered."), JsonValueType::Array, Some(check_tag_list)).unwrap(),
+        SettingDes::new("min-bookmarks", gettext("Skip the artworks which have fewer bookmarks than this. Artworks with unknown bookmark count are not filtered."), JsonValueType::Number, Some(check_u64)).unwrap(),
+        SettingDes::new("upload-since", gettext("Only download artworks uploaded after this time. Format: YYYY-MM-DD or RFC 3339. Artworks with unknown upload date are not filtered."), JsonValueType::Str, Some(check_date_time)).unwrap(),
+        SettingDes::new("upload-until", gettext("Only download artworks uploaded before this time. Format: YYYY-MM-DD or RFC 3339. Artworks with unknown upload date are not filtered."), JsonValueType::Str, Some(check_date_time)).unwrap(),
+        SettingDes::new("pages", gettext("The pages to download in multi-page artworks. Page numbers start from 1 and negative numbers are counted from the last page. For example: 1-3,7,-1"), JsonValueType::Str, Some(check_page_range)).unwrap(),
+        SettingDes::new("image-size", gettext("The size variant of the pictures to download. Available values: original, regular, medium, square_medium."), JsonValueType::Str, Some(check_image_size)).unwrap(),
     ]
 }
 
@@ -95,6 +137,13 @@ fn check_os(obj: &JsonValue) -> bool {
     }
 }
 
+fn check_date_time(obj: &JsonValue) -> bool {
+    match obj.as_str() {
+        Some(s) => parse_date_time(s, false).is_ok(),
+        None => false,
+    }
+}
+
 fn check_i64(obj: &JsonValue) -> bool {
     let r = obj.as_i64();
     r.is_some()
@@ -140,6 +189,11 @@ fn check_nonzero_u64(obj: &JsonValue) -> bool {
     }
 }
 
+#[inline]
+fn check_parse_size(obj: &JsonValue) -> bool {
+    parse_size(obj).is_some()
+}
+
 #[inline]
 fn check_parse_size_u32(obj: &JsonValue) -> bool {
     parse_u32_size(obj).is_some()
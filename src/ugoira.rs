@@ -12,6 +12,7 @@ use crate::ext::rawhandle::ToRawHandle;
 use crate::ext::subprocess::PopenAsyncExt;
 use crate::ext::try_err::TryErr;
 use crate::gettext;
+use crate::opt::ugoira_format::{GifDither, UgoiraFormat};
 use std::collections::HashMap;
 use std::convert::AsRef;
 use std::default::Default;
@@ -49,6 +50,8 @@ const UGOIRA_NO_AVAILABLE_ENCODER: c_int = 10;
 const UGOIRA_OPEN_FILE: c_int = 11;
 const UGOIRA_UNABLE_SCALE: c_int = 12;
 const UGOIRA_JSON_ERROR: c_int = 13;
+const UGOIRA_INVALID_FORMAT: c_int = 14;
+const UGOIRA_UNABLE_FILTER: c_int = 15;
 
 #[derive(Debug, derive_more::From)]
 pub enum UgoiraError {
@@ -145,6 +148,8 @@ impl UgoiraCodeError {
             UGOIRA_OPEN_FILE => gettext("Failed to open output file."),
             UGOIRA_UNABLE_SCALE => gettext("Unable to scale image."),
             UGOIRA_JSON_ERROR => gettext("Failed to parse JSON file."),
+            UGOIRA_INVALID_FORMAT => gettext("Unknown ugoira format."),
+            UGOIRA_UNABLE_FILTER => gettext("Unable to create filters."),
             _ => gettext("Unknown error."),
         }
     }
@@ -537,6 +542,63 @@ pub fn convert_ugoira_to_mp4<
     Ok(())
 }
 
+#[cfg(feature = "ugoira")]
+/// Convert ugoira to animated image. The delay of every frame is kept.
+/// * `format` - The output format. MP4 is not supported, use [convert_ugoira_to_mp4] instead.
+/// * `opts` - Options passed to the encoder
+pub fn convert_ugoira_to_animation<
+    S: AsRef<OsStr> + ?Sized,
+    D: AsRef<OsStr> + ?Sized,
+    F: AsRef<UgoiraFrames> + ?Sized,
+    O: AsRef<AVDict> + ?Sized,
+    M: AsRef<AVDict> + ?Sized,
+>(
+    src: &S,
+    dest: &D,
+    frames: &F,
+    format: UgoiraFormat,
+    opts: &O,
+    metadata: &M,
+) -> Result<(), UgoiraError> {
+    let format = match format {
+        UgoiraFormat::Mp4 => {
+            return Err(UgoiraError::from(UgoiraCodeError::from(
+                UGOIRA_INVALID_FORMAT,
+            )));
+        }
+        UgoiraFormat::Gif => _ugoira::UGOIRA_FORMAT_GIF,
+        UgoiraFormat::Webp => _ugoira::UGOIRA_FORMAT_WEBP,
+        UgoiraFormat::Apng => _ugoira::UGOIRA_FORMAT_APNG,
+    };
+    let src = src.as_ref();
+    let dest = dest.as_ref();
+    let frames = frames.as_ref();
+    let opts = opts.as_ref();
+    let metadata = metadata.as_ref();
+    let src = src
+        .to_str()
+        .try_err(gettext("Failed to convert path."))?
+        .to_cstr()?;
+    let dest = dest
+        .to_str()
+        .try_err(gettext("Failed to convert path."))?
+        .to_cstr()?;
+    let re = unsafe {
+        _ugoira::convert_ugoira_to_animation(
+            src.as_ptr(),
+            dest.as_ptr(),
+            frames.to_const_handle(),
+            format as c_int,
+            opts.to_const_handle(),
+            metadata.to_const_handle(),
+        )
+    };
+    if re.code != 0 {
+        Err(re)?;
+    }
+    Ok(())
+}
+
 pub async fn convert_ugoira_to_mp4_subprocess<
     B: AsRef<OsStr> + ?Sized,
     S: AsRef<OsStr> + ?Sized,
@@ -581,6 +643,53 @@ pub async fn convert_ugoira_to_mp4_subprocess<
         }
         None => {}
     }
+    run_ugoira_cli(argv).await
+}
+
+/// Convert ugoira to animated image with ugoira cli. The delay of every frame is kept.
+/// * `format` - The output format. MP4 is not supported, use [convert_ugoira_to_mp4_subprocess] instead.
+pub async fn convert_ugoira_to_animation_subprocess<
+    B: AsRef<OsStr> + ?Sized,
+    S: AsRef<OsStr> + ?Sized,
+    D: AsRef<OsStr> + ?Sized,
+    J: AsRef<OsStr> + ?Sized,
+>(
+    base: &B,
+    src: &S,
+    dest: &D,
+    json: &J,
+    format: UgoiraFormat,
+    metadata: HashMap<String, String>,
+    dither: Option<GifDither>,
+) -> Result<(), UgoiraError> {
+    if format == UgoiraFormat::Mp4 {
+        return Err(UgoiraError::from(UgoiraCodeError::from(
+            UGOIRA_INVALID_FORMAT,
+        )));
+    }
+    let mut argv: Vec<OsString> = Vec::with_capacity(5);
+    argv.push(base.as_ref().to_owned());
+    argv.push(src.as_ref().to_owned());
+    argv.push(dest.as_ref().to_owned());
+    argv.push(json.as_ref().to_owned());
+    argv.push(format!("-F{}", format.as_str()).into());
+    for (k, v) in metadata {
+        argv.push("-m".into());
+        argv.push(format!("{}={}", k, v).into());
+    }
+    match dither {
+        Some(d) => {
+            if format == UgoiraFormat::Gif {
+                argv.push("--dither".into());
+                argv.push(d.as_str().into());
+            }
+        }
+        None => {}
+    }
+    run_ugoira_cli(argv).await
+}
+
+async fn run_ugoira_cli(argv: Vec<OsString>) -> Result<(), UgoiraError> {
     log::debug!(target: "ugoira_cli", "Command line: {:?}", argv);
     let mut p = Popen::create(
         &argv,
@@ -696,6 +805,100 @@ fn test_convert_ugoira_to_mp4() -> Result<(), UgoiraError> {
     )
 }
 
+#[cfg(feature = "ugoira")]
+#[test]
+fn test_convert_ugoira_to_animation() -> Result<(), UgoiraError> {
+    let frames_path = Path::new("./testdata/74841737_frames.json");
+    if !frames_path.exists() {
+        Err("Can not find frames file.")?;
+    }
+    let mut f = File::open(frames_path).unwrap();
+    let mut s = String::from("");
+    f.read_to_string(&mut s).unwrap();
+    let frames = UgoiraFrames::from_json(json::parse(s.as_str()).unwrap())?;
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let mut metadata = AVDict::new();
+    metadata.set("title", "動く nachoneko :3", None).unwrap();
+    let options = AVDict::new();
+    assert!(convert_ugoira_to_animation(
+        "./testdata/74841737_ugoira600x600.zip",
+        "./test/74841737.mp4",
+        &frames,
+        UgoiraFormat::Mp4,
+        &options,
+        &metadata,
+    )
+    .is_err());
+    for format in [UgoiraFormat::Gif, UgoiraFormat::Apng] {
+        convert_ugoira_to_animation(
+            "./testdata/74841737_ugoira600x600.zip",
+            &format!("./test/74841737_anim.{}", format.extension()),
+            &frames,
+            format,
+            &options,
+            &metadata,
+        )?;
+    }
+    let mut options = AVDict::new();
+    options
+        .set("dither", GifDither::None.as_str(), None)
+        .unwrap();
+    convert_ugoira_to_animation(
+        "./testdata/74841737_ugoira600x600.zip",
+        "./test/74841737_anim_nodither.gif",
+        &frames,
+        UgoiraFormat::Gif,
+        &options,
+        &metadata,
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "ugoira")]
+#[test]
+fn test_convert_ugoira_to_webp() -> Result<(), UgoiraError> {
+    let frames_path = Path::new("./testdata/74841737_frames.json");
+    if !frames_path.exists() {
+        Err("Can not find frames file.")?;
+    }
+    let mut f = File::open(frames_path).unwrap();
+    let mut s = String::from("");
+    f.read_to_string(&mut s).unwrap();
+    let frames = UgoiraFrames::from_json(json::parse(s.as_str()).unwrap())?;
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let metadata = AVDict::new();
+    let options = AVDict::new();
+    let target = Path::new("./test/74841737_anim.webp");
+    match convert_ugoira_to_animation(
+        "./testdata/74841737_ugoira600x600.zip",
+        target,
+        &frames,
+        UgoiraFormat::Webp,
+        &options,
+        &metadata,
+    ) {
+        Ok(_) => {}
+        Err(UgoiraError::CodeError(e)) if e.code == UGOIRA_NO_AVAILABLE_ENCODER => {
+            println!("FFmpeg is not built with libwebp, skip test.");
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    }
+    let mut data = Vec::new();
+    File::open(target).unwrap().read_to_end(&mut data).unwrap();
+    assert_eq!(&data[0..4], b"RIFF");
+    assert_eq!(&data[8..12], b"WEBP");
+    Ok(())
+}
+
 #[proc_macros::async_timeout_test(120s)]
 #[tokio::test(flavor = "multi_thread")]
 async fn test_convert_ugoira_to_mp4_subprocess() -> Result<(), UgoiraError> {
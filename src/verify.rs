@@ -0,0 +1,196 @@
+use crate::data::data::PixivArtworkContext;
+use crate::db::open_and_init_database;
+use crate::db::PixivDownloaderDb;
+use crate::db::PixivFile;
+use crate::download::download_artwork;
+use crate::downloader::verify::file_sha256;
+use crate::downloader::verify::get_sha256_file_path;
+use crate::downloader::verify::read_sha256_file;
+use crate::downloader::verify::verify_file;
+use crate::error::PixivDownloaderError;
+use crate::gettext;
+use crate::opthelper::get_helper;
+use crate::pixiv_app::PixivAppClient;
+use crate::pixiv_web::PixivWebClient;
+use crate::task_manager::TaskManager;
+use crate::Main;
+use proc_macros::print_error;
+use std::fs::remove_file;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Check a file in download history. Returns the reason if the file is broken.
+/// * `file` - The file
+fn check_pixiv_file(file: &PixivFile) -> Option<String> {
+    let path = Path::new(&file.path);
+    if !path.exists() {
+        return Some(gettext("The file does not exist.").to_owned());
+    }
+    if let Err(e) = verify_file(path, 0, None, true) {
+        return Some(e.to_string());
+    }
+    let expected = match &file.sha256 {
+        Some(hash) => Some(hash.clone()),
+        None => read_sha256_file(path),
+    };
+    if let Some(expected) = expected {
+        match file_sha256(path) {
+            Ok(hash) => {
+                if hash != expected {
+                    return Some(gettext("The SHA-256 hash does not match.").to_owned());
+                }
+            }
+            Err(e) => return Some(e.to_string()),
+        }
+    }
+    None
+}
+
+/// Remove the broken file, its SHA-256 hash file and its record in download history.
+/// The record is removed so the artwork is downloaded again even if archive mode is enabled.
+/// * `db` - Database
+/// * `file` - The broken file
+async fn remove_broken_file(db: &Box<dyn PixivDownloaderDb + Send + Sync>, file: &PixivFile) {
+    let path = Path::new(&file.path);
+    for p in [path.to_owned(), get_sha256_file_path(path)] {
+        if p.exists() {
+            if let Err(e) = remove_file(&p) {
+                log::warn!(
+                    "{} {} {}",
+                    gettext("Warning: Failed to remove file:"),
+                    p.to_str().unwrap_or("(null)"),
+                    e
+                );
+            }
+        }
+    }
+    if let Err(e) = db.delete_pixiv_file(file.id, file.page).await {
+        log::warn!(
+            "{} {}",
+            gettext("Warning: Failed to remove file from download history:"),
+            e
+        );
+    }
+}
+
+impl Main {
+    /// Verify all files in download history and download the artworks
+    /// which have broken files again.
+    pub async fn verify(&mut self) -> i32 {
+        let db = Arc::new(print_error!(
+            gettext("Failed to open database:"),
+            open_and_init_database(get_helper().db()).await,
+            1
+        ));
+        let files = print_error!(
+            gettext("Failed to get files in download history:"),
+            db.get_all_pixiv_files().await,
+            1
+        );
+        let mut ids = Vec::new();
+        let mut broken = 0usize;
+        for file in files.iter() {
+            if let Some(reason) = check_pixiv_file(file) {
+                log::warn!("{} {} ({})", gettext("Broken file:"), file.path, reason);
+                remove_broken_file(&db, file).await;
+                broken += 1;
+                if !ids.contains(&file.id) {
+                    ids.push(file.id);
+                }
+            }
+        }
+        log::info!(
+            "{} {}, {} {}",
+            gettext("Verified files:"),
+            files.len(),
+            gettext("broken files:"),
+            broken
+        );
+        if ids.is_empty() {
+            return 0;
+        }
+        let pw = Arc::new(PixivWebClient::new());
+        let ac = PixivAppClient::with_db(Some(db));
+        let tasks = TaskManager::new_post();
+        let download_multiple_posts = get_helper().download_multiple_posts();
+        for id in ids {
+            tasks
+                .add_task(download_artwork(
+                    ac.clone(),
+                    Arc::clone(&pw),
+                    id,
+                    PixivArtworkContext::default(),
+                ))
+                .await;
+            if !download_multiple_posts {
+                tasks.join().await;
+            }
+        }
+        tasks.join().await;
+        let mut re = 0;
+        for task in tasks.take_finished_tasks() {
+            let result = match task.await {
+                Ok(result) => result,
+                Err(e) => Err(PixivDownloaderError::from(e)),
+            };
+            if let Err(e) = result {
+                log::error!("{} {}", gettext("Failed to download post:"), e);
+                re = 1;
+            }
+        }
+        re
+    }
+}
+
+#[cfg(feature = "db_sqlite")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_verify_with_archive() {
+    use crate::db::PixivDownloaderDbConfig;
+    use crate::download::is_artwork_in_history;
+    use chrono::Utc;
+    use flagset::FlagSet;
+    let p = Path::new("./test");
+    if !p.exists() {
+        let re = std::fs::create_dir("./test");
+        assert!(re.is_ok() || p.exists());
+    }
+    let db_path = Path::new("test/verify.db");
+    if db_path.exists() {
+        remove_file(db_path).unwrap();
+    }
+    let db = open_and_init_database(
+        PixivDownloaderDbConfig::new(&json::object! {
+            "type": "sqlite",
+            "path": "test/verify.db",
+        })
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+    let path = "test/verify_1.txt";
+    std::fs::write(path, "abc").unwrap();
+    let sha256 = file_sha256(path).unwrap();
+    db.add_pixiv_artwork(1, "", "", 0, "", 1, false, &FlagSet::default())
+        .await
+        .unwrap();
+    db.add_pixiv_file(
+        1,
+        0,
+        path,
+        "https://i.pximg.net/1.txt",
+        &Utc::now(),
+        Some(&sha256),
+    )
+    .await
+    .unwrap();
+    assert!(is_artwork_in_history(&db, 1).await.unwrap());
+    let files = db.get_pixiv_files(1).await.unwrap();
+    assert!(check_pixiv_file(&files[0]).is_none());
+    std::fs::write(path, "abd").unwrap();
+    assert!(check_pixiv_file(&files[0]).is_some());
+    remove_broken_file(&db, &files[0]).await;
+    assert!(!Path::new(path).exists());
+    // The artwork must not be skipped in archive mode when downloading again.
+    assert!(!is_artwork_in_history(&db, 1).await.unwrap());
+    assert!(db.get_pixiv_files(1).await.unwrap().is_empty());
+}
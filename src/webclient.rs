@@ -9,6 +9,7 @@ use crate::formdata::FormData;
 use crate::gettext;
 use crate::list::NonTailList;
 use crate::opthelper::get_helper;
+use crate::rate_limiter;
 use json::JsonValue;
 use proc_macros::print_error;
 use wreq::multipart::Form;
@@ -338,11 +339,13 @@ impl WebClient {
         let retry = self.get_retry();
         while retry < 0 || count <= retry {
             let r = self._aget2(url.clone(), headers.clone()).await;
-            if r.is_some() {
+            // The next request waits as long as the server asks.
+            let limited = rate_limiter::is_too_many_requests(&r);
+            if r.is_some() && (!limited || count == retry) {
                 return r;
             }
             count += 1;
-            if retry < 0 || count <= retry {
+            if !limited && (retry < 0 || count <= retry) {
                 let t =
                     self.get_retry_interval().as_ref().unwrap()[(count - 1).try_into().unwrap()];
                 if !t.is_zero() {
@@ -371,7 +374,10 @@ impl WebClient {
             gettext("Failed to generate request:"),
             self._aget(url, headers)
         );
+        let host = r.url().host_str().unwrap_or("").to_owned();
+        rate_limiter::acquire(&host).await;
         let r = print_error!(gettext("Error when request:"), self.client.execute(r).await);
+        rate_limiter::handle_response(&host, &r);
         self.handle_set_cookie(&r);
         log::debug!(target: "webclient", "{}", r.status());
         Some(r)
@@ -423,11 +429,13 @@ impl WebClient {
             let r = self
                 ._apost2(url.clone(), headers.clone(), form.clone())
                 .await;
-            if r.is_some() {
+            // The next request waits as long as the server asks.
+            let limited = rate_limiter::is_too_many_requests(&r);
+            if r.is_some() && (!limited || count == retry) {
                 return r;
             }
             count += 1;
-            if retry < 0 || count <= retry {
+            if !limited && (retry < 0 || count <= retry) {
                 let t =
                     self.get_retry_interval().as_ref().unwrap()[(count - 1).try_into().unwrap()];
                 if !t.is_zero() {
@@ -463,11 +471,13 @@ impl WebClient {
             let r = self
                 ._apost_multipart2(url.clone(), headers.clone(), f)
                 .await;
-            if r.is_some() {
+            // The next request waits as long as the server asks.
+            let limited = rate_limiter::is_too_many_requests(&r);
+            if r.is_some() && (!limited || count == retry) {
                 return r;
             }
             count += 1;
-            if retry < 0 || count <= retry {
+            if !limited && (retry < 0 || count <= retry) {
                 let t =
                     self.get_retry_interval().as_ref().unwrap()[(count - 1).try_into().unwrap()];
                 if !t.is_zero() {
@@ -500,7 +510,10 @@ impl WebClient {
             gettext("Failed to generate request:"),
             self._apost(url, headers, form)
         );
+        let host = r.url().host_str().unwrap_or("").to_owned();
+        rate_limiter::acquire(&host).await;
         let r = print_error!(gettext("Error when request:"), self.client.execute(r).await);
+        rate_limiter::handle_response(&host, &r);
         self.handle_set_cookie(&r);
         log::debug!(target: "webclient","{}", r.status());
         Some(r)
@@ -557,7 +570,10 @@ impl WebClient {
             gettext("Failed to generate request:"),
             self._apost_multipart(url, headers, form)
         );
+        let host = r.url().host_str().unwrap_or("").to_owned();
+        rate_limiter::acquire(&host).await;
         let r = print_error!(gettext("Error when request:"), self.client.execute(r).await);
+        rate_limiter::handle_response(&host, &r);
         self.handle_set_cookie(&r);
         log::debug!(target: "webclient","{}", r.status());
         Some(r)
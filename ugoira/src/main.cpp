@@ -41,10 +41,15 @@ extern "C" {
 
 void print_help() {
     printf("%s", "Usage: ugoira [options] INPUT DEST JSON\n\
-Convert pixiv GIF zip to mp4 file.\n\
+Convert pixiv GIF zip to mp4 file or animated image.\n\
 \n\
 Options:\n\
     -h, --help              Print this help message.\n\
+    -F FORMAT, --format FORMAT\n\
+                            Set output format. Available values: mp4, gif,\n\
+                            webp, apng. Default: mp4.\n\
+    --dither DITHER         Set dither algorithm of paletteuse filter. Only\n\
+                            used by gif. Default: sierra2_4a.\n\
     -M FPS, --max-fps FPS   Set maximum FPS. Default: 60fps.\n\
     -m KEY=VALUE --meta KEY=VALUE\n\
                             Set metadata.\n\
@@ -58,6 +63,24 @@ Options:\n\
 }
 
 #define CRF 128
+#define DITHER 129
+#define FORMAT_MP4 0
+
+int parse_format(const char* s, int& format) {
+    std::string f(s);
+    if (f == "mp4") {
+        format = FORMAT_MP4;
+    } else if (f == "gif") {
+        format = UGOIRA_FORMAT_GIF;
+    } else if (f == "webp") {
+        format = UGOIRA_FORMAT_WEBP;
+    } else if (f == "apng" || f == "png") {
+        format = UGOIRA_FORMAT_APNG;
+    } else {
+        return 0;
+    }
+    return 1;
+}
 
 int main(int argc, char* argv[]) {
 #if _WIN32
@@ -87,15 +110,18 @@ int main(int argc, char* argv[]) {
         { "preset", 1, nullptr, 'p' },
         { "level", 1, nullptr, 'l' },
         { "profile", 1, nullptr, 'P' },
+        { "format", 1, nullptr, 'F' },
+        { "dither", 1, nullptr, DITHER },
         nullptr,
     };
     int c;
-    std::string shortopts = "-hM:m:fp:l:P:";
+    std::string shortopts = "-hM:m:fp:l:P:F:";
     std::string input;
     std::string dest;
     std::string json;
     bool printh = false;
     float max_fps = 60;
+    int format = FORMAT_MP4;
     struct AVDictionary* metadata = nullptr, * options = nullptr;
     while ((c = getopt_long(argc, argv, shortopts.c_str(), opts, nullptr)) != -1) {
         switch (c) {
@@ -177,6 +203,28 @@ int main(int argc, char* argv[]) {
                 printf("Failed to set profile: %s\n", optarg);
 #if _WIN32
                 if (have_wargv) wchar_util::freeArgv(wargv, wargc);
+#endif
+                av_dict_free(&metadata);
+                av_dict_free(&options);
+                return 1;
+            }
+            break;
+        case 'F':
+            if (!parse_format(optarg, format)) {
+                printf("Invalid format: %s\n", optarg);
+#if _WIN32
+                if (have_wargv) wchar_util::freeArgv(wargv, wargc);
+#endif
+                av_dict_free(&metadata);
+                av_dict_free(&options);
+                return UGOIRA_INVALID_FORMAT;
+            }
+            break;
+        case DITHER:
+            if (av_dict_set(&options, "dither", optarg, 0) < 0) {
+                printf("Failed to set dither: %s\n", optarg);
+#if _WIN32
+                if (have_wargv) wchar_util::freeArgv(wargv, wargc);
 #endif
                 av_dict_free(&metadata);
                 av_dict_free(&options);
@@ -288,7 +336,8 @@ int main(int argc, char* argv[]) {
             top = tail;
         }
     }
-    auto e = convert_ugoira_to_mp4(input.c_str(), dest.c_str(), top, max_fps, options, metadata);
+    auto e = format == FORMAT_MP4 ? convert_ugoira_to_mp4(input.c_str(), dest.c_str(), top, max_fps, options, metadata)
+        : convert_ugoira_to_animation(input.c_str(), dest.c_str(), top, format, options, metadata);
     free_ugoira_frames(top);
     av_dict_free(&metadata);
     av_dict_free(&options);
@@ -1,15 +1,21 @@
 #include "ugoira_config.h"
 #include "../ugoira.h"
 
+#include <inttypes.h>
 #include <malloc.h>
+#include <stdio.h>
 #include <string.h>
 #include "cfileop.h"
 #include "cmath.h"
 #include "cstr_util.h"
 #include "libavutil/avutil.h"
 #include "libavutil/opt.h"
+#include "libavutil/pixdesc.h"
 #include "libavformat/avformat.h"
 #include "libavcodec/avcodec.h"
+#include "libavfilter/avfilter.h"
+#include "libavfilter/buffersink.h"
+#include "libavfilter/buffersrc.h"
 #include "libswscale/swscale.h"
 #include "zip.h"
 
@@ -472,6 +478,513 @@ end:
     return zip_err ? RZERR(zip_err) : RERR(err);
 }
 
+typedef struct UgoiraReader {
+    zip_t* zip;
+    zip_file_t* zf;
+    AVIOContext* iioc;
+    AVFormatContext* ic;
+    AVCodecContext* eic;
+    AVStream* is;
+} UgoiraReader;
+
+void ugoira_reader_close_file(UgoiraReader* r) {
+    if (r->eic) avcodec_free_context(&r->eic);
+    if (r->ic) avformat_close_input(&r->ic);
+    if (r->iioc) {
+        av_freep(&r->iioc->buffer);
+        avio_context_free(&r->iioc);
+    }
+    if (r->zf) {
+        zip_fclose(r->zf);
+        r->zf = NULL;
+    }
+    r->is = NULL;
+}
+
+int ugoira_reader_open_file(UgoiraReader* r, const char* file, zip_error_t* ziperr) {
+    int err = 0;
+    unsigned char* buff = NULL;
+    const AVCodec* input_codec = NULL;
+    ugoira_reader_close_file(r);
+    if (!(r->zf = zip_fopen(r->zip, file, 0))) {
+        copy_zip_error(ziperr, zip_get_error(r->zip));
+        return UGOIRA_ZIP;
+    }
+    if (!(buff = av_malloc(4096))) {
+        return UGOIRA_OOM;
+    }
+    if (!(r->iioc = avio_alloc_context(buff, 4096, 0, (void*)r->zf, zip_file_read_packet, NULL, NULL))) {
+        av_free(buff);
+        return UGOIRA_OOM;
+    }
+    if (!(r->ic = avformat_alloc_context())) {
+        return UGOIRA_OOM;
+    }
+    r->ic->pb = r->iioc;
+    if ((err = avformat_open_input(&r->ic, NULL, NULL, NULL)) < 0) {
+        return err;
+    }
+    if ((err = avformat_find_stream_info(r->ic, NULL)) < 0) {
+        return err;
+    }
+    for (unsigned int si = 0; si < r->ic->nb_streams; si++) {
+        if (r->ic->streams[si]->codecpar->codec_type == AVMEDIA_TYPE_VIDEO) {
+            r->is = r->ic->streams[si];
+            break;
+        }
+    }
+    if (!r->is) {
+        return UGOIRA_NO_VIDEO_STREAM;
+    }
+    if (!(input_codec = avcodec_find_decoder(r->is->codecpar->codec_id))) {
+        return UGOIRA_NO_AVAILABLE_DECODER;
+    }
+    if (!(r->eic = avcodec_alloc_context3(input_codec))) {
+        return UGOIRA_OOM;
+    }
+    if ((err = avcodec_parameters_to_context(r->eic, r->is->codecpar)) < 0) {
+        return err;
+    }
+    if ((err = avcodec_open2(r->eic, input_codec, NULL)) < 0) {
+        return err;
+    }
+    return UGOIRA_OK;
+}
+
+int ugoira_reader_read_frame(UgoiraReader* r, AVFrame* fr) {
+    int err = 0;
+    AVPacket* pkt = av_packet_alloc();
+    if (!pkt) {
+        return UGOIRA_OOM;
+    }
+    while (1) {
+        if ((err = av_read_frame(r->ic, pkt)) < 0) {
+            if (err != AVERROR_EOF) break;
+            // Flush the decoder.
+            if ((err = avcodec_send_packet(r->eic, NULL)) < 0 && err != AVERROR_EOF) break;
+            err = avcodec_receive_frame(r->eic, fr);
+            break;
+        }
+        if (pkt->stream_index != r->is->index) {
+            av_packet_unref(pkt);
+            continue;
+        }
+        err = avcodec_send_packet(r->eic, pkt);
+        av_packet_unref(pkt);
+        if (err < 0) break;
+        err = avcodec_receive_frame(r->eic, fr);
+        if (err == AVERROR(EAGAIN)) continue;
+        break;
+    }
+    av_packet_free(&pkt);
+    return err < 0 ? err : UGOIRA_OK;
+}
+
+const AVCodec* ugoira_find_animation_encoder(int format) {
+    switch (format) {
+    case UGOIRA_FORMAT_GIF:
+        return avcodec_find_encoder(AV_CODEC_ID_GIF);
+    case UGOIRA_FORMAT_WEBP:
+        // libwebp_anim puts all frames in one packet, so the muxer can not get the delay of every frame.
+        return avcodec_find_encoder_by_name("libwebp");
+    case UGOIRA_FORMAT_APNG:
+        return avcodec_find_encoder(AV_CODEC_ID_APNG);
+    default:
+        return NULL;
+    }
+}
+
+const char* ugoira_get_animation_muxer(int format) {
+    switch (format) {
+    case UGOIRA_FORMAT_GIF:
+        return "gif";
+    case UGOIRA_FORMAT_WEBP:
+        return "webp";
+    case UGOIRA_FORMAT_APNG:
+        return "apng";
+    default:
+        return NULL;
+    }
+}
+
+int ugoira_set_animation_muxer_opts(AVDictionary** d, int format, int64_t last_delay) {
+    char buf[64];
+    int err = 0;
+    switch (format) {
+    case UGOIRA_FORMAT_GIF:
+        // GIF stores delays in centiseconds.
+        snprintf(buf, sizeof(buf), "%" PRId64, (last_delay + 5) / 10);
+        if ((err = av_dict_set(d, "loop", "0", 0)) < 0) return err;
+        return av_dict_set(d, "final_delay", buf, 0);
+    case UGOIRA_FORMAT_WEBP:
+        return av_dict_set(d, "loop", "0", 0);
+    case UGOIRA_FORMAT_APNG:
+        snprintf(buf, sizeof(buf), "%" PRId64 "/1000", last_delay);
+        if ((err = av_dict_set(d, "plays", "0", 0)) < 0) return err;
+        return av_dict_set(d, "final_delay", buf, 0);
+    default:
+        return 0;
+    }
+}
+
+enum AVPixelFormat ugoira_get_animation_pixfmt(const AVCodec* codec, enum AVPixelFormat src) {
+    const AVPixFmtDescriptor* desc = av_pix_fmt_desc_get(src);
+    int has_alpha = desc && (desc->flags & AV_PIX_FMT_FLAG_ALPHA);
+    enum AVPixelFormat re = AV_PIX_FMT_NONE;
+    // swscale can not output palette images.
+    if (src != AV_PIX_FMT_PAL8 && ugoira_is_supported_pixfmt(src, codec->pix_fmts)) {
+        return src;
+    }
+    re = avcodec_find_best_pix_fmt_of_list(codec->pix_fmts, src == AV_PIX_FMT_PAL8 ? AV_PIX_FMT_RGBA : src, has_alpha, NULL);
+    if (re == AV_PIX_FMT_PAL8 || re == AV_PIX_FMT_NONE) {
+        re = has_alpha ? AV_PIX_FMT_RGBA : AV_PIX_FMT_RGB24;
+    }
+    return re;
+}
+
+/// Find the duration of the frame which starts at pts. starts contains count + 1 items.
+int64_t ugoira_get_frame_duration(const int64_t* starts, size_t count, int64_t pts) {
+    size_t l = 0, r = count;
+    while (l < r) {
+        size_t m = (l + r) / 2;
+        if (starts[m] == pts) return starts[m + 1] - starts[m];
+        if (starts[m] < pts) {
+            l = m + 1;
+        } else {
+            r = m;
+        }
+    }
+    return 0;
+}
+
+int ugoira_init_palette_filter(AVFilterGraph** graph, AVFilterContext** src, AVFilterContext** sink, const AVCodecContext* eoc, const AVDictionary* opts) {
+    char args[256];
+    char desc[256];
+    int err = UGOIRA_OK;
+    AVFilterInOut* inputs = NULL, * outputs = NULL;
+    AVDictionaryEntry* dither = NULL;
+    AVRational sar = eoc->sample_aspect_ratio;
+    const enum AVPixelFormat pix_fmts[] = { AV_PIX_FMT_PAL8, AV_PIX_FMT_NONE };
+    if (!avfilter_get_by_name("palettegen") || !avfilter_get_by_name("paletteuse")) {
+        return UGOIRA_UNABLE_FILTER;
+    }
+    if (!sar.den) {
+        sar = (AVRational){ 0, 1 };
+    }
+    if (!(*graph = avfilter_graph_alloc())) {
+        return UGOIRA_OOM;
+    }
+    snprintf(args, sizeof(args), "video_size=%dx%d:pix_fmt=%d:time_base=%d/%d:pixel_aspect=%d/%d", eoc->width, eoc->height, AV_PIX_FMT_BGRA, eoc->time_base.num, eoc->time_base.den, sar.num, sar.den);
+    if ((err = avfilter_graph_create_filter(src, avfilter_get_by_name("buffer"), "in", args, NULL, *graph)) < 0) {
+        return err;
+    }
+    if ((err = avfilter_graph_create_filter(sink, avfilter_get_by_name("buffersink"), "out", NULL, NULL, *graph)) < 0) {
+        return err;
+    }
+    if ((err = av_opt_set_int_list(*sink, "pix_fmts", pix_fmts, AV_PIX_FMT_NONE, AV_OPT_SEARCH_CHILDREN)) < 0) {
+        return err;
+    }
+    if (opts) {
+        dither = av_dict_get(opts, "dither", NULL, 0);
+    }
+    // Generate a palette for every frame, GIF supports local color tables.
+    snprintf(desc, sizeof(desc), "split[a][b];[a]palettegen=stats_mode=single[p];[b][p]paletteuse=new=1:dither=%s", dither ? dither->value : "sierra2_4a");
+    if (!(outputs = avfilter_inout_alloc()) || !(inputs = avfilter_inout_alloc())) {
+        err = UGOIRA_OOM;
+        goto end;
+    }
+    outputs->name = av_strdup("in");
+    outputs->filter_ctx = *src;
+    outputs->pad_idx = 0;
+    outputs->next = NULL;
+    inputs->name = av_strdup("out");
+    inputs->filter_ctx = *sink;
+    inputs->pad_idx = 0;
+    inputs->next = NULL;
+    if (!outputs->name || !inputs->name) {
+        err = UGOIRA_OOM;
+        goto end;
+    }
+    if ((err = avfilter_graph_parse_ptr(*graph, desc, &inputs, &outputs, NULL)) < 0) {
+        goto end;
+    }
+    if ((err = avfilter_graph_config(*graph, NULL)) < 0) {
+        goto end;
+    }
+    err = UGOIRA_OK;
+end:
+    avfilter_inout_free(&inputs);
+    avfilter_inout_free(&outputs);
+    return err;
+}
+
+int ugoira_encode_animation_frame(AVFrame* fr, AVFormatContext* oc, AVCodecContext* eoc, AVStream* os, AVPacket* pkt, const int64_t* starts, size_t count) {
+    int64_t duration = 0;
+    int err = avcodec_send_frame(eoc, fr);
+    if (err < 0 && err != AVERROR_EOF) {
+        return err;
+    }
+    while (1) {
+        err = avcodec_receive_packet(eoc, pkt);
+        if (err == AVERROR(EAGAIN) || err == AVERROR_EOF) {
+            return UGOIRA_OK;
+        }
+        if (err < 0) {
+            return err;
+        }
+        // Encoders may not set the duration, which is needed by the last frame.
+        if (pkt->pts != AV_NOPTS_VALUE && (duration = ugoira_get_frame_duration(starts, count, pkt->pts)) > 0) {
+            pkt->duration = duration;
+        }
+        av_packet_rescale_ts(pkt, eoc->time_base, os->time_base);
+        pkt->stream_index = os->index;
+        if ((err = av_interleaved_write_frame(oc, pkt)) < 0) {
+            return err;
+        }
+    }
+}
+
+int ugoira_encode_filtered_frames(AVFilterContext* sink, AVFrame* ffr, AVFormatContext* oc, AVCodecContext* eoc, AVStream* os, AVPacket* pkt, const int64_t* starts, size_t count) {
+    int err = 0;
+    AVRational tb = av_buffersink_get_time_base(sink);
+    while (1) {
+        err = av_buffersink_get_frame(sink, ffr);
+        if (err == AVERROR(EAGAIN) || err == AVERROR_EOF) {
+            return UGOIRA_OK;
+        }
+        if (err < 0) {
+            return err;
+        }
+        ffr->pts = av_rescale_q(ffr->pts, tb, eoc->time_base);
+        err = ugoira_encode_animation_frame(ffr, oc, eoc, os, pkt, starts, count);
+        av_frame_unref(ffr);
+        if (err != UGOIRA_OK) {
+            return err;
+        }
+    }
+}
+
+UgoiraError convert_ugoira_to_animation(const char* src, const char* dest, const UgoiraFrame* frames, int format, const AVDictionary* opts, const AVDictionary* metadata) {
+    if (!src || !dest || !frames) return RERR(UGOIRA_NULL_POINTER);
+    const char* muxer = ugoira_get_animation_muxer(format);
+    const AVCodec* output_codec = NULL;
+    if (!muxer) {
+        return RERR(UGOIRA_INVALID_FORMAT);
+    }
+    if (!check_ugoira_frames(frames)) {
+        return RERR(UGOIRA_INVALID_FRAMES);
+    }
+    if (!(output_codec = ugoira_find_animation_encoder(format))) {
+        return RERR(UGOIRA_NO_AVAILABLE_ENCODER);
+    }
+    if (fileop_exists(dest)) {
+        if (!fileop_remove(dest)) {
+            return RERR(UGOIRA_REMOVE_OUTPUT_FILE_FAILED);
+        }
+    }
+    int err = UGOIRA_OK;
+    int zip_err = 0;
+    zip_error_t ziperr;
+    UgoiraReader reader;
+    AVFormatContext* oc = NULL;
+    AVCodecContext* eoc = NULL;
+    AVStream* os = NULL;
+    AVFrame* ifr = NULL, * sfr = NULL, * ffr = NULL;
+    AVPacket* pkt = NULL;
+    AVFilterGraph* graph = NULL;
+    AVFilterContext* buffersrc = NULL, * buffersink = NULL;
+    AVDictionary* enc_opts = NULL, * mux_opts = NULL;
+    struct SwsContext* sws_ctx = NULL;
+    enum AVPixelFormat sws_pixfmt = AV_PIX_FMT_NONE;
+    int pre_pixfmt = AV_PIX_FMT_NONE, pre_width = -1, pre_height = -1;
+    const UgoiraFrame* cur_frame = NULL;
+    int64_t* starts = NULL;
+    size_t count = 0, i = 0;
+    double total = 0;
+    const static AVRational tb = { 1, 1000 };
+    memset(&reader, 0, sizeof(UgoiraReader));
+    zip_error_init(&ziperr);
+    // The start time of every frame in milliseconds.
+    for (cur_frame = frames; cur_frame; cur_frame = cur_frame->next) {
+        count++;
+    }
+    if (!(starts = malloc(sizeof(int64_t) * (count + 1)))) {
+        err = UGOIRA_OOM;
+        goto end;
+    }
+    starts[0] = 0;
+    for (cur_frame = frames, i = 0; cur_frame; cur_frame = cur_frame->next, i++) {
+        total += cur_frame->delay;
+        if (format == UGOIRA_FORMAT_GIF) {
+            // GIF stores delays in centiseconds. Round the start time instead of every delay, so the errors do not add up.
+            starts[i + 1] = FFMAX((int64_t)((total + 5) / 10) * 10, starts[i] + 10);
+        } else {
+            starts[i + 1] = FFMAX((int64_t)(total + 0.5), starts[i] + 1);
+        }
+    }
+    if (!(ifr = av_frame_alloc()) || !(sfr = av_frame_alloc()) || !(ffr = av_frame_alloc())) {
+        err = UGOIRA_OOM;
+        goto end;
+    }
+    if (!(pkt = av_packet_alloc())) {
+        err = UGOIRA_OOM;
+        goto end;
+    }
+    if (!(reader.zip = zip_open(src, ZIP_RDONLY, &zip_err))) {
+        goto end;
+    }
+    for (cur_frame = frames, i = 0; cur_frame; cur_frame = cur_frame->next, i++) {
+        if ((err = ugoira_reader_open_file(&reader, cur_frame->file, &ziperr)) != UGOIRA_OK) {
+            goto end;
+        }
+        if ((err = ugoira_reader_read_frame(&reader, ifr)) != UGOIRA_OK) {
+            goto end;
+        }
+        if (i == 0) {
+            if ((err = avformat_alloc_output_context2(&oc, NULL, muxer, dest)) < 0) {
+                goto end;
+            }
+            if (metadata) {
+                if ((err = av_dict_copy(&oc->metadata, metadata, 0)) < 0) {
+                    goto end;
+                }
+            }
+            if (!(eoc = avcodec_alloc_context3(output_codec))) {
+                err = UGOIRA_OOM;
+                goto end;
+            }
+            eoc->width = ifr->width;
+            eoc->height = ifr->height;
+            eoc->sample_aspect_ratio = reader.eic->sample_aspect_ratio;
+            eoc->time_base = tb;
+            if (format == UGOIRA_FORMAT_GIF) {
+                // paletteuse only accepts BGRA.
+                sws_pixfmt = AV_PIX_FMT_BGRA;
+                eoc->pix_fmt = AV_PIX_FMT_PAL8;
+                if ((err = ugoira_init_palette_filter(&graph, &buffersrc, &buffersink, eoc, opts)) != UGOIRA_OK) {
+                    goto end;
+                }
+            } else {
+                eoc->pix_fmt = ugoira_get_animation_pixfmt(output_codec, ifr->format);
+                sws_pixfmt = eoc->pix_fmt;
+            }
+            if (oc->oformat->flags & AVFMT_GLOBALHEADER) {
+                eoc->flags |= AV_CODEC_FLAG_GLOBAL_HEADER;
+            }
+            if (opts) {
+                if ((err = av_dict_copy(&enc_opts, opts, 0)) < 0) {
+                    goto end;
+                }
+                av_dict_set(&enc_opts, "dither", NULL, 0);
+            }
+            if ((err = avcodec_open2(eoc, output_codec, &enc_opts)) < 0) {
+                goto end;
+            }
+            if (!(os = avformat_new_stream(oc, NULL))) {
+                err = UGOIRA_OOM;
+                goto end;
+            }
+            os->time_base = tb;
+            if ((err = avcodec_parameters_from_context(os->codecpar, eoc)) < 0) {
+                goto end;
+            }
+            if (!(oc->oformat->flags & AVFMT_NOFILE)) {
+                if (avio_open(&oc->pb, dest, AVIO_FLAG_WRITE) < 0) {
+                    err = UGOIRA_OPEN_FILE;
+                    goto end;
+                }
+            }
+            if ((err = ugoira_set_animation_muxer_opts(&mux_opts, format, starts[count] - starts[count - 1])) < 0) {
+                goto end;
+            }
+            if ((err = avformat_write_header(oc, &mux_opts)) < 0) {
+                goto end;
+            }
+            sfr->width = eoc->width;
+            sfr->height = eoc->height;
+            sfr->format = sws_pixfmt;
+            if ((err = av_frame_get_buffer(sfr, 0)) < 0) {
+                goto end;
+            }
+            err = 0;
+        }
+        if (!sws_ctx || ifr->format != pre_pixfmt || ifr->width != pre_width || ifr->height != pre_height) {
+            if (sws_ctx) {
+                sws_freeContext(sws_ctx);
+                sws_ctx = NULL;
+            }
+            if (!(sws_ctx = sws_getContext(ifr->width, ifr->height, ifr->format, eoc->width, eoc->height, sws_pixfmt, SWS_BILINEAR, NULL, NULL, NULL))) {
+                err = UGOIRA_UNABLE_SCALE;
+                goto end;
+            }
+            pre_pixfmt = ifr->format;
+            pre_width = ifr->width;
+            pre_height = ifr->height;
+        }
+        if ((err = av_frame_make_writable(sfr)) < 0) {
+            goto end;
+        }
+        if ((err = sws_scale(sws_ctx, (const uint8_t* const*)ifr->data, ifr->linesize, 0, ifr->height, sfr->data, sfr->linesize)) < 0) {
+            goto end;
+        }
+        sfr->pts = starts[i];
+        if (graph) {
+            if ((err = av_buffersrc_add_frame_flags(buffersrc, sfr, AV_BUFFERSRC_FLAG_KEEP_REF)) < 0) {
+                goto end;
+            }
+            if ((err = ugoira_encode_filtered_frames(buffersink, ffr, oc, eoc, os, pkt, starts, count)) != UGOIRA_OK) {
+                goto end;
+            }
+        } else {
+            if ((err = ugoira_encode_animation_frame(sfr, oc, eoc, os, pkt, starts, count)) != UGOIRA_OK) {
+                goto end;
+            }
+        }
+        err = 0;
+    }
+    if (graph) {
+        if ((err = av_buffersrc_add_frame_flags(buffersrc, NULL, 0)) < 0) {
+            goto end;
+        }
+        if ((err = ugoira_encode_filtered_frames(buffersink, ffr, oc, eoc, os, pkt, starts, count)) != UGOIRA_OK) {
+            goto end;
+        }
+    }
+    if ((err = ugoira_encode_animation_frame(NULL, oc, eoc, os, pkt, starts, count)) != UGOIRA_OK) {
+        goto end;
+    }
+    if ((err = av_write_trailer(oc)) < 0) {
+        goto end;
+    }
+    err = 0;
+end:
+    ugoira_reader_close_file(&reader);
+    if (reader.zip) zip_discard(reader.zip);
+    if (graph) avfilter_graph_free(&graph);
+    if (sws_ctx) sws_freeContext(sws_ctx);
+    if (eoc) avcodec_free_context(&eoc);
+    if (oc) {
+        if (!(oc->oformat->flags & AVFMT_NOFILE)) avio_closep(&oc->pb);
+        avformat_free_context(oc);
+    }
+    if (ifr) av_frame_free(&ifr);
+    if (sfr) av_frame_free(&sfr);
+    if (ffr) av_frame_free(&ffr);
+    if (pkt) av_packet_free(&pkt);
+    av_dict_free(&enc_opts);
+    av_dict_free(&mux_opts);
+    if (starts) free(starts);
+    if (ziperr.sys_err || ziperr.zip_err) {
+        zip_error_t* tmp = malloc(sizeof(zip_error_t));
+        if (!tmp) {
+            return RERR(UGOIRA_OOM);
+        }
+        memcpy(tmp, &ziperr, sizeof(zip_error_t));
+        return (UgoiraError){ UGOIRA_ZIP, 0, tmp };
+    }
+    return zip_err ? RZERR(zip_err) : RERR(err);
+}
+
 char* ugoira_get_zip_err_msg(int code) {
     zip_error_t err;
     zip_error_init_with_code(&err, code);
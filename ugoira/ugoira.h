@@ -14,6 +14,14 @@
 #define UGOIRA_OPEN_FILE 11
 #define UGOIRA_UNABLE_SCALE 12
 #define UGOIRA_JSON_ERROR 13
+#define UGOIRA_INVALID_FORMAT 14
+#define UGOIRA_UNABLE_FILTER 15
+/// Animated GIF with per-frame optimised palettes
+#define UGOIRA_FORMAT_GIF 1
+/// Animated WebP
+#define UGOIRA_FORMAT_WEBP 2
+/// Animated PNG
+#define UGOIRA_FORMAT_APNG 3
 typedef struct UgoiraFrame {
     char* file;
     float delay;
@@ -40,6 +48,9 @@ UgoiraFrame* new_ugoira_frame(const char* file, float delay, UgoiraFrame* prev);
 void free_ugoira_frame(UgoiraFrame* f);
 void free_ugoira_frames(UgoiraFrame* frames);
 UgoiraError convert_ugoira_to_mp4(const char* src, const char* dest, const UgoiraFrame* frames, float max_fps, const AVDictionary* opts, const AVDictionary* metadata);
+/// Convert ugoira to animated image. Every frame is encoded once with its own delay.
+/// format: one of UGOIRA_FORMAT_*. opts: passed to the encoder, `dither` is used by GIF's palette filter.
+UgoiraError convert_ugoira_to_animation(const char* src, const char* dest, const UgoiraFrame* frames, int format, const AVDictionary* opts, const AVDictionary* metadata);
 char* ugoira_get_zip_err_msg(int code);
 void ugoira_mfree(void* data);
 zip_error_t* new_ugoira_error();